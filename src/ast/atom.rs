@@ -1,4 +1,5 @@
-use crate::parser::Rule;
+use crate::parser::{Parser, Rule};
+use pest::Parser as _;
 use std::cell::RefCell;
 use std::fmt::{self, Display, Formatter};
 use std::rc::{Rc, Weak};
@@ -51,18 +52,19 @@ impl Atom {
         };
         Self::from(string)
     }
+
+    /// Whether this atom can be written without quotes, as it would parse as a bare atom.
+    fn is_bare(&self) -> bool {
+        Parser::parse(Rule::bare_atom, &self.0)
+            .ok()
+            .and_then(|mut pairs| pairs.next())
+            .is_some_and(|pair| pair.as_str().len() == self.0.len())
+    }
 }
 
 impl Display for Atom {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        // TODO: properly quote atoms as necessary
-        //       somehow detect that by un-parsing the atom string?
-        if self
-            .0
-            .chars()
-            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
-            && self.0.chars().next().unwrap().is_ascii_alphabetic()
-        {
+        if self.is_bare() {
             self.0.fmt(f)
         } else if !self.0.contains('\'') {
             write!(f, "'{}'", self.0)
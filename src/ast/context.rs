@@ -1,10 +1,12 @@
 use super::*;
 use crate::program::*;
-use crate::{Lumber, Question};
+use crate::{Diagnostic, Lumber, SourceSpan};
 use pest::Span;
 use ramp::Int;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 #[derive(Default)]
 pub struct Context<'p> {
@@ -14,6 +16,23 @@ pub struct Context<'p> {
     pub(crate) current_environment: HashMap<String, Identifier>,
     pub(crate) modules: HashMap<Scope, ModuleHeader>,
     pub(crate) errors: HashMap<Scope, Vec<crate::Error>>,
+    /// The path (if known) and source code of each module, used to locate errors.
+    pub(crate) sources: HashMap<Scope, (Option<PathBuf>, Rc<str>)>,
+    /// The part of the current module's source code being compiled, if known.
+    pub(crate) current_span: Option<SourceSpan>,
+    /// Warnings found by lints, along with the module they were found in.
+    pub(crate) warnings: Vec<(Scope, Lint, Diagnostic)>,
+    /// Declarations made by directives, and where they were made, if known.
+    pub(crate) declarations: HashMap<Declaration, Option<SourceSpan>>,
+    /// Declarations which have been used while resolving references.
+    pub(crate) used: RefCell<HashSet<Declaration>>,
+    /// Predicates which are referenced by tests, or by predicates other than themselves.
+    pub(crate) referenced: HashSet<Handle>,
+    /// Operators which are used in the bodies of predicates.
+    pub(crate) used_operators: HashSet<Operator>,
+    /// Whether any syntax errors have been found, in which case compiling stops once all of
+    /// the modules have been parsed.
+    pub(crate) syntax_errors: bool,
 }
 
 impl<'p> Context<'p> {
@@ -34,29 +53,21 @@ impl<'p> Context<'p> {
         source: &str,
         natives: HashMap<Handle, NativeFunction<'p>>,
         run_tests: bool,
+        strict: bool,
+        check_modes: bool,
     ) -> crate::Result<Lumber<'p>> {
-        self.root_path = root_path;
-        if self.root_path.exists() && std::fs::metadata(&self.root_path)?.is_file() {
-            self.root_path.pop();
-        }
-        self.modules
-            .insert(Scope::default(), ModuleHeader::new(Scope::default()));
-
-        let mut root_module = Module::new(source, &mut self)?;
+        let mut root_module = self.parse(root_path, source)?;
         let native_handles: Vec<_> = natives.keys().collect();
-        self.validate_headers(native_handles.as_slice());
-        if !self.errors.is_empty() {
-            return Err(crate::Error::multiple_by_module(self.errors));
-        }
-        root_module.resolve_scopes(&mut self);
-        if !self.errors.is_empty() {
-            return Err(crate::Error::multiple_by_module(self.errors));
-        }
-        let tests = if run_tests {
-            root_module.take_tests()
-        } else {
-            vec![]
-        };
+        self.check(&mut root_module, native_handles.as_slice())?;
+        let warnings = self.take_warnings();
+        let mut tests = root_module.take_tests();
+        tests.sort_by_key(|test| {
+            (
+                test.module.to_string(),
+                test.location.line,
+                test.location.column,
+            )
+        });
         let mut database: Database =
             Database::new(root_module.into_definitions(), self.public_operators());
         for (_, header) in self
@@ -66,27 +77,63 @@ impl<'p> Context<'p> {
         {
             database.apply_header(header, &natives);
         }
-        let database = self
+        let mut database = self
             .libraries
             .into_iter()
             .fold(database, |database, (_, library)| database.merge(library));
-        let failed_tests: Vec<_> = tests
-            .into_iter()
-            .filter_map(|test| {
-                let question = Question::new(test);
-                let failed = database.unify_test(&question).next().is_none();
-                if failed {
-                    Some(question)
-                } else {
-                    None
-                }
-            })
-            .collect();
-        if failed_tests.is_empty() {
-            Ok(Lumber::build(self.modules, database))
-        } else {
-            Err(crate::Error::test(failed_tests))
+        database.set_strict(strict);
+        database.set_check_modes(check_modes);
+        let lumber = Lumber::build(self.modules, database, warnings, tests);
+        if run_tests {
+            let report = lumber.tests().run();
+            if !report.is_success() {
+                return Err(crate::Error::test(&report));
+            }
         }
+        Ok(lumber)
+    }
+
+    /// Parses the root module of a program, along with all of its submodules.
+    pub(crate) fn parse(&mut self, root_path: PathBuf, source: &str) -> crate::Result<Module> {
+        self.root_path = root_path;
+        let mut root_file = None;
+        if self.root_path.exists() && std::fs::metadata(&self.root_path)?.is_file() {
+            root_file = Some(self.root_path.clone());
+            self.root_path.pop();
+        }
+        self.modules
+            .insert(Scope::default(), ModuleHeader::new(Scope::default()));
+        self.add_source(root_file, source);
+
+        let root_module = Module::new(source, self)?;
+        if self.syntax_errors {
+            return Err(self.take_errors());
+        }
+        Ok(root_module)
+    }
+
+    /// Checks the headers of the parsed modules, resolves the references made in the program,
+    /// and lints it. Warnings are left to be taken once the program is built.
+    pub(crate) fn check(
+        &mut self,
+        root_module: &mut Module,
+        natives: &[&Handle],
+    ) -> crate::Result<()> {
+        self.validate_headers(natives);
+        if !self.errors.is_empty() {
+            return Err(self.take_errors());
+        }
+        root_module.resolve_scopes(self);
+        if !self.errors.is_empty() {
+            return Err(self.take_errors());
+        }
+        root_module.lint(self);
+        self.lint_declarations();
+        Ok(())
+    }
+
+    fn take_errors(&mut self) -> crate::Error {
+        crate::Error::multiple_by_module(std::mem::take(&mut self.errors))
     }
 
     fn enter_module(&mut self, module: Atom) {
@@ -132,6 +179,7 @@ impl<'p> Context<'p> {
             module_path = module_path.with_file_name(format!("{}/mod.lumber", module.as_ref()));
         }
         let source = std::fs::read_to_string(&module_path)?;
+        self.add_source(Some(module_path), &source);
         let module = Module::new(&source, self)?;
         self.leave_module();
         Ok(Some(module))
@@ -156,6 +204,7 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn declare_mutable(&mut self, handle: Handle) {
+        self.declare(Declaration::Mutable(handle.clone()));
         let handle = self.current_module_mut().insert_mutable(handle);
         if let Some(handle) = handle {
             self.error_duplicate_mutable(handle);
@@ -163,6 +212,7 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn declare_alias(&mut self, alias: Handle, source: Handle) {
+        self.declare(Declaration::Alias(alias.clone()));
         let alias = self.current_module_mut().insert_alias(alias, source);
         if let Some((alias, source)) = alias {
             self.error_duplicate_import(alias, source);
@@ -170,6 +220,7 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn declare_incomplete(&mut self, handle: Handle) {
+        self.declare(Declaration::Incomplete(handle.clone()));
         let (export, incomplete) = self.current_module_mut().insert_incomplete(handle);
         if let Some(incomplete) = incomplete {
             self.error_duplicate_incomplete(incomplete);
@@ -179,6 +230,10 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn import_glob(&mut self, module: Scope) {
+        self.declare(Declaration::Glob(
+            self.current_scope.clone(),
+            module.clone(),
+        ));
         let module = self.current_module_mut().insert_glob(module);
         if let Some(module) = module {
             self.error_duplicate_glob(module);
@@ -193,6 +248,11 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn declare_operator_alias(&mut self, operator: Atom, scope: Scope) {
+        self.declare(Declaration::OperatorAlias(
+            self.current_scope.clone(),
+            operator.clone(),
+            scope.clone(),
+        ));
         let scope = self
             .current_module_mut()
             .insert_operator_alias(operator.clone(), scope);
@@ -202,6 +262,10 @@ impl<'p> Context<'p> {
     }
 
     pub(crate) fn declare_operator(&mut self, operator: Operator) {
+        self.declare(Declaration::Operator(
+            self.current_scope.clone(),
+            operator.key(),
+        ));
         let operator = self.current_module_mut().insert_operator(operator);
         if let Some(operator) = operator {
             self.error_duplicate_operator(operator);
@@ -212,6 +276,29 @@ impl<'p> Context<'p> {
         self.current_module_mut().insert(predicate);
     }
 
+    pub(crate) fn document_module(&mut self, module: Atom, doc: &str) {
+        let scope = self.current_scope.join(module);
+        if let Some(module) = self.modules.get_mut(&scope) {
+            module.insert_doc(doc);
+        }
+    }
+
+    pub(crate) fn document_predicate(&mut self, predicate: Handle, doc: &str) {
+        self.current_module_mut()
+            .insert_predicate_doc(predicate, doc);
+    }
+
+    pub(crate) fn document_operator(&mut self, operator: Atom, doc: &str) {
+        self.current_module_mut().insert_operator_doc(operator, doc);
+    }
+
+    pub(crate) fn declare_allowed(&mut self, lint: Atom) {
+        match Lint::from_code(lint.as_ref()) {
+            Some(lint) => self.current_module_mut().insert_allowed(lint),
+            None => self.error_unknown_lint(lint),
+        }
+    }
+
     fn validate_headers(&mut self, natives: &[&Handle]) {
         let modules = self
             .modules
@@ -220,6 +307,10 @@ impl<'p> Context<'p> {
         for (scope, module) in modules.clone() {
             let errors = module.errors(self, natives);
             if !errors.is_empty() {
+                let errors = errors
+                    .into_iter()
+                    .map(|error| self.locate_error(scope, error))
+                    .collect::<Vec<_>>();
                 self.errors.entry(scope.clone()).or_default().extend(errors);
             }
         }
@@ -256,6 +347,12 @@ impl<'p> Context<'p> {
         self.leave_module();
     }
 
+    pub(crate) fn lint_module(&mut self, module: &Module, name: Atom) {
+        self.enter_module(name);
+        module.lint(self);
+        self.leave_module();
+    }
+
     pub(crate) fn resolve_handle<'a>(&'a mut self, handle: &'a Handle) -> Option<Handle> {
         self.resolve_handle_in_scope(handle, &self.current_scope.clone())
     }
@@ -284,7 +381,7 @@ impl<'p> Context<'p> {
         match resolved {
             Ok(resolved) => Some(resolved.clone()),
             Err(error) => {
-                self.current_errors_mut().push(error);
+                self.push_error(error);
                 None
             }
         }
@@ -307,9 +404,13 @@ impl<'p> Context<'p> {
             }
         };
         match resolved {
-            Ok(resolved) => Some(resolved.clone()),
+            Ok(resolved) => {
+                let resolved = resolved.clone();
+                self.used_operators.insert(resolved.clone());
+                Some(resolved)
+            }
             Err(error) => {
-                self.current_errors_mut().push(error);
+                self.push_error(error);
                 None
             }
         }
@@ -325,154 +426,269 @@ impl<'p> Context<'p> {
 }
 
 impl Context<'_> {
-    fn current_errors_mut(&mut self) -> &mut Vec<crate::Error> {
-        self.errors.entry(self.current_scope.clone()).or_default()
+    fn add_source(&mut self, path: Option<PathBuf>, source: &str) {
+        self.sources
+            .insert(self.current_scope.clone(), (path, Rc::from(source)));
+    }
+
+    /// Sets the part of the current module's source code being compiled, so that errors found
+    /// in it can be located.
+    pub(crate) fn locate(&mut self, span: Option<SourceSpan>) {
+        self.current_span = span;
+    }
+
+    /// Fills in the location of the diagnostics of an error found in a module, as far as it is
+    /// known.
+    pub(crate) fn locate_error(&self, scope: &Scope, mut error: crate::Error) -> crate::Error {
+        for diagnostic in error.diagnostics.iter_mut() {
+            self.locate_diagnostic(scope, diagnostic);
+        }
+        error
+    }
+
+    pub(crate) fn locate_diagnostic(&self, scope: &Scope, diagnostic: &mut Diagnostic) {
+        let (path, source) = match self.sources.get(scope) {
+            Some((path, source)) => (path.as_deref(), source.as_ref()),
+            None => (None, ""),
+        };
+        let span = if *scope == self.current_scope {
+            self.current_span
+        } else {
+            None
+        };
+        diagnostic.locate(path, span, source);
+    }
+
+    fn push_error(&mut self, error: crate::Error) {
+        let error = self.locate_error(&self.current_scope, error);
+        self.errors
+            .entry(self.current_scope.clone())
+            .or_default()
+            .push(error);
+    }
+
+    fn report(&mut self, diagnostic: Diagnostic) {
+        self.push_error(crate::Error::diagnostic(diagnostic));
+    }
+
+    fn current_source(&self) -> Option<&str> {
+        self.sources
+            .get(&self.current_scope)
+            .map(|(_, source)| source.as_ref())
+    }
+
+    pub(crate) fn error_syntax(&mut self, error: crate::Error) {
+        self.syntax_errors = true;
+        self.push_error(error);
     }
 
     pub(crate) fn error_duplicate_module(&mut self, module: Scope) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Module {} declared multiple times.",
-            module
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_module",
+            format!("Module {} declared multiple times.", module),
+        ));
     }
 
     pub(crate) fn error_duplicate_export(&mut self, handle: Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "{} exported multiple times.",
-            handle
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_export",
+            format!("{} exported multiple times.", handle),
+        ));
     }
 
     pub(crate) fn error_duplicate_export_op(&mut self, operator: Atom) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "operator {} exported multiple times.",
-            operator.as_ref()
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_export_op",
+            format!("operator {} exported multiple times.", operator.as_ref()),
+        ));
     }
 
     pub(crate) fn error_duplicate_incomplete(&mut self, handle: Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "{} decared as incomplete multiple times.",
-            handle
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_incomplete",
+            format!("{} decared as incomplete multiple times.", handle),
+        ));
     }
 
     pub(crate) fn error_duplicate_mutable(&mut self, handle: Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "{} set as mutable multiple times.",
-            handle
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_mutable",
+            format!("{} set as mutable multiple times.", handle),
+        ));
     }
 
     pub(crate) fn error_negative_scope(&mut self, span: Span) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Scope {} goes above the main module.",
-            span.as_str()
-        )));
+        self.report(
+            Diagnostic::error(
+                "negative_scope",
+                format!("Scope {} goes above the main module.", span.as_str()),
+            )
+            .with_span(SourceSpan::from_pest(span)),
+        );
     }
 
     pub(crate) fn error_duplicate_import(&mut self, import: Handle, from: Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "{} already imported from {}.",
-            import, from
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_import",
+            format!("{} already imported from {}.", import, from),
+        ));
     }
 
     pub(crate) fn error_duplicate_operator_alias(&mut self, import: Atom, from: Scope) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "{} already imported from {}.",
-            import, from
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_operator_alias",
+            format!("{} already imported from {}.", import, from),
+        ));
     }
 
     pub(crate) fn error_duplicate_glob(&mut self, module: Scope) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Module {} imported multiple times.",
-            module
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_glob",
+            format!("Module {} imported multiple times.", module),
+        ));
     }
 
     pub(crate) fn error_duplicate_native(&mut self, handle: Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Native function {} declared multiple times.",
-            handle
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_native",
+            format!("Native function {} declared multiple times.", handle),
+        ));
     }
 
     pub(crate) fn error_duplicate_operator(&mut self, operator: Operator) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Operator {:?} declared multiple times.",
-            operator,
-        )));
+        self.report(Diagnostic::error(
+            "duplicate_operator",
+            format!("Operator {:?} declared multiple times.", operator),
+        ));
     }
 
     pub(crate) fn error_invalid_alias_arity(&mut self, input: &Handle, output: &Handle) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Cannot change arity of {} when aliasing to {}.",
-            input, output,
-        )));
+        self.report(Diagnostic::error(
+            "invalid_alias_arity",
+            format!(
+                "Cannot change arity of {} when aliasing to {}.",
+                input, output,
+            ),
+        ));
     }
 
     pub(crate) fn error_singleton_variable(&mut self, handle: &Handle, variable: &str) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Singleton variable {} in predicate {}.",
-            variable, handle,
-        )));
+        let mut diagnostic = Diagnostic::error(
+            "singleton_variable",
+            format!("Singleton variable {} in predicate {}.", variable, handle),
+        )
+        .with_suggestion(format!(
+            "replace {} with _ if its value is not needed",
+            variable
+        ));
+        let span = self
+            .current_span
+            .zip(self.current_source())
+            .and_then(|(span, source)| span.find(source, variable));
+        if let Some(span) = span {
+            diagnostic = diagnostic.with_span(span);
+        }
+        self.report(diagnostic);
     }
 
     pub(crate) fn error_unlinked_library(&mut self, handle: &Handle, library: &Atom) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Referencing predicate {} from unlinked library {}.",
-            handle, library,
-        )));
+        self.report(
+            Diagnostic::error(
+                "unlinked_library",
+                format!(
+                    "Referencing predicate {} from unlinked library {}.",
+                    handle, library,
+                ),
+            )
+            .with_suggestion(format!(
+                "link the library @{} using `LumberBuilder::link`",
+                library
+            )),
+        );
     }
 
     pub(crate) fn error_undeclared_module(&mut self, handle: &Handle, module: Scope) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Referencing predicate {} from undeclared module {}.",
-            handle, module,
-        )));
+        self.report(
+            Diagnostic::error(
+                "undeclared_module",
+                format!(
+                    "Referencing predicate {} from undeclared module {}.",
+                    handle, module,
+                ),
+            )
+            .with_note(format!("no module {} has been declared", module)),
+        );
     }
 
     pub(crate) fn error_undeclared_module_op(&mut self, operator: &OpKey, module: &Scope) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Referencing operator {} from undeclared module {}.",
-            operator, module,
-        )));
+        self.report(Diagnostic::error(
+            "undeclared_module_op",
+            format!(
+                "Referencing operator {} from undeclared module {}.",
+                operator, module,
+            ),
+        ));
     }
 
     pub(crate) fn error_unresolved_library_predicate(&mut self, handle: &Handle, library: &Atom) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "No predicate {} is exported by the library {}.",
-            handle, library,
-        )));
+        self.report(Diagnostic::error(
+            "unresolved_library_predicate",
+            format!(
+                "No predicate {} is exported by the library {}.",
+                handle, library,
+            ),
+        ));
     }
 
     pub(crate) fn error_operator_precedence(&mut self, name: Atom, precedence: Int) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Precedence {} for operator {} is too high (maximum is 9).",
-            precedence, name,
-        )))
+        self.report(Diagnostic::error(
+            "operator_precedence",
+            format!(
+                "Precedence {} for operator {} is too high (maximum is 9).",
+                precedence, name,
+            ),
+        ))
     }
 
     pub(crate) fn error_unary_operator_restriction(&mut self, name: Atom) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
+        self.report(Diagnostic::error(
+            "unary_operator_restriction",
+            format!(
             "Operator {} is defined as a unary expression operator, so must be right associative with maximum (9) precedence.",
             name,
-        )))
+        ),
+        ))
     }
 
     pub(crate) fn error_operator_arity_relation(&mut self, name: Atom, len: u32) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Arity of relational operator {} must be 1 or 2, found {}.",
-            name, len,
-        )))
+        self.report(Diagnostic::error(
+            "operator_arity_relation",
+            format!(
+                "Arity of relational operator {} must be 1 or 2, found {}.",
+                name, len,
+            ),
+        ))
     }
 
     pub(crate) fn error_operator_arity_expression(&mut self, name: Atom, len: u32) {
-        self.current_errors_mut().push(crate::Error::parse(&format!(
-            "Arity of expression operator {} must be 2 or 3, found {}.",
-            name, len,
-        )))
+        self.report(Diagnostic::error(
+            "operator_arity_expression",
+            format!(
+                "Arity of expression operator {} must be 2 or 3, found {}.",
+                name, len,
+            ),
+        ))
+    }
+
+    pub(crate) fn error_unknown_lint(&mut self, lint: Atom) {
+        let lints = Lint::ALL
+            .iter()
+            .map(|lint| lint.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.report(
+            Diagnostic::error("unknown_lint", format!("Unknown lint {}.", lint.as_ref()))
+                .with_note(format!("the lints are: {}", lints)),
+        )
     }
 }
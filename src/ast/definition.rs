@@ -3,31 +3,46 @@ use super::*;
 /// The definition of a rule. A predicate may be defined multiple times with disjoint
 /// heads and distinct bodies.
 #[derive(Default, Clone, Debug)]
-pub(crate) struct Definition(Vec<(Head, RuleKind, Option<Body>)>);
+pub(crate) struct Definition(Vec<(Head, RuleKind, Option<Body>, Location)>);
 
 impl Definition {
-    pub fn insert(&mut self, head: Head, kind: RuleKind, body: Option<Body>) {
-        self.0.push((head, kind, body));
+    pub fn insert(&mut self, head: Head, kind: RuleKind, body: Option<Body>, location: Location) {
+        self.0.push((head, kind, body, location));
     }
 
-    pub fn bodies_mut(&mut self) -> impl Iterator<Item = &mut Body> {
-        self.0.iter_mut().filter_map(|(_, _, body)| body.as_mut())
+    pub fn clauses(&self) -> &[(Head, RuleKind, Option<Body>, Location)] {
+        &self.0
     }
 
-    pub fn resolve_handles<F: FnMut(&Handle) -> Option<Handle>>(&mut self, mut resolve: F) {
-        self.bodies_mut()
-            .for_each(move |body| body.resolve_handles(&mut resolve));
+    /// The bodies of each clause, along with the location of the clause they belong to.
+    pub fn bodies_mut(&mut self) -> impl Iterator<Item = (&mut Body, Location)> {
+        self.0
+            .iter_mut()
+            .filter_map(|(_, _, body, location)| Some((body.as_mut()?, *location)))
     }
 
-    pub fn resolve_operators<F: FnMut(&OpKey) -> Option<Operator>>(&mut self, mut resolve: F) {
-        self.bodies_mut()
-            .for_each(move |body| body.resolve_operators(&mut resolve));
+    pub fn resolve_handles<F>(&mut self, mut resolve: F)
+    where
+        F: FnMut(&Handle, Location) -> Option<Handle>,
+    {
+        self.bodies_mut().for_each(move |(body, location)| {
+            body.resolve_handles(&mut |handle| resolve(handle, location))
+        });
+    }
+
+    pub fn resolve_operators<F>(&mut self, mut resolve: F)
+    where
+        F: FnMut(&OpKey, Location) -> Option<Operator>,
+    {
+        self.bodies_mut().for_each(move |(body, location)| {
+            body.resolve_operators(&mut |operator| resolve(operator, location))
+        });
     }
 }
 
 impl IntoIterator for Definition {
-    type Item = <Vec<(Head, RuleKind, Option<Body>)> as IntoIterator>::Item;
-    type IntoIter = <Vec<(Head, RuleKind, Option<Body>)> as IntoIterator>::IntoIter;
+    type Item = <Vec<(Head, RuleKind, Option<Body>, Location)> as IntoIterator>::Item;
+    type IntoIter = <Vec<(Head, RuleKind, Option<Body>, Location)> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
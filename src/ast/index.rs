@@ -0,0 +1,209 @@
+use super::*;
+use crate::parser::{Parser, Rule};
+use crate::SourceSpan;
+
+/// Something in a program which is referred to by name.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) enum Symbol {
+    Predicate(Handle),
+    /// An operator, by the module which defines it and its name.
+    Operator(Scope, Atom),
+    Module(Scope),
+}
+
+/// A place in the source code of a module where a symbol is named.
+#[derive(Clone, Debug)]
+pub(crate) struct Occurrence {
+    /// The module in which the symbol is named.
+    pub scope: Scope,
+    pub span: SourceSpan,
+    pub symbol: Symbol,
+    /// Whether the symbol is defined here, rather than referred to.
+    pub definition: bool,
+}
+
+impl Context<'_> {
+    /// Finds each place where a symbol is named in the source code of the modules of the
+    /// program, along with the symbol it resolves to. Names which do not resolve are skipped.
+    ///
+    /// This is done once the modules have been parsed, so that the references can be resolved
+    /// as they would be while compiling the program.
+    pub(crate) fn index(&mut self) -> Vec<Occurrence> {
+        let sources: Vec<_> = self
+            .sources
+            .iter()
+            .map(|(scope, (_, source))| (scope.clone(), source.clone()))
+            .collect();
+        let errors = std::mem::take(&mut self.errors);
+        let mut occurrences = vec![];
+        for (scope, source) in sources {
+            self.current_scope = scope;
+            let (source, _) = Parser::recover_module(&source);
+            let module = Parser::parse_module(&source).unwrap().next().unwrap();
+            for pair in module.into_inner() {
+                self.index_pair(pair, &mut occurrences);
+            }
+        }
+        self.current_scope = Scope::default();
+        self.errors = errors;
+        occurrences
+    }
+
+    fn index_pair(&mut self, pair: crate::Pair, occurrences: &mut Vec<Occurrence>) {
+        let scope = self.current_scope.clone();
+        match pair.as_rule() {
+            Rule::mod_ => {
+                let atom = just!(Rule::atom, pair.into_inner());
+                let span = SourceSpan::from_pest(atom.as_span());
+                let symbol = Symbol::Module(scope.join(Atom::new(atom)));
+                occurrences.push(self.occurrence(span, symbol, false));
+            }
+            Rule::use_ => {
+                let mut pairs = just!(Rule::multi_handle, pair.into_inner()).into_inner();
+                let path = pairs.next().unwrap();
+                let span = SourceSpan::from_pest(path.as_span());
+                let module = match Scope::new_module_path(path, self) {
+                    Some(module) => module,
+                    None => return,
+                };
+                occurrences.push(self.occurrence(span, Symbol::Module(module.clone()), false));
+                for pair in pairs.flat_map(|pair| pair.into_inner()) {
+                    match pair.as_rule() {
+                        Rule::handle => self.index_handle(pair, &module, false, occurrences),
+                        Rule::operator => self.index_operator(pair, &module, occurrences),
+                        Rule::alias => {
+                            let mut pairs = pair.into_inner();
+                            self.index_handle(pairs.next().unwrap(), &module, false, occurrences);
+                            self.index_handle(pairs.next().unwrap(), &scope, false, occurrences);
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            Rule::nat => {
+                let pair = just!(Rule::handle, pair.into_inner());
+                self.index_handle(pair, &scope, true, occurrences);
+            }
+            Rule::pub_ | Rule::mut_ | Rule::inc => {
+                let pair = just!(pair.into_inner());
+                match pair.as_rule() {
+                    Rule::handle => self.index_handle(pair, &scope, false, occurrences),
+                    Rule::operator => self.index_operator(pair, &scope, occurrences),
+                    _ => unreachable!(),
+                }
+            }
+            Rule::op => {
+                let mut pairs = pair.into_inner();
+                let operator = pairs.next().unwrap();
+                let span = SourceSpan::from_pest(operator.as_span());
+                let symbol = Symbol::Operator(scope.clone(), Atom::from(operator.as_str()));
+                occurrences.push(self.occurrence(span, symbol, true));
+                self.index_handle(pairs.next().unwrap(), &scope, false, occurrences);
+            }
+            Rule::head => {
+                let mut pairs = pair.into_inner();
+                let atom = pairs.next().unwrap();
+                let span = SourceSpan::from_pest(atom.as_span());
+                let handle = Handle::from_parts(scope.join(Atom::new(atom)), arity(pairs.next()));
+                let handle = self.resolve_handle(&handle).unwrap_or(handle);
+                occurrences.push(self.occurrence(span, Symbol::Predicate(handle), true));
+            }
+            Rule::predicate => {
+                let mut pairs = pair.into_inner();
+                let path = pairs.next().unwrap();
+                let name = path.clone().into_inner().next_back().unwrap();
+                let span = SourceSpan::from_pest(name.as_span());
+                let arguments = pairs.next();
+                if let Some(path) = Scope::new(path, self) {
+                    let handle = Handle::from_parts(path, arity(arguments.clone()));
+                    if let Some(handle) = self.resolve_handle(&handle) {
+                        occurrences.push(self.occurrence(span, Symbol::Predicate(handle), false));
+                    }
+                }
+                for pair in arguments.into_iter().flat_map(|pair| pair.into_inner()) {
+                    self.index_pair(pair, occurrences);
+                }
+            }
+            Rule::operator => self.index_operator(pair, &scope, occurrences),
+            _ => {
+                for pair in pair.into_inner() {
+                    self.index_pair(pair, occurrences);
+                }
+            }
+        }
+    }
+
+    /// Indexes a handle naming a predicate in the given module, as seen from the current module.
+    fn index_handle(
+        &mut self,
+        pair: crate::Pair,
+        module: &Scope,
+        definition: bool,
+        occurrences: &mut Vec<Occurrence>,
+    ) {
+        let atom = pair.clone().into_inner().next().unwrap();
+        let span = SourceSpan::from_pest(atom.as_span());
+        let handle = Handle::new_in_scope(module.clone(), pair);
+        let resolved = match self.resolve_handle(&handle) {
+            Some(resolved) => resolved,
+            None if definition => handle,
+            None => return,
+        };
+        occurrences.push(self.occurrence(span, Symbol::Predicate(resolved), definition));
+    }
+
+    /// Indexes an operator defined in (or imported into) the given module, as seen from the
+    /// current module.
+    fn index_operator(
+        &mut self,
+        pair: crate::Pair,
+        module: &Scope,
+        occurrences: &mut Vec<Occurrence>,
+    ) {
+        let name = Atom::from(pair.as_str());
+        let span = SourceSpan::from_pest(pair.as_span());
+        let header = match self.modules.get(module) {
+            Some(header) => header,
+            None => return,
+        };
+        let resolved = OpKey::all_types(name.clone()).find_map(|key| {
+            header
+                .resolve_operator(&key, &self.current_scope, self)
+                .ok()
+                .map(|operator| operator.handle().module())
+        });
+        if let Some(module) = resolved {
+            occurrences.push(self.occurrence(span, Symbol::Operator(module, name), false));
+        }
+    }
+
+    fn occurrence(&self, span: SourceSpan, symbol: Symbol, definition: bool) -> Occurrence {
+        Occurrence {
+            scope: self.current_scope.clone(),
+            span,
+            symbol,
+            definition,
+        }
+    }
+}
+
+/// The arity of the arguments of a predicate, or the parameters of a head.
+fn arity(pair: Option<crate::Pair>) -> Arity {
+    let mut arity = Arity::default();
+    for pair in pair.into_iter().flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::bare_arguments | Rule::bare_params => {
+                arity.len = pair.into_inner().count() as u32;
+            }
+            Rule::named_arguments | Rule::named_params => {
+                for pair in pair.into_inner() {
+                    let mut pairs = pair.into_inner();
+                    let name = Atom::new(pairs.next().unwrap());
+                    arity.push(name, pairs.next().unwrap().into_inner().count() as u32);
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+    arity
+}
@@ -0,0 +1,307 @@
+use super::*;
+use crate::Diagnostic;
+use std::collections::HashMap;
+
+/// The checks for likely mistakes which are made once a program has been compiled. Lints
+/// report warnings, which do not prevent the program from being created, and can be silenced
+/// in a module using the `:- allow(lint).` directive.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) enum Lint {
+    /// A private predicate which is never referenced, other than by itself.
+    UnusedPredicate,
+    /// A predicate or operator which is imported, but never used.
+    UnusedImport,
+    /// A module which is glob imported, but from which nothing is used.
+    UnusedGlob,
+    /// An incomplete predicate which no other module extends, or a mutable predicate which
+    /// has no clauses.
+    UnextendedPredicate,
+    /// A clause which follows a last-rule that matches every call the clause would.
+    UnreachableClause,
+    /// An operator which is defined, but never used or exported.
+    UnusedOperator,
+    /// A clause which is separated from the earlier clauses of the same predicate.
+    DiscontiguousClauses,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedPredicate,
+        Lint::UnusedImport,
+        Lint::UnusedGlob,
+        Lint::UnextendedPredicate,
+        Lint::UnreachableClause,
+        Lint::UnusedOperator,
+        Lint::DiscontiguousClauses,
+    ];
+
+    /// The name of the lint, which is used as the code of its warnings, and to allow it.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedPredicate => "unused_predicate",
+            Lint::UnusedImport => "unused_import",
+            Lint::UnusedGlob => "unused_glob",
+            Lint::UnextendedPredicate => "unextended_predicate",
+            Lint::UnreachableClause => "unreachable_clause",
+            Lint::UnusedOperator => "unused_operator",
+            Lint::DiscontiguousClauses => "discontiguous_clauses",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|lint| lint.code() == code)
+    }
+}
+
+/// Something declared by a directive, which may be checked by a lint.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) enum Declaration {
+    /// An imported predicate, by the handle it is imported as.
+    Alias(Handle),
+    /// A glob import of a module (the second scope) into a module (the first scope).
+    Glob(Scope, Scope),
+    /// An operator imported into a module from another module.
+    OperatorAlias(Scope, Atom, Scope),
+    /// An operator defined in a module.
+    Operator(Scope, OpKey),
+    /// A predicate declared as incomplete.
+    Incomplete(Handle),
+    /// A predicate declared as mutable.
+    Mutable(Handle),
+}
+
+impl Context<'_> {
+    /// Records a declaration made by the directive currently being compiled.
+    pub(crate) fn declare(&mut self, declaration: Declaration) {
+        self.declarations.insert(declaration, self.current_span);
+    }
+
+    /// Records that a declaration has been used while resolving a reference.
+    pub(crate) fn use_declaration(&self, declaration: Declaration) {
+        self.used.borrow_mut().insert(declaration);
+    }
+
+    /// Reports a warning found in the current module.
+    pub(crate) fn warn(&mut self, lint: Lint, diagnostic: Diagnostic) {
+        let scope = self.current_scope.clone();
+        self.warn_in(scope, lint, diagnostic);
+    }
+
+    fn warn_in(&mut self, scope: Scope, lint: Lint, diagnostic: Diagnostic) {
+        let mut diagnostic = diagnostic.with_note(format!(
+            "this warning can be silenced using `:- allow({}).`",
+            lint.code()
+        ));
+        self.locate_diagnostic(&scope, &mut diagnostic);
+        self.warnings.push((scope, lint, diagnostic));
+    }
+
+    /// Reports a clause which is separated from the earlier clauses of its predicate.
+    pub(crate) fn lint_contiguous(&mut self, location: Location, earlier: &Definition) {
+        let (head, _, _, previous) = earlier.clauses().last().unwrap();
+        self.warn(
+            Lint::DiscontiguousClauses,
+            Diagnostic::warning(
+                Lint::DiscontiguousClauses.code(),
+                format!("Clauses of predicate {} are not together.", head.as_ref()),
+            )
+            .with_span(location.span())
+            .with_note(format!("the previous clause is at {}", previous)),
+        );
+    }
+
+    pub(crate) fn lint_definition(&mut self, handle: &Handle, definition: &Definition) {
+        if handle.module() == self.current_scope {
+            self.use_declaration(Declaration::Mutable(handle.clone()));
+        } else {
+            self.use_declaration(Declaration::Incomplete(handle.clone()));
+        }
+        let clauses = definition.clauses();
+        for (i, (head, kind, _, location)) in clauses.iter().enumerate() {
+            if *kind != RuleKind::Once {
+                continue;
+            }
+            for (j, (other, _, _, other_location)) in clauses.iter().enumerate().skip(i + 1) {
+                if head.subsumes(other) {
+                    self.warn(
+                        Lint::UnreachableClause,
+                        Diagnostic::warning(
+                            Lint::UnreachableClause.code(),
+                            format!("Clause {} of predicate {} is unreachable.", j, handle),
+                        )
+                        .with_span(other_location.span())
+                        .with_note(format!(
+                            "clause {} at {} is a last-rule matching every call this one would",
+                            i, location,
+                        )),
+                    );
+                }
+            }
+        }
+
+        let header = &self.modules[&self.current_scope];
+        let unused = handle.module() == self.current_scope
+            && header.definitions.contains(handle)
+            && !header.exports.contains(handle)
+            && !self.referenced.contains(handle)
+            && !self
+                .modules
+                .values()
+                .flat_map(|module| module.operators.values())
+                .any(|operator| operator.handle() == handle);
+        if unused {
+            let (_, _, _, location) = clauses.first().unwrap();
+            self.warn(
+                Lint::UnusedPredicate,
+                Diagnostic::warning(
+                    Lint::UnusedPredicate.code(),
+                    format!("Predicate {} is never used.", handle),
+                )
+                .with_span(location.span())
+                .with_suggestion(format!(
+                    "export it using `:- pub({}).` if it is meant to be used by other modules",
+                    handle.head(),
+                )),
+            );
+        }
+    }
+
+    /// Checks that each of the declarations made in the program has been used.
+    pub(crate) fn lint_declarations(&mut self) {
+        let mut declarations: Vec<_> = self
+            .declarations
+            .iter()
+            .map(|(declaration, span)| (declaration.clone(), *span))
+            .collect();
+        declarations.retain(|(declaration, _)| !self.used.borrow().contains(declaration));
+        for (declaration, span) in declarations {
+            let (scope, lint, message) = match &declaration {
+                Declaration::Alias(handle) => (
+                    handle.module(),
+                    Lint::UnusedImport,
+                    format!("Imported predicate {} is never used.", handle.head()),
+                ),
+                Declaration::Glob(scope, module) => (
+                    scope.clone(),
+                    Lint::UnusedGlob,
+                    format!("Nothing imported from module {} is used.", module),
+                ),
+                Declaration::OperatorAlias(scope, operator, module) => (
+                    scope.clone(),
+                    Lint::UnusedImport,
+                    format!(
+                        "Operator {} imported from module {} is never used.",
+                        operator.as_ref(),
+                        module,
+                    ),
+                ),
+                Declaration::Operator(scope, key) => {
+                    let header = &self.modules[scope];
+                    let used = header.operator_exports.contains(&key.name())
+                        || self.used_operators.contains(&header.operators[key]);
+                    if used {
+                        continue;
+                    }
+                    (
+                        scope.clone(),
+                        Lint::UnusedOperator,
+                        format!("Operator {} is never used.", key),
+                    )
+                }
+                Declaration::Incomplete(handle) => (
+                    handle.module(),
+                    Lint::UnextendedPredicate,
+                    format!("Incomplete predicate {} is never extended.", handle.head()),
+                ),
+                Declaration::Mutable(handle) => (
+                    handle.module(),
+                    Lint::UnextendedPredicate,
+                    format!("Mutable predicate {} has no clauses.", handle.head()),
+                ),
+            };
+            let mut diagnostic = Diagnostic::warning(lint.code(), message);
+            if let Some(span) = span {
+                diagnostic = diagnostic.with_span(span);
+            }
+            self.warn_in(scope, lint, diagnostic);
+        }
+    }
+
+    /// Takes the warnings which have not been allowed in the module they were found in, in the
+    /// order they appear in the source code.
+    pub(crate) fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        let mut warnings: Vec<_> = std::mem::take(&mut self.warnings)
+            .into_iter()
+            .filter(|(scope, lint, _)| {
+                self.modules
+                    .get(scope)
+                    .is_none_or(|module| !module.allowed.contains(lint))
+            })
+            .map(|(scope, _, diagnostic)| (scope.to_string(), diagnostic))
+            .collect();
+        warnings.sort_by(|(a, lhs), (b, rhs)| {
+            (a, lhs.span(), lhs.message()).cmp(&(b, rhs.span(), rhs.message()))
+        });
+        warnings
+            .into_iter()
+            .map(|(_, diagnostic)| diagnostic)
+            .collect()
+    }
+}
+
+impl Head {
+    /// Whether every call which would unify with the other head would also unify with this
+    /// one. This is only an approximation, which errs on the side of returning `false`.
+    pub fn subsumes(&self, other: &Head) -> bool {
+        let mut bindings = HashMap::new();
+        self.handle == other.handle
+            && self
+                .patterns
+                .iter()
+                .zip(&other.patterns)
+                .all(|(pattern, other)| pattern.subsumes(other, &mut bindings))
+    }
+}
+
+impl Pattern {
+    fn subsumes(&self, other: &Pattern, bindings: &mut HashMap<Identifier, Pattern>) -> bool {
+        match (self, other) {
+            (Pattern::Wildcard, _) => true,
+            (Pattern::Variable(identifier), other) => match bindings.get(identifier) {
+                Some(bound) => bound == other,
+                None => {
+                    bindings.insert(identifier.clone(), other.clone());
+                    true
+                }
+            },
+            (Pattern::Literal(lhs), Pattern::Literal(rhs)) => lhs == rhs,
+            (Pattern::Struct(lhs), Pattern::Struct(rhs)) => {
+                lhs.name == rhs.name
+                    && match (&lhs.contents, &rhs.contents) {
+                        (None, None) => true,
+                        (Some(lhs), Some(rhs)) => lhs.subsumes(rhs, bindings),
+                        _ => false,
+                    }
+            }
+            (Pattern::List(lhs, None), Pattern::List(rhs, None)) => {
+                lhs.len() == rhs.len()
+                    && lhs
+                        .iter()
+                        .zip(rhs)
+                        .all(|(lhs, rhs)| lhs.subsumes(rhs, bindings))
+            }
+            (Pattern::List(lhs, Some(tail)), Pattern::List(rhs, rest))
+                if lhs.len() <= rhs.len() =>
+            {
+                lhs.iter()
+                    .zip(rhs)
+                    .all(|(lhs, rhs)| lhs.subsumes(rhs, bindings))
+                    && tail.subsumes(
+                        &Pattern::List(rhs[lhs.len()..].to_vec(), rest.clone()),
+                        bindings,
+                    )
+            }
+            _ => false,
+        }
+    }
+}
@@ -41,13 +41,17 @@ impl Literal {
                 let numerator = format!("{}{}", unit, fractional).parse().unwrap();
                 Literal::Rational(Rational::new(numerator, denominator))
             }
-            Rule::string => {
-                let string = pair.as_str().trim_matches('#');
-                Self::String(string[1..string.len() - 1].to_owned())
-            }
+            Rule::string => Self::String(Self::string(pair)),
             _ => unreachable!(),
         }
     }
+
+    /// The contents of a string literal, without its quotes.
+    pub fn string(pair: crate::Pair) -> String {
+        assert_eq!(pair.as_rule(), Rule::string);
+        let string = pair.as_str().trim_matches('#');
+        string[1..string.len() - 1].to_owned()
+    }
 }
 
 impl Display for Literal {
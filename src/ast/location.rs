@@ -0,0 +1,27 @@
+use crate::SourceSpan;
+use std::fmt::{self, Display, Formatter};
+
+/// A position in the source code of a module, counted from 1.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct Location {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl Location {
+    pub fn new(pair: &crate::Pair) -> Self {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        Self { line, column }
+    }
+
+    /// The span of the single character at this location.
+    pub fn span(&self) -> SourceSpan {
+        SourceSpan::new((self.line, self.column), (self.line, self.column + 1))
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
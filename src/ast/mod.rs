@@ -15,6 +15,7 @@ mod handle;
 mod head;
 mod identifier;
 mod literal;
+mod location;
 mod module;
 mod op_arity;
 mod op_key;
@@ -27,6 +28,7 @@ mod scope;
 mod step;
 mod r#struct;
 mod term;
+mod unit_test;
 
 pub(crate) use alias::Alias;
 pub(crate) use arity::Arity;
@@ -41,6 +43,7 @@ pub(crate) use handle::{AsHandle, Handle};
 pub(crate) use head::Head;
 pub(crate) use identifier::Identifier;
 pub(crate) use literal::Literal;
+pub(crate) use location::Location;
 pub(crate) use module::Module;
 pub(crate) use op_arity::OpArity;
 pub(crate) use op_key::OpKey;
@@ -53,11 +56,16 @@ pub(crate) use rule_kind::RuleKind;
 pub(crate) use scope::Scope;
 pub(crate) use step::Step;
 pub(crate) use term::Term;
+pub(crate) use unit_test::UnitTest;
 
 mod context;
+mod index;
+mod lint;
 mod module_header;
 
 pub(crate) use context::Context;
+pub(crate) use index::{Occurrence, Symbol};
+pub(crate) use lint::{Declaration, Lint};
 pub(crate) use module_header::ModuleHeader;
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use super::*;
-use crate::parser::{Parser, Rule};
+use crate::parser::{doc_comments, Parser, Rule};
+use crate::SourceSpan;
 use std::collections::HashMap;
 
 /// A module within a Lumber program.
@@ -10,19 +11,37 @@ pub(crate) struct Module {
     /// All predicates defined in this module.
     definitions: HashMap<Handle, Definition>,
     /// Unit tests that are defined in this module.
-    tests: Vec<Body>,
+    tests: Vec<UnitTest>,
 }
 
 impl Module {
     pub fn new(source_str: &str, context: &mut Context) -> crate::Result<Self> {
-        let pairs = Parser::parse_module(source_str)?;
+        context.locate(None);
+        let recovered;
+        let pairs = match Parser::parse_module(source_str) {
+            Ok(pairs) => pairs,
+            Err(..) => {
+                // Report every syntax error in the module, and continue with the statements
+                // that do parse so that errors in other modules are found too.
+                let (source, errors) = Parser::recover_module(source_str);
+                for error in errors {
+                    context.error_syntax(error);
+                }
+                recovered = source;
+                Parser::parse_module(&recovered)?
+            }
+        };
         let pairs = just!(Rule::module, pairs).into_inner();
+        let docs = doc_comments(source_str);
 
         let mut submodules = HashMap::<Atom, Module>::new();
         let mut definitions = HashMap::<Handle, Definition>::new();
-        let mut tests: Vec<Body> = vec![];
+        let mut tests: Vec<UnitTest> = vec![];
+        let mut previous: Option<Handle> = None;
 
         for pair in pairs {
+            context.locate(Some(SourceSpan::from_pest(pair.as_span())));
+            let doc = docs.get(&pair.as_span().start());
             match pair.as_rule() {
                 Rule::directive => {
                     let pair = just!(Rule::instruction, pair.into_inner());
@@ -32,6 +51,9 @@ impl Module {
                             let atom = just!(Rule::atom, pair.into_inner());
                             let atom = Atom::new(atom);
                             if let Some(module) = context.add_module(atom.clone())? {
+                                if let Some(doc) = doc {
+                                    context.document_module(atom.clone(), doc);
+                                }
                                 submodules.insert(atom, module);
                             }
                         }
@@ -40,10 +62,16 @@ impl Module {
                             match pair.as_rule() {
                                 Rule::handle => {
                                     let handle = Handle::new(pair, context);
+                                    if let Some(doc) = doc {
+                                        context.document_predicate(handle.clone(), doc);
+                                    }
                                     context.declare_export(handle);
                                 }
                                 Rule::operator => {
                                     let operator = Atom::from(pair.as_str());
+                                    if let Some(doc) = doc {
+                                        context.document_operator(operator.clone(), doc);
+                                    }
                                     context.declare_operator_export(operator);
                                 }
                                 _ => unreachable!(),
@@ -70,35 +98,49 @@ impl Module {
                         Rule::mut_ => {
                             let handle = just!(Rule::handle, pair.into_inner());
                             let handle = Handle::new(handle, context);
+                            if let Some(doc) = doc {
+                                context.document_predicate(handle.clone(), doc);
+                            }
                             context.declare_mutable(handle);
                         }
                         Rule::inc => {
                             let handle = just!(Rule::handle, pair.into_inner());
                             let handle = Handle::new(handle, context);
+                            if let Some(doc) = doc {
+                                context.document_predicate(handle.clone(), doc);
+                            }
                             context.declare_incomplete(handle);
                         }
                         Rule::nat => {
                             let pair = just!(Rule::handle, pair.into_inner());
                             let handle = Handle::new(pair, context);
-                            context.declare_native(handle.clone());
+                            if let Some(doc) = doc {
+                                context.document_predicate(handle.clone(), doc);
+                            }
+                            context.declare_native(handle);
                         }
                         Rule::op => {
                             if let Some(operator) = Operator::new(pair, context) {
+                                if let Some(doc) = doc {
+                                    context.document_operator(operator.key().name(), doc);
+                                }
                                 context.declare_operator(operator);
                             }
                         }
-                        Rule::test => {
-                            let pair = just!(Rule::body, pair.into_inner());
-                            match Body::new(pair, context) {
-                                Some(body) => tests.push(body),
-                                None => continue,
-                            }
+                        Rule::test => match UnitTest::new(pair, context) {
+                            Some(test) => tests.push(test),
+                            None => continue,
+                        },
+                        Rule::allow => {
+                            let atom = just!(Rule::atom, pair.into_inner());
+                            context.declare_allowed(Atom::new(atom));
                         }
                         _ => unreachable!(),
                     }
                 }
                 Rule::clause => {
                     context.reset_environment();
+                    let location = Location::new(&pair);
                     let pair = just!(pair.into_inner());
                     let (head, kind, body) = match pair.as_rule() {
                         Rule::fact => {
@@ -127,16 +169,27 @@ impl Module {
                     } else {
                         head.check_variables(context);
                     }
-                    context.declare_predicate(head.as_ref().clone());
+                    let handle = head.as_ref().clone();
+                    if let Some(doc) = doc {
+                        context.document_predicate(handle.clone(), doc);
+                    }
+                    context.declare_predicate(handle.clone());
+                    if previous.as_ref() != Some(&handle) {
+                        if let Some(earlier) = definitions.get(&handle) {
+                            context.lint_contiguous(location, earlier);
+                        }
+                    }
                     definitions
-                        .entry(head.as_ref().clone())
+                        .entry(handle.clone())
                         .or_default()
-                        .insert(head, kind, body);
+                        .insert(head, kind, body, location);
+                    previous = Some(handle);
                 }
                 Rule::EOI => {}
                 _ => unreachable!(),
             }
         }
+        context.locate(None);
 
         Ok(Self {
             submodules,
@@ -150,9 +203,10 @@ impl Module {
             context.resolve_scopes(module, name.clone());
         }
         for test in self.tests.iter_mut() {
-            for handle in test.handles_mut() {
+            for handle in test.body.handles_mut() {
                 if let Some(resolved) = context.resolve_handle(handle) {
-                    *handle = resolved.clone();
+                    context.referenced.insert(resolved.clone());
+                    *handle = resolved;
                 }
             }
         }
@@ -160,8 +214,19 @@ impl Module {
             .definitions
             .drain()
             .map(|(handle, mut definition)| {
-                definition.resolve_handles(|handle| context.resolve_handle(handle));
-                definition.resolve_operators(|operator| context.resolve_operator(operator));
+                definition.resolve_handles(|reference, location| {
+                    context.locate(Some(location.span()));
+                    let resolved = context.resolve_handle(reference)?;
+                    if *reference != handle {
+                        context.referenced.insert(resolved.clone());
+                    }
+                    Some(resolved)
+                });
+                definition.resolve_operators(|operator, location| {
+                    context.locate(Some(location.span()));
+                    context.resolve_operator(operator)
+                });
+                context.locate(None);
                 (
                     context.resolve_handle(&handle).unwrap_or(handle),
                     definition,
@@ -170,6 +235,15 @@ impl Module {
             .collect();
     }
 
+    pub fn lint(&self, context: &mut Context) {
+        for (name, module) in self.submodules.iter() {
+            context.lint_module(module, name.clone());
+        }
+        for (handle, definition) in self.definitions.iter() {
+            context.lint_definition(handle, definition);
+        }
+    }
+
     pub fn into_definitions(self) -> Box<dyn Iterator<Item = (Handle, Definition)>> {
         Box::new(
             self.definitions.into_iter().chain(
@@ -181,7 +255,7 @@ impl Module {
         )
     }
 
-    pub fn take_tests(&mut self) -> Vec<Body> {
+    pub fn take_tests(&mut self) -> Vec<UnitTest> {
         self.submodules
             .iter_mut()
             .flat_map(|(_, module)| module.take_tests())
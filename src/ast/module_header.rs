@@ -29,6 +29,14 @@ pub(crate) struct ModuleHeader {
     pub operator_aliases: HashMap<Atom, Vec<Scope>>,
     /// Operators defined in this module.
     pub operators: HashMap<OpKey, Operator>,
+    /// Lints which have been allowed in this module.
+    pub allowed: HashSet<Lint>,
+    /// Documentation of this module, from the comments on the directive which declares it.
+    pub doc: Option<String>,
+    /// Documentation of predicates, from the comments on their clauses and directives.
+    pub predicate_docs: HashMap<Handle, String>,
+    /// Documentation of operators, from the comments on their directives.
+    pub operator_docs: HashMap<Atom, String>,
 }
 
 macro_rules! add_lib {
@@ -57,6 +65,10 @@ impl ModuleHeader {
             aliases: Default::default(),
             operator_aliases: Default::default(),
             operators: Default::default(),
+            allowed: Default::default(),
+            doc: Default::default(),
+            predicate_docs: Default::default(),
+            operator_docs: Default::default(),
         }
     }
 
@@ -86,6 +98,14 @@ impl ModuleHeader {
             .for_each(|scope| {
                 scope.add_lib(lib.clone());
             });
+        self.predicate_docs = self
+            .predicate_docs
+            .into_iter()
+            .map(|(mut handle, doc)| {
+                handle.add_lib(lib.clone());
+                (handle, doc)
+            })
+            .collect();
         self
     }
 
@@ -126,6 +146,22 @@ impl ModuleHeader {
         self.definitions.insert(handle);
     }
 
+    pub fn insert_allowed(&mut self, lint: Lint) {
+        self.allowed.insert(lint);
+    }
+
+    pub fn insert_doc(&mut self, doc: &str) {
+        append_doc(self.doc.get_or_insert_with(String::new), doc);
+    }
+
+    pub fn insert_predicate_doc(&mut self, handle: Handle, doc: &str) {
+        append_doc(self.predicate_docs.entry(handle).or_default(), doc);
+    }
+
+    pub fn insert_operator_doc(&mut self, operator: Atom, doc: &str) {
+        append_doc(self.operator_docs.entry(operator).or_default(), doc);
+    }
+
     pub fn insert_alias(&mut self, alias: Handle, source: Handle) -> Option<(Handle, Handle)> {
         self.aliases
             .insert(alias.clone(), source)
@@ -151,10 +187,10 @@ impl ModuleHeader {
         context: &'a Context,
     ) -> crate::Result<&'a Handle> {
         match self.resolve_inner(handle, from_scope, context, &mut vec![])? {
-            None => Err(crate::Error::parse(&format!(
-                "Unresolved predicate {} in scope {}.",
-                handle, from_scope
-            ))),
+            None => Err(crate::Error::coded(
+                "unresolved_predicate",
+                format!("Unresolved predicate {} in scope {}.", handle, from_scope),
+            )),
             Some(resolved) => Ok(resolved),
         }
     }
@@ -168,13 +204,16 @@ impl ModuleHeader {
     ) -> crate::Result<Option<&'a Handle>> {
         if path.contains(&handle) {
             path.push(handle);
-            return Err(crate::Error::parse(&format!(
-                "Alias loop detected: {}",
-                path.iter_mut()
-                    .map(|handle| handle.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" -> "),
-            )));
+            return Err(crate::Error::coded(
+                "alias_loop",
+                format!(
+                    "Alias loop detected: {}",
+                    path.iter_mut()
+                        .map(|handle| handle.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                ),
+            ));
         }
         path.push(handle);
         let resolved = if let Some(resolved) = self.definitions.get(handle) {
@@ -182,70 +221,81 @@ impl ModuleHeader {
         } else if let Some(resolved) = self.natives.get(handle) {
             resolved
         } else if let Some(alias) = self.aliases.get(handle) {
+            context.use_declaration(Declaration::Alias(handle.clone()));
             if let Some(lib) = alias.library().first() {
-                let lib = context.libraries.get(&lib).unwrap();
-                let handle = lib.resolve(alias, true);
-                return Ok(handle);
+                // The library or module may not exist if the headers have not been validated,
+                // as when indexing a program with errors.
+                let library = context.libraries.get(&lib);
+                return Ok(library.and_then(|library| library.resolve(alias, true)));
             }
-            match context
-                .modules
-                .get(&alias.module())
-                .unwrap()
-                .resolve_inner(alias, &self.scope, context, path)?
-            {
+            let module = match context.modules.get(&alias.module()) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+            match module.resolve_inner(alias, &self.scope, context, path)? {
                 Some(resolved) => resolved,
                 None => return Ok(None),
             }
         } else {
-            let (mut errors, candidates) = self
+            let (mut errors, candidates, sources) = self
                 .globbed_modules()
                 .filter_map(|scope| {
                     context
                         .modules
-                        .get(scope)
-                        .unwrap()
+                        .get(scope)?
                         .resolve_like(handle, from_scope, context, path)
                         .transpose()
+                        .map(|candidate| (scope, candidate))
                 })
                 .fold(
-                    (vec![], HashSet::new()),
-                    |(mut errs, mut oks), candidate| {
+                    (vec![], HashSet::new(), vec![]),
+                    |(mut errs, mut oks, mut sources), (scope, candidate)| {
                         match candidate {
                             Ok(ok) => {
                                 oks.insert(ok);
+                                sources.push(scope);
                             }
                             Err(err) => {
                                 errs.push(err);
                             }
                         };
-                        (errs, oks)
+                        (errs, oks, sources)
                     },
                 );
 
             if candidates.len() == 1 {
+                for scope in sources {
+                    context.use_declaration(Declaration::Glob(self.scope.clone(), scope.clone()));
+                }
                 candidates.into_iter().next().unwrap()
             } else if candidates.is_empty() {
                 return errors.pop().map(Err).unwrap_or(Ok(None));
             } else {
-                return Err(crate::Error::parse(&format!(
-                    "Ambiguous reference {}. Could be referring to any of:\n{}",
-                    handle,
-                    candidates
-                        .iter()
-                        .map(|candidate| format!("\t{}", candidate))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )));
+                return Err(crate::Error::coded(
+                    "ambiguous_reference",
+                    format!(
+                        "Ambiguous reference {}. Could be referring to any of:\n{}",
+                        handle,
+                        candidates
+                            .iter()
+                            .map(|candidate| format!("\t{}", candidate))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                ));
             }
         };
 
         if self.scope >= *from_scope || self.exports.contains(handle) {
             Ok(Some(resolved))
         } else {
-            Err(crate::Error::parse(&format!(
-                "Predicate {} is not visible from scope {}.",
-                handle, from_scope
-            )))
+            Err(crate::Error::coded(
+                "invisible_predicate",
+                format!(
+                    "Predicate {} is not visible from scope {}.",
+                    handle, from_scope
+                ),
+            ))
         }
     }
 
@@ -267,10 +317,10 @@ impl ModuleHeader {
         context: &'a Context,
     ) -> crate::Result<&'a Operator> {
         match self.resolve_operator_inner(&operator, from_scope, context, &mut vec![])? {
-            None => Err(crate::Error::parse(&format!(
-                "Unresolved operator {} in scope {}.",
-                operator, from_scope
-            ))),
+            None => Err(crate::Error::coded(
+                "unresolved_operator",
+                format!("Unresolved operator {} in scope {}.", operator, from_scope),
+            )),
             Some(resolved) => Ok(resolved),
         }
     }
@@ -284,14 +334,17 @@ impl ModuleHeader {
     ) -> crate::Result<Option<&'a Operator>> {
         if path.contains(&&self.scope) {
             path.push(&self.scope);
-            return Err(crate::Error::parse(&format!(
-                "Alias loop detected for operator {}: {}",
-                operator,
-                path.iter_mut()
-                    .map(|handle| handle.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" -> "),
-            )));
+            return Err(crate::Error::coded(
+                "alias_loop",
+                format!(
+                    "Alias loop detected for operator {}: {}",
+                    operator,
+                    path.iter_mut()
+                        .map(|handle| handle.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                ),
+            ));
         }
         path.push(&self.scope);
 
@@ -301,12 +354,19 @@ impl ModuleHeader {
             let candidates = scopes
                 .iter()
                 .filter_map(|scope| {
-                    context
+                    let candidate = context
                         .modules
-                        .get(scope)
-                        .unwrap()
+                        .get(scope)?
                         .resolve_operator_inner(operator, from_scope, context, &mut path.to_vec())
-                        .transpose()
+                        .transpose();
+                    if let Some(Ok(..)) = candidate {
+                        context.use_declaration(Declaration::OperatorAlias(
+                            self.scope.clone(),
+                            operator.name(),
+                            scope.clone(),
+                        ));
+                    }
+                    candidate
                 })
                 .collect::<Result<HashSet<_>, _>>()?;
             if candidates.len() == 1 {
@@ -317,15 +377,18 @@ impl ModuleHeader {
                     None => return Ok(None),
                 }
             } else {
-                return Err(crate::Error::parse(&format!(
-                    "Ambiguous reference to operator {}. Could be referring to any of:\n{}",
-                    operator,
-                    candidates
-                        .iter()
-                        .map(|candidate| format!("\t{}", candidate))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )));
+                return Err(crate::Error::coded(
+                    "ambiguous_reference",
+                    format!(
+                        "Ambiguous reference to operator {}. Could be referring to any of:\n{}",
+                        operator,
+                        candidates
+                            .iter()
+                            .map(|candidate| format!("\t{}", candidate))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                ));
             }
         } else {
             match self.globbed_operators(operator, from_scope, context, path)? {
@@ -337,10 +400,13 @@ impl ModuleHeader {
         if self.scope >= *from_scope || self.operator_exports.contains(&operator.name()) {
             Ok(Some(resolved))
         } else {
-            Err(crate::Error::parse(&format!(
-                "Operator {} is not visible from scope {}.",
-                operator, from_scope
-            )))
+            Err(crate::Error::coded(
+                "invisible_operator",
+                format!(
+                    "Operator {} is not visible from scope {}.",
+                    operator, from_scope
+                ),
+            ))
         }
     }
 
@@ -354,12 +420,15 @@ impl ModuleHeader {
         let candidates = self
             .globbed_modules()
             .filter_map(|scope| {
-                context
+                let candidate = context
                     .modules
-                    .get(scope)
-                    .unwrap()
+                    .get(scope)?
                     .resolve_operator_inner(operator, from_scope, context, &mut path.to_vec())
-                    .transpose()
+                    .transpose();
+                if let Some(Ok(..)) = candidate {
+                    context.use_declaration(Declaration::Glob(self.scope.clone(), scope.clone()));
+                }
+                candidate
             })
             .collect::<Result<HashSet<_>, _>>()?;
         if candidates.len() == 1 {
@@ -367,15 +436,18 @@ impl ModuleHeader {
         } else if candidates.is_empty() {
             Ok(None)
         } else {
-            Err(crate::Error::parse(&format!(
-                "Ambiguous reference to operator {}. Could be referring to any of:\n{}",
-                operator,
-                candidates
-                    .iter()
-                    .map(|candidate| format!("\t{}", candidate))
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            )))
+            Err(crate::Error::coded(
+                "ambiguous_reference",
+                format!(
+                    "Ambiguous reference to operator {}. Could be referring to any of:\n{}",
+                    operator,
+                    candidates
+                        .iter()
+                        .map(|candidate| format!("\t{}", candidate))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+            ))
         }
     }
 
@@ -402,53 +474,56 @@ impl ModuleHeader {
         for module in &self.globs {
             if let Some(lib) = module.library().first() {
                 if !context.libraries.contains_key(lib) {
-                    errors.push(crate::Error::parse(&format!(
-                        "Referencing unlinked library {} in glob import {}.",
-                        lib, module,
-                    )));
+                    errors.push(crate::Error::coded(
+                        "unlinked_library",
+                        format!(
+                            "Referencing unlinked library {} in glob import {}.",
+                            lib, module,
+                        ),
+                    ));
                 }
             } else if !context.modules.contains_key(module) {
-                errors.push(crate::Error::parse(&format!(
-                    "Unresolved module {} in glob import.",
-                    module,
-                )));
+                errors.push(crate::Error::coded(
+                    "unresolved_module",
+                    format!("Unresolved module {} in glob import.", module,),
+                ));
             }
         }
         for native in &self.natives {
             if !native_handles.contains(&native) {
-                errors.push(crate::Error::parse(&format!(
-                    "Native function {} is not bound.",
-                    native,
-                )));
+                errors.push(crate::Error::coded(
+                    "unbound_native",
+                    format!("Native function {} is not bound.", native,),
+                ));
             }
             if self.definitions.contains(native) {
-                errors.push(crate::Error::parse(&format!(
-                    "Native function {} cannot also be implemented.",
-                    native,
-                )));
+                errors.push(crate::Error::coded(
+                    "native_conflict",
+                    format!("Native function {} cannot also be implemented.", native,),
+                ));
             } else if self.aliases.contains_key(native) {
-                errors.push(crate::Error::parse(&format!(
-                    "Native function {} cannot also be imported.",
-                    native,
-                )));
+                errors.push(crate::Error::coded(
+                    "native_conflict",
+                    format!("Native function {} cannot also be imported.", native,),
+                ));
             } else if self.mutables.contains(native) {
-                errors.push(crate::Error::parse(&format!(
-                    "Native function {} cannot be set as mutable.",
-                    native,
-                )));
+                errors.push(crate::Error::coded(
+                    "native_conflict",
+                    format!("Native function {} cannot be set as mutable.", native,),
+                ));
             } else if self.incompletes.contains(native) {
-                errors.push(crate::Error::parse(&format!(
-                    "Native function {} cannot be set as incomplete.",
-                    native,
-                )));
+                errors.push(crate::Error::coded(
+                    "native_conflict",
+                    format!("Native function {} cannot be set as incomplete.", native,),
+                ));
             }
         }
         for export in &self.exports {
             if self.resolve(export, &self.scope, context).is_err() {
-                errors.push(crate::Error::parse(&format!(
-                    "Exported predicate {} cannot be found.",
-                    export.head(),
-                )));
+                errors.push(crate::Error::coded(
+                    "missing_export",
+                    format!("Exported predicate {} cannot be found.", export.head(),),
+                ));
             }
         }
         for operator in self.operators.keys() {
@@ -456,10 +531,13 @@ impl ModuleHeader {
                 .resolve_operator(operator, &self.scope, context)
                 .is_err()
             {
-                errors.push(crate::Error::parse(&format!(
-                    "Predicate definition for operator {} cannot be found.",
-                    operator,
-                )));
+                errors.push(crate::Error::coded(
+                    "missing_operator_definition",
+                    format!(
+                        "Predicate definition for operator {} cannot be found.",
+                        operator,
+                    ),
+                ));
             }
         }
         for operator_export in &self.operator_exports {
@@ -468,43 +546,43 @@ impl ModuleHeader {
                     .is_ok()
             });
             if !op_exists {
-                errors.push(crate::Error::parse(&format!(
-                    "Exported operator {} cannot be found.",
-                    operator_export,
-                )));
+                errors.push(crate::Error::coded(
+                    "missing_export_op",
+                    format!("Exported operator {} cannot be found.", operator_export,),
+                ));
             }
         }
         for scopes in self.operator_aliases.values() {
             for scope in scopes {
                 if let Some(lib) = scope.library().first() {
                     if !context.libraries.contains_key(lib) {
-                        errors.push(crate::Error::parse(&format!(
-                            "Referencing unlinked library {} in import {}.",
-                            lib, scope,
-                        )));
+                        errors.push(crate::Error::coded(
+                            "unlinked_library",
+                            format!("Referencing unlinked library {} in import {}.", lib, scope,),
+                        ));
                     }
                 } else if !context.modules.contains_key(scope) {
-                    errors.push(crate::Error::parse(&format!(
-                        "Unresolved module {} in import.",
-                        scope,
-                    )));
+                    errors.push(crate::Error::coded(
+                        "unresolved_module",
+                        format!("Unresolved module {} in import.", scope,),
+                    ));
                 }
             }
         }
         for mutable in &self.mutables {
             if self.aliases.contains_key(mutable) {
-                errors.push(crate::Error::parse(&format!(
-                    "Cannot set alias {} as mutable.",
-                    mutable.head(),
-                )));
+                errors.push(crate::Error::coded(
+                    "alias_conflict",
+                    format!("Cannot set alias {} as mutable.", mutable.head(),),
+                ));
             }
         }
         for incomplete in &self.incompletes {
             if self.aliases.contains_key(incomplete) {
-                errors.push(crate::Error::parse(&format!(
-                    "Cannot set alias {} as incomplete.",
-                    incomplete.head(),
-                )));
+                errors.push(crate::Error::coded(
+                    "alias_conflict",
+                    format!("Cannot set alias {} as incomplete.", incomplete.head(),),
+                ));
             }
         }
         for definition in &self.definitions {
@@ -524,7 +602,7 @@ impl ModuleHeader {
                         message.push_str(&format!(" (aliased as {})", key.head()));
                     }
                     message.push('.');
-                    errors.push(crate::Error::parse(&message));
+                    errors.push(crate::Error::coded("conflicting_definition", message));
                 }
             }
         }
@@ -536,27 +614,35 @@ impl ModuleHeader {
             match alias.library().first() {
                 Some(library) => match context.libraries.get(&library) {
                     None => {
-                        errors.push(crate::Error::parse(&format!(
-                            "Referencing predicate {} from unlinked library {}.",
-                            alias, library,
-                        )));
+                        errors.push(crate::Error::coded(
+                            "unlinked_library",
+                            format!(
+                                "Referencing predicate {} from unlinked library {}.",
+                                alias, library,
+                            ),
+                        ));
                     }
                     Some(lib) if lib.exports(alias) => continue,
                     Some(..) => {
-                        errors.push(crate::Error::parse(&format!(
-                            "No predicate {} is exported by the library {}.",
-                            alias, library,
-                        )));
+                        errors.push(crate::Error::coded(
+                            "unexported_predicate",
+                            format!(
+                                "No predicate {} is exported by the library {}.",
+                                alias, library,
+                            ),
+                        ));
                     }
                 },
                 None => {
-                    match context.modules.get(&alias.module()).unwrap().resolve(
-                        alias,
-                        &self.scope,
-                        context,
-                    ) {
-                        Ok(..) => {}
-                        Err(error) => errors.push(error),
+                    match context.modules.get(&alias.module()) {
+                        Some(module) => match module.resolve(alias, &self.scope, context) {
+                            Ok(..) => {}
+                            Err(error) => errors.push(error),
+                        },
+                        None => errors.push(crate::Error::coded(
+                            "unresolved_module",
+                            format!("Unresolved module {} in import.", alias.module()),
+                        )),
                     }
                     let aliases = self
                         .aliases
@@ -566,15 +652,18 @@ impl ModuleHeader {
                         .collect::<Vec<_>>();
                     if aliases.len() != 1 {
                         reported.insert(alias.clone());
-                        errors.push(crate::Error::parse(&format!(
-                            "{} is aliased multiple times, as:\n\t{}",
-                            alias,
-                            aliases
-                                .into_iter()
-                                .map(|alias| format!("\t{}", alias))
-                                .collect::<Vec<_>>()
-                                .join("\n"),
-                        )));
+                        errors.push(crate::Error::coded(
+                            "duplicate_alias",
+                            format!(
+                                "{} is aliased multiple times, as:\n\t{}",
+                                alias,
+                                aliases
+                                    .into_iter()
+                                    .map(|alias| format!("\t{}", alias))
+                                    .collect::<Vec<_>>()
+                                    .join("\n"),
+                            ),
+                        ));
                     }
                 }
             }
@@ -582,3 +671,13 @@ impl ModuleHeader {
         errors
     }
 }
+
+/// Adds documentation to what has been written about something already. Documentation from
+/// separate comments (e.g. on both a clause and the directive which exports it) is kept as
+/// separate paragraphs.
+fn append_doc(docs: &mut String, doc: &str) {
+    if !docs.is_empty() {
+        docs.push_str("\n\n");
+    }
+    docs.push_str(doc);
+}
@@ -0,0 +1,41 @@
+use super::*;
+use crate::parser::Rule;
+
+/// A unit test, defined in a module by the `:- test(...)` directive.
+#[derive(Clone, Debug)]
+pub(crate) struct UnitTest {
+    /// The name of the test. Tests which are not named are named by the source code of
+    /// their body.
+    pub name: String,
+    /// The module in which the test is defined.
+    pub module: Scope,
+    pub location: Location,
+    pub body: Body,
+}
+
+impl UnitTest {
+    pub fn new(pair: crate::Pair, context: &mut Context) -> Option<Self> {
+        assert_eq!(pair.as_rule(), Rule::test);
+        let location = Location::new(&pair);
+        let mut pairs = pair.into_inner();
+        let mut pair = pairs.next().unwrap();
+        let name = match pair.as_rule() {
+            Rule::string => {
+                let name = Literal::string(pair);
+                pair = pairs.next().unwrap();
+                name
+            }
+            _ => pair
+                .as_str()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        Some(Self {
+            name,
+            module: context.current_scope.clone(),
+            location,
+            body: Body::new(pair, context)?,
+        })
+    }
+}
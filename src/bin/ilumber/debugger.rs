@@ -0,0 +1,151 @@
+use lumber::{Event, Port, Tracer};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::io::{stdin, stdout, Write};
+
+/// How far the search runs before the debugger next stops to prompt.
+#[derive(Copy, Clone, Debug)]
+enum Mode {
+    /// Stop at every event.
+    Creep,
+    /// Stop at the next event at or above the given depth, stepping over deeper calls.
+    Skip(usize),
+    /// Stop at the next event above the given depth, stepping out of the current call.
+    Out(usize),
+    /// Stop only at spy points.
+    Leap,
+    /// Do not stop until the next answer has been found.
+    Answer,
+    /// Do not stop again, as the search has been abandoned.
+    Abort,
+}
+
+const HELP: &str = "\
+    c, <enter>  creep: step into the goal
+    s           skip: step over the goal
+    o           out: step out of the current goal
+    l           leap: continue to the next spy point
+    n           next: continue to the next answer
+    b           bindings: print the variables of the question
+    a           abort: abandon the question
+    h, ?        help: print this message";
+
+/// A tracer which stops at the events of a search to let the user decide how to continue.
+#[derive(Debug)]
+pub struct Debugger {
+    spy_points: RefCell<HashSet<String>>,
+    mode: Cell<Mode>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Self {
+            spy_points: RefCell::default(),
+            mode: Cell::new(Mode::Creep),
+        }
+    }
+
+    /// Sets a spy point on a predicate, given its handle (e.g. `parent/2`).
+    pub fn spy(&self, handle: &str) {
+        self.spy_points.borrow_mut().insert(handle.to_owned());
+    }
+
+    /// Removes a spy point, returning whether there was one.
+    pub fn nospy(&self, handle: &str) -> bool {
+        self.spy_points.borrow_mut().remove(handle)
+    }
+
+    pub fn spy_points(&self) -> Vec<String> {
+        let mut spy_points: Vec<_> = self.spy_points.borrow().iter().cloned().collect();
+        spy_points.sort();
+        spy_points
+    }
+
+    /// Prepares to debug a new question: stepping from the first call, unless there are spy
+    /// points to leap to.
+    pub fn start(&self) {
+        if self.spy_points.borrow().is_empty() {
+            self.mode.set(Mode::Creep);
+        } else {
+            self.mode.set(Mode::Leap);
+        }
+    }
+
+    /// Whether the user abandoned the search for answers to the current question.
+    pub fn is_aborted(&self) -> bool {
+        matches!(self.mode.get(), Mode::Abort)
+    }
+
+    /// Resumes stepping after an answer has been found, if the user was waiting for one.
+    pub fn answered(&self) {
+        if let Mode::Answer = self.mode.get() {
+            self.mode.set(Mode::Creep);
+        }
+    }
+
+    fn stops_at(&self, event: &Event, spied: bool) -> bool {
+        match self.mode.get() {
+            Mode::Creep => true,
+            Mode::Skip(depth) => spied || event.depth() <= depth,
+            Mode::Out(depth) => spied || event.depth() < depth,
+            Mode::Leap => spied,
+            Mode::Answer | Mode::Abort => false,
+        }
+    }
+
+    fn prompt(&self, event: &Event, spied: bool) {
+        let marker = if spied { '*' } else { ' ' };
+        let indent = event.depth().saturating_sub(1) * 2;
+        loop {
+            print!("{} {:indent$}{} ? ", marker, "", event, indent = indent);
+            stdout().flush().unwrap();
+            let mut command = String::new();
+            if stdin().read_line(&mut command).unwrap() == 0 {
+                println!();
+                command = "a".to_owned();
+            }
+            let mode = match command.trim() {
+                "" | "c" => Mode::Creep,
+                "s" => match event.port() {
+                    Port::Call | Port::Redo => Mode::Skip(event.depth()),
+                    Port::Exit | Port::Fail => Mode::Creep,
+                },
+                "o" => Mode::Out(event.depth()),
+                "l" => Mode::Leap,
+                "n" => Mode::Answer,
+                "b" => {
+                    for (name, value) in event.bindings() {
+                        match value {
+                            Some(value) => println!("    {} = {}", name, value),
+                            None => println!("    {} = _", name),
+                        }
+                    }
+                    continue;
+                }
+                "a" => {
+                    event.abandon();
+                    Mode::Abort
+                }
+                "h" | "?" => {
+                    println!("{}", HELP);
+                    continue;
+                }
+                command => {
+                    println!("Unknown command {:?}. Enter h for help.", command);
+                    continue;
+                }
+            };
+            self.mode.set(mode);
+            return;
+        }
+    }
+}
+
+impl Tracer for Debugger {
+    fn trace(&self, event: &Event) {
+        let spied = self.spy_points.borrow().contains(event.handle());
+        if self.stops_at(event, spied) {
+            self.prompt(event, spied);
+        }
+    }
+}
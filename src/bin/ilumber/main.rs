@@ -0,0 +1,217 @@
+use debugger::Debugger;
+use lumber::{Lumber, Profiler, Question};
+use std::convert::TryFrom;
+use std::io::{stdout, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+mod debugger;
+
+/// Interactive Lumber (REPL)
+#[derive(structopt::StructOpt)]
+struct Opts {
+    /// Query to be run, instead of opening the REPL.
+    /// May be supplied multiple times.
+    #[structopt(short, long)]
+    query: Vec<String>,
+    /// Run in strict mode, where referencing a predicate that does not exist is an error.
+    #[structopt(long)]
+    strict: bool,
+    /// Throw instantiation errors when native functions are called with too few bound arguments.
+    #[structopt(long)]
+    check_modes: bool,
+    /// Print the clauses used to find each answer, as a tree.
+    #[structopt(long)]
+    explain: bool,
+    /// Print how often each predicate was called, and how long was spent in each, after the
+    /// answers to each question. Use with --limit to profile questions with infinitely many
+    /// answers.
+    #[structopt(long)]
+    profile: bool,
+    /// Stop looking for answers to each question once this many have been found.
+    #[structopt(long)]
+    limit: Option<usize>,
+    /// Start in debug mode, stepping through the search for answers to each question.
+    #[structopt(long)]
+    debug: bool,
+    /// The main module of your program. Otherwise, opens the REPL with only standard definitions.
+    module: Option<PathBuf>,
+}
+
+#[paw::main]
+pub fn main(opts: Opts) {
+    let builder = Lumber::builder()
+        .strict(opts.strict)
+        .check_modes(opts.check_modes);
+    let program = match opts.module {
+        Some(path) => builder.build_from_file(path),
+        None => builder.build_from_str(""),
+    };
+    let program = match program {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+    for warning in program.warnings() {
+        eprintln!("{}", warning);
+    }
+    let mut session = Session {
+        program,
+        explain: opts.explain,
+        profile: opts.profile,
+        limit: opts.limit.unwrap_or(usize::MAX),
+        debugging: opts.debug,
+        debugger: Rc::new(Debugger::new()),
+    };
+    if opts.query.is_empty() {
+        let mut query = String::new();
+        loop {
+            print!("?- ");
+            stdout().flush().unwrap();
+            let len = std::io::stdin().read_line(&mut query).unwrap();
+            if len == 0 {
+                break;
+            }
+            let query = std::mem::take(&mut query);
+            session.answer(&query);
+        }
+    } else {
+        for query in &opts.query {
+            session.answer(query);
+        }
+    }
+}
+
+struct Session<'p> {
+    program: Lumber<'p>,
+    explain: bool,
+    profile: bool,
+    limit: usize,
+    debugging: bool,
+    debugger: Rc<Debugger>,
+}
+
+impl Session<'_> {
+    fn answer(&mut self, query: &str) {
+        let query = query.trim().trim_end_matches('.');
+        if query.is_empty() {
+            return;
+        }
+        if let Some(query) = query.strip_prefix(":why") {
+            self.why(query.trim());
+            return;
+        }
+        if query == ":debug" {
+            self.debugging = !self.debugging;
+            match self.debugging {
+                true => println!("Debug mode on."),
+                false => println!("Debug mode off."),
+            }
+            return;
+        }
+        if query == ":spy" {
+            for handle in self.debugger.spy_points() {
+                println!("{}", handle);
+            }
+            return;
+        }
+        if let Some(handle) = query.strip_prefix(":spy ") {
+            self.debugger.spy(handle.trim());
+            return;
+        }
+        if let Some(handle) = query.strip_prefix(":nospy ") {
+            if !self.debugger.nospy(handle.trim()) {
+                println!("There is no spy point on {}.", handle.trim());
+            }
+            return;
+        }
+        let question = match Question::try_from(query) {
+            Ok(question) => question,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+        if self.debugging {
+            self.debug(&question);
+            return;
+        }
+        if self.profile {
+            let profiler = Rc::new(Profiler::new());
+            let answers = self.program.try_ask_traced(&question, profiler.clone());
+            self.print_answers(answers, |answer| println!("{}", answer));
+            print!("{}", profiler.profile());
+            return;
+        }
+        if self.explain {
+            let answers = self.program.try_ask_explained(&question);
+            self.print_answers(answers, |(answer, explanation)| {
+                println!("{}", answer);
+                print!("{}", explanation);
+            });
+            return;
+        }
+        let answers = self.program.try_ask(&question);
+        self.print_answers(answers, |answer| println!("{}", answer));
+    }
+
+    /// Prints the answers to a question, up to the limit, followed by the exception which
+    /// prevented further answers from being found, if one was thrown.
+    fn print_answers<T>(
+        &self,
+        answers: impl Iterator<Item = lumber::Result<T>>,
+        mut print: impl FnMut(T),
+    ) {
+        let mut answers = answers.take(self.limit).peekable();
+        if answers.peek().is_none() {
+            println!("No answer.");
+        }
+        for answer in answers {
+            match answer {
+                Ok(answer) => print(answer),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+    }
+
+    /// Answers a question while stepping through the search with the debugger, until the
+    /// user aborts it.
+    fn debug(&self, question: &Question) {
+        self.debugger.start();
+        let answers = self
+            .program
+            .try_ask_traced(question, self.debugger.clone())
+            .take_while(|_| !self.debugger.is_aborted())
+            .take(self.limit);
+        let mut answered = false;
+        for answer in answers {
+            answered = true;
+            match answer {
+                Ok(answer) => println!("{}", answer),
+                Err(error) => eprintln!("{}", error),
+            }
+            self.debugger.answered();
+        }
+        if self.debugger.is_aborted() {
+            println!("Aborted.");
+        } else if !answered {
+            println!("No answer.");
+        }
+    }
+
+    fn why(&self, query: &str) {
+        let question = match Question::try_from(query) {
+            Ok(question) => question,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+        match self.program.diagnose(&question) {
+            Some(diagnosis) => print!("{}", diagnosis),
+            None => println!("The question has an answer."),
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! A language server for Lumber, which communicates over standard input and output.
+//!
+//! If the workspace contains a `main.lumber` file (or the file named by the `main`
+//! initialization option), it is analysed as the main module of the program, along with all of
+//! its submodules. Otherwise, each file that is opened is analysed as part of the program found
+//! by [`Analysis::find_main`][], which walks up from the file to the module that includes it.
+//!
+//! Libraries such as `@core` are not read from files, so there is no definition to go to for
+//! the predicates and operators they define, though hovering over them describes them.
+//!
+//! Programs are analysed from the files on disk, so diagnostics are updated when a file is
+//! opened or saved.
+
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidOpenTextDocument, DidSaveTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, References, Request as _};
+use lsp_types::*;
+use lumber::{Analysis, CompletionKind, SourceLocation, SourceSpan};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+fn main() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..TextDocumentSyncOptions::default()
+            },
+        )),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..ServerCapabilities::default()
+    };
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let mut server = Server::new(connection, main_file(&params));
+    server.run()?;
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Finds the main module of the workspace, if it has one.
+#[allow(deprecated)]
+fn main_file(params: &InitializeParams) -> Option<PathBuf> {
+    let root = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| &folder.uri)
+        .or(params.root_uri.as_ref())
+        .and_then(|uri| uri.to_file_path().ok())?;
+    let main = params
+        .initialization_options
+        .as_ref()
+        .and_then(|options| options.get("main")?.as_str())
+        .unwrap_or("main.lumber");
+    let main = root.join(main);
+    if main.is_file() {
+        Some(main)
+    } else {
+        None
+    }
+}
+
+struct Server {
+    connection: Connection,
+    main: Option<PathBuf>,
+    /// The analysis of each program, by the path of its main module.
+    analyses: HashMap<PathBuf, Analysis>,
+    /// The files for which diagnostics have been published, by the main module of their program.
+    published: HashMap<PathBuf, HashSet<Url>>,
+}
+
+impl Server {
+    fn new(connection: Connection, main: Option<PathBuf>) -> Self {
+        Self {
+            connection,
+            main,
+            analyses: HashMap::default(),
+            published: HashMap::default(),
+        }
+    }
+
+    fn run(&mut self) -> Result<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.respond(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.notified(notification)?,
+                Message::Response(..) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn notified(&mut self, notification: Notification) -> Result<()> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                serde_json::from_value::<DidOpenTextDocumentParams>(notification.params)?
+                    .text_document
+                    .uri
+            }
+            DidSaveTextDocument::METHOD => {
+                serde_json::from_value::<DidSaveTextDocumentParams>(notification.params)?
+                    .text_document
+                    .uri
+            }
+            _ => return Ok(()),
+        };
+        if let Ok(path) = uri.to_file_path() {
+            self.analyse(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Analyses the program which a file is part of, and publishes its diagnostics.
+    fn analyse(&mut self, path: &Path) -> Result<()> {
+        let main = match self.main_of(path) {
+            Some(main) => main.to_owned(),
+            None => Analysis::find_main(path).unwrap_or_else(|_| path.to_owned()),
+        };
+        let analysis = match Analysis::from_file(&main) {
+            Ok(analysis) => analysis,
+            Err(error) => {
+                self.show_message(MessageType::ERROR, format!("{}: {}", main.display(), error))?;
+                return Ok(());
+            }
+        };
+
+        let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::default();
+        for diagnostic in analysis.diagnostics() {
+            let path = diagnostic.path().unwrap_or(&main);
+            let uri = match Url::from_file_path(path) {
+                Ok(uri) => uri,
+                Err(..) => continue,
+            };
+            let range = diagnostic
+                .span()
+                .map(|span| range(path, span))
+                .unwrap_or_default();
+            let mut message = diagnostic.message().to_owned();
+            for note in diagnostic.notes() {
+                message.push('\n');
+                message.push_str(note);
+            }
+            diagnostics.entry(uri).or_default().push(Diagnostic {
+                range,
+                severity: Some(match diagnostic.severity() {
+                    lumber::Severity::Error => DiagnosticSeverity::ERROR,
+                    lumber::Severity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                code: Some(NumberOrString::String(diagnostic.code().to_owned())),
+                source: Some("lumber".to_owned()),
+                message,
+                ..Diagnostic::default()
+            });
+        }
+
+        // Files which no longer have any diagnostics must be cleared.
+        let published = self.published.remove(&main).unwrap_or_default();
+        for uri in published {
+            diagnostics.entry(uri).or_default();
+        }
+        self.published.insert(
+            main.clone(),
+            diagnostics
+                .iter()
+                .filter(|(_, diagnostics)| !diagnostics.is_empty())
+                .map(|(uri, _)| uri.clone())
+                .collect(),
+        );
+        for (uri, diagnostics) in diagnostics {
+            let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+            let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+            self.connection.sender.send(notification.into())?;
+        }
+        self.analyses.insert(main, analysis);
+        Ok(())
+    }
+
+    /// The main module of the program which a file is part of, if it is known.
+    fn main_of(&self, path: &Path) -> Option<&Path> {
+        if let Some(main) = &self.main {
+            return Some(main);
+        }
+        self.analyses
+            .iter()
+            .find(|(_, analysis)| analysis.contains(path))
+            .map(|(main, _)| main.as_path())
+    }
+
+    fn show_message(&self, typ: MessageType, message: String) -> Result<()> {
+        let params = ShowMessageParams { typ, message };
+        let notification = Notification::new(notification::ShowMessage::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    fn respond(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        match self.handle(request) {
+            Ok(result) => Response::new_ok(id, result),
+            Err(error) => Response::new_err(
+                id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                error.to_string(),
+            ),
+        }
+    }
+
+    fn handle(&mut self, request: Request) -> Result<serde_json::Value> {
+        let value = match request.method.as_str() {
+            GotoDefinition::METHOD => {
+                let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+                let locations = self
+                    .locate(&params.text_document_position_params, Analysis::definitions)
+                    .map(GotoDefinitionResponse::Array);
+                serde_json::to_value(locations)?
+            }
+            References::METHOD => {
+                let params: ReferenceParams = serde_json::from_value(request.params)?;
+                let locations = self.locate(&params.text_document_position, Analysis::references);
+                serde_json::to_value(locations)?
+            }
+            HoverRequest::METHOD => {
+                let params: HoverParams = serde_json::from_value(request.params)?;
+                let hover = self
+                    .at(&params.text_document_position_params)
+                    .and_then(|(analysis, path, line, column)| analysis.hover(path, line, column))
+                    .map(|description| Hover {
+                        contents: HoverContents::Scalar(MarkedString::String(description)),
+                        range: None,
+                    });
+                serde_json::to_value(hover)?
+            }
+            Completion::METHOD => {
+                let params: CompletionParams = serde_json::from_value(request.params)?;
+                let completions = self
+                    .at(&params.text_document_position)
+                    .map(|(analysis, path, ..)| analysis.completions(path))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|completion| CompletionItem {
+                        label: completion.name().to_owned(),
+                        kind: Some(match completion.kind() {
+                            CompletionKind::Predicate => CompletionItemKind::FUNCTION,
+                            CompletionKind::Operator => CompletionItemKind::OPERATOR,
+                            CompletionKind::Module => CompletionItemKind::MODULE,
+                        }),
+                        detail: Some(completion.detail().to_owned()),
+                        ..CompletionItem::default()
+                    })
+                    .collect();
+                serde_json::to_value(CompletionResponse::Array(completions))?
+            }
+            _ => return Err(format!("Unsupported request {}", request.method).into()),
+        };
+        Ok(value)
+    }
+
+    /// Finds the analysis of the program containing a document, along with the path of the
+    /// document and the position within it, as understood by the analysis.
+    fn at(
+        &self,
+        position: &TextDocumentPositionParams,
+    ) -> Option<(&Analysis, PathBuf, usize, usize)> {
+        let path = position.text_document.uri.to_file_path().ok()?;
+        let analysis = self.analyses.get(self.main_of(&path)?)?;
+        let line = position.position.line as usize;
+        let text = line_of(&path, line).unwrap_or_default();
+        let column = column(&text, position.position.character);
+        Some((analysis, path, line + 1, column))
+    }
+
+    fn locate(
+        &self,
+        position: &TextDocumentPositionParams,
+        find: fn(&Analysis, PathBuf, usize, usize) -> Vec<SourceLocation>,
+    ) -> Option<Vec<Location>> {
+        let (analysis, path, line, column) = self.at(position)?;
+        let locations = find(analysis, path, line, column)
+            .into_iter()
+            .filter_map(|location| {
+                Some(Location::new(
+                    Url::from_file_path(location.path()).ok()?,
+                    range(location.path(), location.span()),
+                ))
+            })
+            .collect();
+        Some(locations)
+    }
+}
+
+/// The text of a line of a file, counted from 0.
+fn line_of(path: &Path, line: usize) -> Option<String> {
+    let source = std::fs::read_to_string(path).ok()?;
+    source.lines().nth(line).map(ToOwned::to_owned)
+}
+
+/// Converts a position in a line given in UTF-16 code units (as in LSP) to a column counted in
+/// characters from 1 (as in Lumber).
+fn column(text: &str, character: u32) -> usize {
+    let mut units = 0;
+    for (index, ch) in text.chars().enumerate() {
+        if units >= character as usize {
+            return index + 1;
+        }
+        units += ch.len_utf16();
+    }
+    text.chars().count() + 1
+}
+
+/// Converts a column counted in characters from 1 (as in Lumber) to a position in a line given
+/// in UTF-16 code units (as in LSP).
+fn character(text: &str, column: usize) -> u32 {
+    text.chars()
+        .take(column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum::<usize>() as u32
+}
+
+fn range(path: &Path, span: SourceSpan) -> Range {
+    let source = std::fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = source.lines().collect();
+    let position = |line: usize, column: usize| {
+        let text = lines
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
+        Position::new(line.saturating_sub(1) as u32, character(text, column))
+    };
+    Range::new(
+        position(span.start_line(), span.start_column()),
+        position(span.end_line(), span.end_column()),
+    )
+}
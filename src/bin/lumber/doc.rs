@@ -0,0 +1,226 @@
+use lumber::{Associativity, ModuleInfo, OperatorInfo, PredicateInfo};
+use std::fmt::Write;
+
+/// Renders the API reference of a program as Markdown: the module tree, followed by the
+/// public predicates and operators of each module.
+pub fn markdown(modules: &[ModuleInfo]) -> String {
+    let mut output = String::from("# API reference\n\n");
+    for (depth, module) in tree(modules) {
+        writeln!(
+            output,
+            "{}- [`{}`](#{})",
+            "  ".repeat(depth),
+            module.path(),
+            anchor(module.path()),
+        )
+        .unwrap();
+    }
+    for (_, module) in tree(modules) {
+        write!(
+            output,
+            "\n<a id=\"{}\"></a>\n\n## Module `{}`\n",
+            anchor(module.path()),
+            module.path(),
+        )
+        .unwrap();
+        if let Some(doc) = module.doc() {
+            write!(output, "\n{}\n", doc).unwrap();
+        }
+        let predicates = public_predicates(module);
+        if !predicates.is_empty() {
+            output.push_str("\n### Predicates\n");
+            for predicate in predicates {
+                write!(output, "\n#### `{}`\n", predicate.handle()).unwrap();
+                if let Some(attributes) = attributes(predicate) {
+                    write!(output, "\n*{}*\n", attributes).unwrap();
+                }
+                if let Some(doc) = predicate.doc() {
+                    write!(output, "\n{}\n", doc).unwrap();
+                }
+            }
+        }
+        let operators = public_operators(module);
+        if !operators.is_empty() {
+            output.push_str("\n### Operators\n");
+            for operator in operators {
+                write!(output, "\n#### `{}`\n", operator.name()).unwrap();
+                let description = describe(operator, |handle| format!("`{}`", handle));
+                write!(output, "\n{}\n", description).unwrap();
+                if let Some(doc) = operator.doc() {
+                    write!(output, "\n{}\n", doc).unwrap();
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Renders the API reference of a program as a standalone HTML page, with the same content as
+/// the Markdown.
+pub fn html(modules: &[ModuleInfo]) -> String {
+    let mut output = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API reference</title>\n</head>\n<body>\n<h1>API reference</h1>\n<ul>\n",
+    );
+    let tree = tree(modules);
+    for (index, (depth, module)) in tree.iter().enumerate() {
+        write!(
+            output,
+            "<li><a href=\"#{}\"><code>{}</code></a>",
+            anchor(module.path()),
+            escape(module.path()),
+        )
+        .unwrap();
+        // Submodules are listed within their parent's item, so items are closed only once the
+        // next module is no deeper than this one.
+        let next = tree.get(index + 1).map_or(0, |(depth, _)| *depth);
+        if next > *depth {
+            output.push_str("\n<ul>\n");
+        } else {
+            output.push_str("</li>\n");
+            output.push_str(&"</ul>\n</li>\n".repeat(depth - next));
+        }
+    }
+    output.push_str("</ul>\n");
+    for (_, module) in tree {
+        writeln!(
+            output,
+            "<h2 id=\"{}\">Module <code>{}</code></h2>",
+            anchor(module.path()),
+            escape(module.path()),
+        )
+        .unwrap();
+        if let Some(doc) = module.doc() {
+            paragraphs(doc, &mut output);
+        }
+        let predicates = public_predicates(module);
+        if !predicates.is_empty() {
+            output.push_str("<h3>Predicates</h3>\n");
+            for predicate in predicates {
+                writeln!(
+                    output,
+                    "<h4><code>{}</code></h4>",
+                    escape(predicate.handle())
+                )
+                .unwrap();
+                if let Some(attributes) = attributes(predicate) {
+                    writeln!(output, "<p><em>{}</em></p>", attributes).unwrap();
+                }
+                if let Some(doc) = predicate.doc() {
+                    paragraphs(doc, &mut output);
+                }
+            }
+        }
+        let operators = public_operators(module);
+        if !operators.is_empty() {
+            output.push_str("<h3>Operators</h3>\n");
+            for operator in operators {
+                writeln!(output, "<h4><code>{}</code></h4>", escape(operator.name())).unwrap();
+                let description = describe(operator, |handle| {
+                    format!("<code>{}</code>", escape(handle))
+                });
+                writeln!(output, "<p>{}</p>", description).unwrap();
+                if let Some(doc) = operator.doc() {
+                    paragraphs(doc, &mut output);
+                }
+            }
+        }
+    }
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+/// Lists the modules in the order of a depth first walk of the module tree, starting from the
+/// main module, along with the depth of each.
+fn tree(modules: &[ModuleInfo]) -> Vec<(usize, &ModuleInfo)> {
+    fn walk<'a>(
+        modules: &'a [ModuleInfo],
+        path: &str,
+        depth: usize,
+        tree: &mut Vec<(usize, &'a ModuleInfo)>,
+    ) {
+        if let Some(module) = modules.iter().find(|module| module.path() == path) {
+            tree.push((depth, module));
+            for submodule in module.submodules() {
+                walk(modules, submodule, depth + 1, tree);
+            }
+        }
+    }
+    let mut tree = vec![];
+    walk(modules, "~", 0, &mut tree);
+    tree
+}
+
+fn public_predicates(module: &ModuleInfo) -> Vec<&PredicateInfo> {
+    module
+        .predicates()
+        .iter()
+        .filter(|predicate| predicate.is_public())
+        .collect()
+}
+
+fn public_operators(module: &ModuleInfo) -> Vec<&OperatorInfo> {
+    module
+        .operators()
+        .iter()
+        .filter(|operator| operator.is_public())
+        .collect()
+}
+
+fn attributes(predicate: &PredicateInfo) -> Option<String> {
+    let attributes: Vec<_> = [
+        (predicate.is_native(), "native"),
+        (predicate.is_mutable(), "mutable"),
+        (predicate.is_incomplete(), "incomplete"),
+    ]
+    .iter()
+    .filter(|(applies, _)| *applies)
+    .map(|(_, attribute)| *attribute)
+    .collect();
+    if attributes.is_empty() {
+        None
+    } else {
+        Some(attributes.join(", "))
+    }
+}
+
+fn describe(operator: &OperatorInfo, code: impl Fn(&str) -> String) -> String {
+    let kind = if operator.is_unary() {
+        "Prefix"
+    } else {
+        "Binary"
+    };
+    let handle = code(operator.handle());
+    match (operator.associativity(), operator.precedence()) {
+        (Some(associativity), Some(precedence)) => {
+            let associativity = match associativity {
+                Associativity::Left => "left",
+                Associativity::Right => "right",
+            };
+            format!(
+                "{} operator for {}, {} associative with precedence {}.",
+                kind, handle, associativity, precedence,
+            )
+        }
+        _ => format!("{} relation operator for {}.", kind, handle),
+    }
+}
+
+fn anchor(path: &str) -> String {
+    if path == "~" {
+        return "main".to_owned();
+    }
+    format!("module-{}", path.replace("::", "-"))
+}
+
+fn paragraphs(doc: &str, output: &mut String) {
+    for paragraph in doc.split("\n\n") {
+        writeln!(output, "<p>{}</p>", escape(paragraph)).unwrap();
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,213 @@
+use lumber::{Analysis, Lumber, Severity};
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
+
+mod doc;
+
+/// Tools for working with Lumber source code.
+#[derive(structopt::StructOpt)]
+enum Opts {
+    /// Format Lumber source files in the canonical style.
+    Fmt {
+        /// Check whether the files are formatted, without changing them. Exits with an error
+        /// if any are not.
+        #[structopt(long)]
+        check: bool,
+        /// The files to format, in place. Otherwise, formats standard input to standard output.
+        files: Vec<PathBuf>,
+    },
+    /// Generate an API reference for a program, from the documentation comments of its public
+    /// predicates and operators.
+    Doc {
+        /// Generate an HTML page, rather than Markdown.
+        #[structopt(long)]
+        html: bool,
+        /// The file to write the reference to. Otherwise, it is written to standard output.
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+        /// The main module of the program.
+        file: PathBuf,
+    },
+    /// Run the unit tests of a program, defined by its `:- test(...)` directives.
+    Test {
+        /// Write the report in the Test Anything Protocol (TAP) format.
+        #[structopt(long, conflicts_with = "junit")]
+        tap: bool,
+        /// Write the report as JUnit XML.
+        #[structopt(long)]
+        junit: bool,
+        /// The file to write the report to. Otherwise, it is written to standard output.
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+        /// Run only the tests in this module (e.g. `animals::cats`) and its submodules.
+        #[structopt(long)]
+        module: Option<String>,
+        /// The time, in milliseconds, after which each test is abandoned and fails.
+        #[structopt(long)]
+        timeout: Option<u64>,
+        /// The main module of the program.
+        file: PathBuf,
+        /// Run only the tests whose names contain this text.
+        filter: Option<String>,
+    },
+}
+
+#[paw::main]
+pub fn main(opts: Opts) {
+    match opts {
+        Opts::Fmt { check, files } => fmt(check, files),
+        Opts::Doc { html, output, file } => doc(html, output, file),
+        Opts::Test {
+            tap,
+            junit,
+            output,
+            module,
+            timeout,
+            file,
+            filter,
+        } => test(tap, junit, output, module, timeout, file, filter),
+    }
+}
+
+fn fmt(check: bool, files: Vec<PathBuf>) {
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(error) = stdin().read_to_string(&mut source) {
+            eprintln!("{}", error);
+            exit(1);
+        }
+        match lumber::format(&source) {
+            Ok(formatted) if check && formatted != source => {
+                eprintln!("Standard input is not formatted.");
+                exit(1);
+            }
+            Ok(..) if check => {}
+            Ok(formatted) => print!("{}", formatted),
+            Err(error) => {
+                eprintln!("{}", error);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut failed = false;
+    for path in files {
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("{}: {}", path.display(), error);
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match lumber::format(&source) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                eprintln!("{}: {}", path.display(), error);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            eprintln!("{} is not formatted.", path.display());
+            failed = true;
+        } else if let Err(error) = fs::write(&path, formatted) {
+            eprintln!("{}: {}", path.display(), error);
+            failed = true;
+        }
+    }
+    if failed {
+        exit(1);
+    }
+}
+
+fn doc(html: bool, output: Option<PathBuf>, file: PathBuf) {
+    let analysis = match Analysis::from_file(&file) {
+        Ok(analysis) => analysis,
+        Err(error) => {
+            eprintln!("{}: {}", file.display(), error);
+            exit(1);
+        }
+    };
+    let errors: Vec<_> = analysis
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+        .collect();
+    if !errors.is_empty() {
+        for error in errors {
+            eprintln!("{}", error);
+        }
+        exit(1);
+    }
+    let modules = analysis.modules();
+    let reference = if html {
+        doc::html(&modules)
+    } else {
+        doc::markdown(&modules)
+    };
+    match output {
+        Some(path) => {
+            if let Err(error) = fs::write(&path, reference) {
+                eprintln!("{}: {}", path.display(), error);
+                exit(1);
+            }
+        }
+        None => print!("{}", reference),
+    }
+}
+
+fn test(
+    tap: bool,
+    junit: bool,
+    output: Option<PathBuf>,
+    module: Option<String>,
+    timeout: Option<u64>,
+    file: PathBuf,
+    filter: Option<String>,
+) {
+    let program = match Lumber::from_file(&file) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}: {}", file.display(), error);
+            exit(1);
+        }
+    };
+    let mut runner = program.tests();
+    if let Some(module) = module {
+        runner = runner.module(module);
+    }
+    if let Some(filter) = filter {
+        runner = runner.filter(filter);
+    }
+    if let Some(timeout) = timeout {
+        runner = runner.timeout(Duration::from_millis(timeout));
+    }
+    let report = runner.run();
+    let formatted = if tap {
+        report.tap()
+    } else if junit {
+        report.junit()
+    } else {
+        report.to_string()
+    };
+    match output {
+        Some(path) => {
+            if let Err(error) = fs::write(&path, formatted) {
+                eprintln!("{}: {}", path.display(), error);
+                exit(1);
+            }
+        }
+        None => print!("{}", formatted),
+    }
+    if !report.is_success() {
+        exit(1);
+    }
+}
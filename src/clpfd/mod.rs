@@ -0,0 +1,61 @@
+//! Implementation of the Lumber @clpfd library, for constraint logic programming over finite
+//! domains of integers.
+
+use crate::program::Builtin;
+use crate::{Lumber, LumberBuilder};
+use std::path::PathBuf;
+
+fn builder() -> LumberBuilder<'static> {
+    Lumber::builder()
+        .builtin("in/3", Builtin::In)
+        .builtin("scalar_product/4", Builtin::ScalarProduct)
+        .builtin("all_different/1", Builtin::AllDifferent)
+        .builtin("labeling/2", Builtin::Labeling)
+        .builtin("fd_inf/2", Builtin::FdInf)
+        .builtin("fd_sup/2", Builtin::FdSup)
+        .builtin("fd_size/2", Builtin::FdSize)
+}
+
+thread_local! {
+    static LIB: Lumber<'static> = builder()
+        .build(PathBuf::from(file!()).parent().unwrap(), include_str!("clpfd.lumber"))
+        .unwrap();
+}
+
+/// The `@clpfd` library, which constrains integer variables to finite domains. Constraints are
+/// checked as soon as they are posted, and again whenever any of their variables are bound or
+/// narrowed, so that a search can fail long before every variable has a value.
+///
+/// The library is not included by default, and should be linked under the name `clpfd`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use lumber::Lumber;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let lumber = Lumber::builder()
+///     .link("clpfd", lumber::clpfd::library())
+///     .build_from_str(r#"
+///         :- use(@clpfd).
+///         :- pub(pair/2).
+///         pair(A, B) :- ins([A, B], 1, 3), A $< B, label([A, B]).
+///     "#)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn library() -> Lumber<'static> {
+    LIB.with(Lumber::clone)
+}
+
+#[cfg(test)]
+#[test]
+fn lumber_clpfd_tests() {
+    let result = builder().test(true).build(
+        PathBuf::from(file!()).parent().unwrap(),
+        include_str!("clpfd.lumber"),
+    );
+
+    if let Err(error) = result {
+        panic!("{}", error);
+    }
+}
@@ -0,0 +1,649 @@
+//! Implementation of the numeric built-ins of the Lumber @core library.
+//!
+//! Integers and rationals form a small numeric tower: operations on two integers produce an
+//! integer whenever the result is exactly representable as one, and any operation involving
+//! a rational produces a rational. Run in reverse, division only finds the operands which
+//! would divide to exactly the given result, integer or rational.
+
+use crate::program::exception::type_error;
+use crate::Value;
+use ramp::{int::Int, rational::Rational};
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A numeric value, either an integer or rational.
+#[derive(Clone, Debug)]
+pub(super) enum Number {
+    Integer(Int),
+    Rational(Rational),
+}
+
+impl Number {
+    fn from_value(value: &Option<Value>) -> Option<Self> {
+        match value {
+            Some(Value::Integer(int)) => Some(Number::Integer(int.clone())),
+            Some(Value::Rational(rat)) => Some(Number::Rational(rat.clone())),
+            _ => None,
+        }
+    }
+
+    fn into_rational(self) -> Rational {
+        match self {
+            Number::Integer(int) => Rational::from(int),
+            Number::Rational(rat) => rat,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Number::Integer(int) => int.sign() == 0,
+            Number::Rational(rat) => rat.sign() == 0,
+        }
+    }
+
+    fn abs(self) -> Self {
+        match self {
+            Number::Integer(int) => Number::Integer(int.abs()),
+            Number::Rational(rat) => Number::Rational(rat.abs()),
+        }
+    }
+
+    /// Whether two numbers are the same kind of number, with the same value, as is required for
+    /// them to unify.
+    fn is_identical(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Number::Integer(lhs), Number::Integer(rhs)) => lhs == rhs,
+            (Number::Rational(lhs), Number::Rational(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
+    /// Exact division. The quotient of two integers is an integer only if the division has no
+    /// remainder. Division by zero has no result.
+    fn divide(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        match (self, rhs) {
+            (Number::Integer(lhs), Number::Integer(rhs)) => {
+                let (quotient, remainder) = lhs.divmod(&rhs);
+                if remainder.sign() == 0 {
+                    Some(Number::Integer(quotient))
+                } else {
+                    Some(Number::Rational(Rational::new(lhs, rhs)))
+                }
+            }
+            (lhs, rhs) => Some(Number::Rational(lhs.into_rational() / rhs.into_rational())),
+        }
+    }
+
+    /// Inverse of multiplication. Integers may only be divided if the result is exactly an
+    /// integer, as no integer could have been multiplied to get this result otherwise.
+    fn factor(self, rhs: Self) -> Option<Self> {
+        match (self, rhs) {
+            (Number::Integer(lhs), Number::Integer(rhs)) if rhs.sign() != 0 => {
+                let (quotient, remainder) = lhs.divmod(&rhs);
+                if remainder.sign() == 0 {
+                    Some(Number::Integer(quotient))
+                } else {
+                    None
+                }
+            }
+            (lhs, rhs) => lhs.divide(rhs),
+        }
+    }
+
+    fn pow(self, exp: &Int) -> Option<Self> {
+        let magnitude = amount(&exp.clone().abs())?;
+        let power = match self {
+            Number::Integer(base) => Number::Integer(base.pow(magnitude)),
+            Number::Rational(base) => {
+                let (numerator, denominator) = base.into_parts();
+                Number::Rational(Rational::new(
+                    numerator.pow(magnitude),
+                    denominator.pow(magnitude),
+                ))
+            }
+        };
+        if exp.sign() < 0 {
+            Number::Integer(Int::one()).divide(power)
+        } else {
+            Some(power)
+        }
+    }
+
+    /// Rounds towards negative infinity.
+    fn floor(self) -> Int {
+        match self {
+            Number::Integer(int) => int,
+            Number::Rational(rat) => {
+                let (numerator, denominator) = rat.into_parts();
+                let (quotient, remainder) = numerator.divmod(&denominator);
+                if remainder.sign() < 0 {
+                    quotient - 1
+                } else {
+                    quotient
+                }
+            }
+        }
+    }
+
+    /// Rounds towards positive infinity.
+    fn ceil(self) -> Int {
+        match self {
+            Number::Integer(int) => int,
+            Number::Rational(rat) => {
+                let (numerator, denominator) = rat.into_parts();
+                let (quotient, remainder) = numerator.divmod(&denominator);
+                if remainder.sign() > 0 {
+                    quotient + 1
+                } else {
+                    quotient
+                }
+            }
+        }
+    }
+
+    /// Rounds towards zero.
+    fn truncate(self) -> Int {
+        match self {
+            Number::Integer(int) => int,
+            Number::Rational(rat) => {
+                let (numerator, denominator) = rat.into_parts();
+                numerator / denominator
+            }
+        }
+    }
+
+    /// Rounds to the nearest integer, rounding half values away from zero.
+    fn round(self) -> Int {
+        match self {
+            Number::Integer(int) => int,
+            Number::Rational(rat) => rat.round(),
+        }
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Integer(int) => Value::Integer(int),
+            Number::Rational(rat) => Value::Rational(rat),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Number::Integer(lhs), Number::Integer(rhs)) => lhs.cmp(rhs),
+            (lhs, rhs) => lhs
+                .clone()
+                .into_rational()
+                .cmp(&rhs.clone().into_rational()),
+        }
+    }
+}
+
+macro_rules! number_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Number {
+            type Output = Number;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                match (self, rhs) {
+                    (Number::Integer(lhs), Number::Integer(rhs)) => {
+                        Number::Integer(lhs.$method(rhs))
+                    }
+                    (lhs, rhs) => {
+                        Number::Rational(lhs.into_rational().$method(rhs.into_rational()))
+                    }
+                }
+            }
+        }
+    };
+}
+
+number_op!(Add, add);
+number_op!(Sub, sub);
+number_op!(Mul, mul);
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Number::Integer(int) => Number::Integer(-int),
+            Number::Rational(rat) => Number::Rational(-rat),
+        }
+    }
+}
+
+/// Interprets a value as a non-negative amount that is small enough to be used as an exponent
+/// or shift.
+fn amount(int: &Int) -> Option<usize> {
+    if int.sign() < 0 || int.bit_length() > 32 {
+        None
+    } else {
+        Some(usize::from(int))
+    }
+}
+
+/// The first of the bound arguments which is not a number, if any.
+fn non_number(args: &[Option<Value>]) -> Option<Value> {
+    args.iter()
+        .flatten()
+        .find(|value| !value.is_integer() && !value.is_rational())
+        .cloned()
+}
+
+native_function! {
+    pub(super) fn add(lhs, rhs, out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs), Number::from_value(&out)) {
+            (Some(lhs), Some(rhs), _) => answer![lhs, rhs, lhs + rhs],
+            (Some(lhs), None, Some(out)) if rhs.is_none() => answer![lhs, out.clone() - lhs.clone(), out],
+            (None, Some(rhs), Some(out)) if lhs.is_none() => answer![out.clone() - rhs.clone(), rhs, out],
+            _ => match (lhs, rhs, out) {
+                (Some(Value::String(lhs)), Some(Value::String(rhs)), None)                                              => answer![lhs, rhs, lhs + &rhs],
+                (Some(Value::String(lhs)), None, Some(Value::String(out))) if out.starts_with(&lhs)                     => answer![lhs, &out[lhs.len()..], out],
+                (None, Some(Value::String(rhs)), Some(Value::String(out))) if out.ends_with(&rhs)                       => answer![&out[..out.len() - rhs.len()], rhs, out],
+                (Some(Value::String(lhs)), Some(Value::String(rhs)), Some(Value::String(sum))) if lhs.to_owned() + &rhs == sum => answer![lhs, rhs, sum],
+                (lhs, rhs, out) => {
+                    let args = [lhs, rhs, out];
+                    if !args.iter().flatten().all(Value::is_string) {
+                        if let Some(culprit) = non_number(&args) {
+                            throw!(type_error("number", Some(culprit)));
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn sub(lhs, rhs, out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs), Number::from_value(&out)) {
+            (Some(lhs), Some(rhs), _) => answer![lhs, rhs, lhs - rhs],
+            (Some(lhs), None, Some(out)) if rhs.is_none() => answer![lhs, lhs.clone() - out.clone(), out],
+            (None, Some(rhs), Some(out)) if lhs.is_none() => answer![out.clone() + rhs.clone(), rhs, out],
+            _ => {
+                if let Some(culprit) = non_number(&[lhs, rhs, out]) {
+                    throw!(type_error("number", Some(culprit)));
+                }
+            }
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn mul(lhs, rhs, out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs), Number::from_value(&out)) {
+            (Some(lhs), Some(rhs), _) => answer![lhs, rhs, lhs * rhs],
+            (Some(lhs), None, Some(out)) if rhs.is_none() => {
+                if lhs.is_zero() && out.is_zero() {
+                    answer![lhs, _, out];
+                } else if let Some(rhs) = out.clone().factor(lhs.clone()) {
+                    answer![lhs, rhs, out];
+                }
+            }
+            (None, Some(rhs), Some(out)) if lhs.is_none() => {
+                if rhs.is_zero() && out.is_zero() {
+                    answer![_, rhs, out];
+                } else if let Some(lhs) = out.clone().factor(rhs.clone()) {
+                    answer![lhs, rhs, out];
+                }
+            }
+            _ => {
+                if let Some(culprit) = non_number(&[lhs, rhs, out]) {
+                    throw!(type_error("number", Some(culprit)));
+                }
+            }
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn div(lhs, rhs, out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs), Number::from_value(&out)) {
+            (Some(lhs), Some(rhs), _) => {
+                if let Some(out) = lhs.clone().divide(rhs.clone()) {
+                    answer![lhs, rhs, out];
+                }
+            }
+            (Some(lhs), None, Some(out)) if rhs.is_none() => {
+                if let Some(rhs) = lhs.clone().divide(out.clone()) {
+                    if lhs.clone().divide(rhs.clone()).is_some_and(|quotient| quotient.is_identical(&out)) {
+                        answer![lhs, rhs, out];
+                    }
+                }
+            }
+            (None, Some(rhs), Some(out)) if lhs.is_none() && !rhs.is_zero() => {
+                let lhs = out.clone() * rhs.clone();
+                if lhs.clone().divide(rhs.clone()).is_some_and(|quotient| quotient.is_identical(&out)) {
+                    answer![lhs, rhs, out];
+                }
+            }
+            _ => {
+                if let Some(culprit) = non_number(&[lhs, rhs, out]) {
+                    throw!(type_error("number", Some(culprit)));
+                }
+            }
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn quot(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) if rhs.sign() != 0 => answer![lhs, rhs, lhs / rhs],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn rem(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) if rhs.sign() != 0 => answer![lhs, rhs, lhs % rhs],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn pow(base, exp, out) {
+        use crate::Value::*;
+        match (Number::from_value(&base), exp, out) {
+            (Some(base), Some(Integer(exp)), _) => {
+                if let Some(out) = base.clone().pow(&exp) {
+                    answer![base, exp, out];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn abs(value, _out) {
+        match Number::from_value(&value) {
+            Some(value) => answer![value, value.abs()],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn neg(value, out) {
+        match (Number::from_value(&value), Number::from_value(&out)) {
+            (Some(value), _) => answer![value, -value.clone()],
+            (None, Some(out)) if value.is_none() => answer![-out.clone(), out],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn min(lhs, rhs, _out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs)) {
+            (Some(lhs), Some(rhs)) => answer![lhs, rhs, std::cmp::min(lhs, rhs)],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn max(lhs, rhs, _out) {
+        match (Number::from_value(&lhs), Number::from_value(&rhs)) {
+            (Some(lhs), Some(rhs)) => answer![lhs, rhs, std::cmp::max(lhs, rhs)],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn floor(value, _out) {
+        match Number::from_value(&value) {
+            Some(value) => answer![value, value.floor()],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn ceil(value, _out) {
+        match Number::from_value(&value) {
+            Some(value) => answer![value, value.ceil()],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn round(value, _out) {
+        match Number::from_value(&value) {
+            Some(value) => answer![value, value.round()],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn truncate(value, _out) {
+        match Number::from_value(&value) {
+            Some(value) => answer![value, value.truncate()],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn to_rational(int, rat) {
+        use crate::Value::*;
+        match (int, rat) {
+            (Some(Integer(int)), _) => answer![int, ramp::rational::Rational::from(int.clone())],
+            (None, Some(Rational(rat))) => {
+                let (numerator, denominator) = rat.clone().into_parts();
+                if denominator == 1 {
+                    answer![numerator, rat];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn gcd(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => answer![lhs, rhs, lhs.gcd(&rhs)],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn shl(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => {
+                if let Some(amount) = amount(&rhs) {
+                    answer![lhs, rhs, &lhs << amount];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn shr(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => {
+                if let Some(amount) = amount(&rhs) {
+                    answer![lhs, rhs, &lhs >> amount];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn bitor(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => answer![lhs, rhs, lhs | rhs],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn bitand(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => answer![lhs, rhs, lhs & rhs],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn bitxor(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => answer![lhs, rhs, lhs ^ rhs],
+            _ => {}
+        }
+    }
+}
+
+/// Compares two values, allowing integers and rationals to be compared with each other.
+fn compare(lhs: &Option<Value>, rhs: &Option<Value>) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Some(Value::String(lhs)), Some(Value::String(rhs))) => Some(lhs.cmp(rhs)),
+        _ => Some(Number::from_value(lhs)?.cmp(&Number::from_value(rhs)?)),
+    }
+}
+
+native_function! {
+    pub(super) fn leq(lhs, rhs) {
+        match compare(&lhs, &rhs) {
+            Some(Ordering::Less) | Some(Ordering::Equal) => answer![_, _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn geq(lhs, rhs) {
+        match compare(&lhs, &rhs) {
+            Some(Ordering::Greater) | Some(Ordering::Equal) => answer![_, _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn lt(lhs, rhs) {
+        match compare(&lhs, &rhs) {
+            Some(Ordering::Less) => answer![_, _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn gt(lhs, rhs) {
+        match compare(&lhs, &rhs) {
+            Some(Ordering::Greater) => answer![_, _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn between(low, high, value) {
+        use crate::Value::*;
+        let high = match high {
+            Some(Integer(high)) => Some(high),
+            Some(Struct(high)) if high.as_atom() == Some("inf") => None,
+            _ => return,
+        };
+        match (low, value) {
+            (Some(Integer(low)), Some(Integer(value))) => {
+                if low <= value && high.as_ref().map(|high| &value <= high).unwrap_or(true) {
+                    answer![_, _, _];
+                }
+            }
+            (Some(Integer(low)), None) => {
+                let mut value = low;
+                while high.as_ref().map(|high| &value <= high).unwrap_or(true) {
+                    answer![_, _, value];
+                    value += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn succ(pred, succ) {
+        use crate::Value::*;
+        match (pred, succ) {
+            (Some(Integer(pred)), _) if pred.sign() >= 0 => answer![_, pred + Int::one()],
+            (None, Some(Integer(succ))) if succ.sign() > 0 => answer![succ - Int::one(), _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn plus(lhs, rhs, out) {
+        use crate::Value::*;
+        match (lhs, rhs, out) {
+            (Some(Integer(lhs)), Some(Integer(rhs)), _) => answer![_, _, lhs + rhs],
+            (Some(Integer(lhs)), None, Some(Integer(out))) => answer![_, out - lhs, _],
+            (None, Some(Integer(rhs)), Some(Integer(out))) => answer![out - rhs, _, _],
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn nat(value) {
+        use crate::Value::*;
+        match value {
+            Some(Integer(value)) if value.sign() >= 0 => answer![_],
+            None => {
+                let mut value = Int::zero();
+                loop {
+                    answer![value];
+                    value += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Implementation of the native parts of the Lumber @core::list module.
+
+use crate::{List, Value};
+
+native_function! {
+    pub(super) fn sort(list, _sorted) {
+        match list {
+            Some(Value::List(list)) if list.complete => {
+                let mut sorted = list.values.clone();
+                sorted.sort();
+                answer![list, List::new(sorted)];
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn sort_unique(list, _sorted) {
+        match list {
+            Some(Value::List(list)) if list.complete => {
+                let mut sorted = list.values.clone();
+                sorted.sort();
+                sorted.dedup();
+                answer![list, List::new(sorted)];
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn sort_by_key(list, _sorted) {
+        match list {
+            Some(Value::List(list)) if list.complete => {
+                let keys = list
+                    .iter()
+                    .map(|pair| pair?.as_list().filter(|pair| pair.complete && pair.len() == 2))
+                    .map(|pair| pair.map(|pair| pair[0].clone()))
+                    .collect::<Option<Vec<_>>>();
+                if let Some(keys) = keys {
+                    let mut sorted: Vec<_> = keys.into_iter().zip(list.values.iter().cloned()).collect();
+                    sorted.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+                    let sorted = sorted.into_iter().map(|(_, pair)| pair).collect();
+                    answer![list, List::new(sorted)];
+                }
+            }
+            _ => {}
+        }
+    }
+}
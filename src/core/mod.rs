@@ -3,165 +3,68 @@
 
 #![allow(clippy::single_match)]
 
-use crate::Lumber;
+use crate::program::exception::instantiation_error;
+use crate::program::Builtin;
+use crate::{Lumber, LumberBuilder};
 use std::path::PathBuf;
 
-native_function! {
-    fn add(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None)   => answer![lhs, rhs, lhs + rhs],
-            (Some(Integer(lhs)), Some(Rational(rhs)), None)  => answer![lhs, rhs, lhs + rhs],
-            (Some(Rational(lhs)), Some(Integer(rhs)), None)  => answer![lhs, rhs, lhs + rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs)), None) => answer![lhs, rhs, lhs + rhs],
-
-            (Some(Integer(lhs)), None, Some(Integer(out)))   => answer![lhs, out.clone() - lhs, out],
-            (Some(Integer(lhs)), None, Some(Rational(out)))  => answer![lhs, out.clone() - ramp::rational::Rational::from(lhs), out],
-            (Some(Rational(lhs)), None, Some(Integer(out)))  => answer![lhs, ramp::rational::Rational::from(out.clone()) - lhs, out],
-            (Some(Rational(lhs)), None, Some(Rational(out))) => answer![lhs, out.clone() - lhs, out],
-
-            (None, Some(Integer(rhs)), Some(Integer(out)))   => answer![out.clone() - rhs.clone(), rhs, out],
-            (None, Some(Integer(rhs)), Some(Rational(out)))  => answer![out.clone() - ramp::rational::Rational::from(rhs.clone()), rhs, out],
-            (None, Some(Rational(rhs)), Some(Integer(out)))  => answer![ramp::rational::Rational::from(out.clone()) - rhs.clone(), rhs, out],
-            (None, Some(Rational(rhs)), Some(Rational(out))) => answer![out.clone() - rhs.clone(), rhs, out],
-
-            (Some(Integer(lhs)), Some(Integer(rhs)), Some(Integer(sum)))    if &lhs + &rhs == sum           => answer![lhs, rhs, sum],
-            (Some(Integer(lhs)), Some(Rational(rhs)), Some(Rational(sum)))  if &lhs + &rhs == sum           => answer![lhs, rhs, sum],
-            (Some(Rational(lhs)), Some(Integer(rhs)), Some(Rational(sum)))  if &lhs + &rhs == sum           => answer![lhs, rhs, sum],
-            (Some(Rational(lhs)), Some(Rational(rhs)), Some(Rational(sum))) if &lhs + &rhs == sum           => answer![lhs, rhs, sum],
-
-            (Some(String(lhs)), Some(String(rhs)), None)                                              => answer![lhs, rhs, lhs + &rhs],
-            (Some(String(lhs)), None, Some(String(out))) if out.starts_with(&lhs)                     => answer![lhs, &out[lhs.len()..], out],
-            (None, Some(String(rhs)), Some(String(out))) if out.ends_with(&rhs)                       => answer![&out[..out.len() - rhs.len()], rhs, out],
-            (Some(String(lhs)), Some(String(rhs)), Some(String(sum))) if lhs.to_owned() + &rhs == sum => answer![lhs, rhs, sum],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn sub(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None)   => answer![lhs, rhs, lhs - rhs],
-            (Some(Integer(lhs)), Some(Rational(rhs)), None)  => answer![lhs, rhs, ramp::rational::Rational::from(lhs) - rhs],
-            (Some(Rational(lhs)), Some(Integer(rhs)), None)  => answer![lhs, rhs, lhs - ramp::rational::Rational::from(rhs)],
-            (Some(Rational(lhs)), Some(Rational(rhs)), None) => answer![lhs, rhs, lhs - rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn mul(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None)   => answer![lhs, rhs, lhs * rhs],
-            (Some(Integer(lhs)), Some(Rational(rhs)), None)  => answer![lhs, rhs, lhs * rhs],
-            (Some(Rational(lhs)), Some(Integer(rhs)), None)  => answer![lhs, rhs, lhs * rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs)), None) => answer![lhs, rhs, lhs * rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn div(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None)   => answer![lhs, rhs, lhs / rhs],
-            (Some(Integer(lhs)), Some(Rational(rhs)), None)  => answer![lhs, rhs, lhs / rhs],
-            (Some(Rational(lhs)), Some(Integer(rhs)), None)  => answer![lhs, rhs, lhs / rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs)), None) => answer![lhs, rhs, lhs / rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn rem(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None) => answer![lhs, rhs, lhs % rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn bitor(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None) => answer![lhs, rhs, lhs | rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn bitand(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None) => answer![lhs, rhs, lhs & rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn bitxor(lhs, rhs, out) {
-        use crate::Value::*;
-        match (lhs, rhs, out) {
-            (Some(Integer(lhs)), Some(Integer(rhs)), None) => answer![lhs, rhs, lhs ^ rhs],
-            _ => {}
+mod arithmetic;
+mod list;
+mod record;
+mod r#struct;
+
+macro_rules! type_test {
+    ($name:ident, $test:expr) => {
+        native_function! {
+            fn $name(value) {
+                match value {
+                    Some(value) if $test(&value) => answer![_],
+                    _ => {}
+                }
+            }
         }
-    }
+    };
 }
 
-native_function! {
-    fn leq(lhs, rhs) {
-        use crate::Value::*;
-        match (lhs, rhs) {
-            (Some(Integer(lhs)), Some(Integer(rhs)))   if lhs <= rhs => answer![lhs, rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs))) if lhs <= rhs => answer![lhs, rhs],
-            (Some(String(lhs)), Some(String(rhs)))     if lhs <= rhs => answer![lhs, rhs],
-            _ => {}
-        }
-    }
-}
+type_test!(integer, crate::Value::is_integer);
+type_test!(rational, crate::Value::is_rational);
+type_test!(number, |value: &crate::Value| value.is_integer()
+    || value.is_rational());
+type_test!(string, crate::Value::is_string);
+type_test!(list, crate::Value::is_list);
+type_test!(record, crate::Value::is_record);
+type_test!(structure, crate::Value::is_struct);
+type_test!(opaque, crate::Value::is_any);
+type_test!(ground, crate::Value::is_ground);
 
 native_function! {
-    fn geq(lhs, rhs) {
-        use crate::Value::*;
+    fn compare(_order, lhs, rhs) {
         match (lhs, rhs) {
-            (Some(Integer(lhs)), Some(Integer(rhs)))   if lhs >= rhs => answer![lhs, rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs))) if lhs >= rhs => answer![lhs, rhs],
-            (Some(String(lhs)), Some(String(rhs)))     if lhs >= rhs => answer![lhs, rhs],
-            _ => {}
+            (Some(lhs), Some(rhs)) => {
+                let order = match lhs.cmp(&rhs) {
+                    std::cmp::Ordering::Less => "less",
+                    std::cmp::Ordering::Equal => "equal",
+                    std::cmp::Ordering::Greater => "greater",
+                };
+                answer![crate::Value::atom(order), _, _];
+            }
+            (lhs, rhs) => {
+                let positions: Vec<_> = [(2, lhs), (3, rhs)]
+                    .iter()
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(position, _)| *position)
+                    .collect();
+                throw!(instantiation_error("@core::compare/3", &positions));
+            }
         }
     }
 }
 
 native_function! {
-    fn lt(lhs, rhs) {
-        use crate::Value::*;
-        match (lhs, rhs) {
-            (Some(Integer(lhs)), Some(Integer(rhs)))   if lhs < rhs => answer![lhs, rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs))) if lhs < rhs => answer![lhs, rhs],
-            (Some(String(lhs)), Some(String(rhs)))     if lhs < rhs => answer![lhs, rhs],
-            _ => {}
-        }
-    }
-}
-
-native_function! {
-    fn gt(lhs, rhs) {
-        use crate::Value::*;
-        match (lhs, rhs) {
-            (Some(Integer(lhs)), Some(Integer(rhs)))   if lhs > rhs => answer![lhs, rhs],
-            (Some(Rational(lhs)), Some(Rational(rhs))) if lhs > rhs => answer![lhs, rhs],
-            (Some(String(lhs)), Some(String(rhs)))     if lhs > rhs => answer![lhs, rhs],
-            _ => {}
+    fn throw(exception) {
+        match exception {
+            Some(exception) => throw!(exception),
+            None => throw!(instantiation_error("@core::throw/1", &[1])),
         }
     }
 }
@@ -181,22 +84,118 @@ native_function! {
     }
 }
 
-thread_local! {
-    pub(crate) static LIB: Lumber<'static> = Lumber::builder()
+fn builder() -> LumberBuilder<'static> {
+    Lumber::builder()
         .core(false)
-        .bind("add/3", add)
-        .bind("sub/3", sub)
-        .bind("mul/3", mul)
-        .bind("div/3", div)
-        .bind("rem/3", rem)
-        .bind("bitor/3", bitor)
-        .bind("bitand/3", bitand)
-        .bind("bitxor/3", bitxor)
-        .bind("leq/2", leq)
-        .bind("geq/2", geq)
-        .bind("lt/2", lt)
-        .bind("gt/2", gt)
+        .bind("add/3", arithmetic::add)
+        .bind("sub/3", arithmetic::sub)
+        .bind("mul/3", arithmetic::mul)
+        .bind("div/3", arithmetic::div)
+        .bind("quot/3", arithmetic::quot)
+        .bind("rem/3", arithmetic::rem)
+        .bind("pow/3", arithmetic::pow)
+        .bind("abs/2", arithmetic::abs)
+        .bind("neg/2", arithmetic::neg)
+        .bind("min/3", arithmetic::min)
+        .bind("max/3", arithmetic::max)
+        .bind("floor/2", arithmetic::floor)
+        .bind("ceil/2", arithmetic::ceil)
+        .bind("round/2", arithmetic::round)
+        .bind("truncate/2", arithmetic::truncate)
+        .bind("to_rational/2", arithmetic::to_rational)
+        .bind("gcd/3", arithmetic::gcd)
+        .bind("shl/3", arithmetic::shl)
+        .bind("shr/3", arithmetic::shr)
+        .bind("bitor/3", arithmetic::bitor)
+        .bind("bitand/3", arithmetic::bitand)
+        .bind("bitxor/3", arithmetic::bitxor)
+        .bind("leq/2", arithmetic::leq)
+        .bind("geq/2", arithmetic::geq)
+        .bind("lt/2", arithmetic::lt)
+        .bind("gt/2", arithmetic::gt)
+        .bind("between/3", arithmetic::between)
+        .bind("succ/2", arithmetic::succ)
+        .bind("plus/3", arithmetic::plus)
+        .bind("nat/1", arithmetic::nat)
+        .bind("throw/1", throw)
+        .builtin("catch/3", Builtin::Catch)
+        .builtin("call/1", Builtin::Call)
+        .builtin("call/2", Builtin::Call)
+        .builtin("call/3", Builtin::Call)
+        .builtin("call/4", Builtin::Call)
+        .builtin("freeze/2", Builtin::Freeze)
+        .builtin("dif/2", Builtin::Dif)
         .bind("print/1", print)
+        .bind("compare/3", compare)
+        .bind("integer/1", integer)
+        .bind("rational/1", rational)
+        .bind("number/1", number)
+        .bind("string/1", string)
+        .bind("list/1", list)
+        .bind("record/1", record)
+        .bind("struct/1", structure)
+        .bind("opaque/1", opaque)
+        .bind("ground/1", ground)
+        .bind("list::sort/2", list::sort)
+        .bind("list::sort_unique/2", list::sort_unique)
+        .bind("list::sort_by_key/2", list::sort_by_key)
+        .bind("record::keys/2", record::keys)
+        .bind("record::get/3", record::get)
+        .bind("record::put/4", record::put)
+        .bind("record::delete/3", record::delete)
+        .bind("record::merge/3", record::merge)
+        .bind("record::pairs/2", record::pairs)
+        .bind("struct::name/2", r#struct::name)
+        .bind("struct::contents/2", r#struct::contents)
+        .bind("struct::new/3", r#struct::new)
+        .bind("struct::atom/1", r#struct::atom)
+        .modes("add/3", &["++-", "+-+", "-++"])
+        .modes("sub/3", &["++-", "+-+", "-++"])
+        .modes("mul/3", &["++-", "+-+", "-++"])
+        .modes("div/3", &["++-", "+-+", "-++"])
+        .modes("quot/3", &["++-"])
+        .modes("rem/3", &["++-"])
+        .modes("pow/3", &["++-"])
+        .modes("abs/2", &["+-"])
+        .modes("neg/2", &["+-", "-+"])
+        .modes("min/3", &["++-"])
+        .modes("max/3", &["++-"])
+        .modes("floor/2", &["+-"])
+        .modes("ceil/2", &["+-"])
+        .modes("round/2", &["+-"])
+        .modes("truncate/2", &["+-"])
+        .modes("to_rational/2", &["+-", "-+"])
+        .modes("gcd/3", &["++-"])
+        .modes("shl/3", &["++-"])
+        .modes("shr/3", &["++-"])
+        .modes("bitor/3", &["++-"])
+        .modes("bitand/3", &["++-"])
+        .modes("bitxor/3", &["++-"])
+        .modes("leq/2", &["++"])
+        .modes("geq/2", &["++"])
+        .modes("lt/2", &["++"])
+        .modes("gt/2", &["++"])
+        .modes("between/3", &["++-"])
+        .modes("succ/2", &["+-", "-+"])
+        .modes("plus/3", &["++-", "+-+", "-++"])
+        .modes("throw/1", &["+"])
+        .modes("compare/3", &["-++"])
+        .modes("list::sort/2", &["+-"])
+        .modes("list::sort_unique/2", &["+-"])
+        .modes("list::sort_by_key/2", &["+-"])
+        .modes("record::keys/2", &["+-"])
+        .modes("record::get/3", &["+--"])
+        .modes("record::put/4", &["++?-"])
+        .modes("record::delete/3", &["++-"])
+        .modes("record::merge/3", &["++-"])
+        .modes("record::pairs/2", &["+-", "-+"])
+        .modes("struct::name/2", &["+-"])
+        .modes("struct::contents/2", &["+-"])
+        .modes("struct::new/3", &["+?-", "--+"])
+}
+
+thread_local! {
+    pub(crate) static LIB: Lumber<'static> = builder()
         .build(PathBuf::from(file!()).parent().unwrap(), include_str!("core.lumber"))
         .unwrap();
 }
@@ -204,26 +203,10 @@ thread_local! {
 #[cfg(test)]
 #[test]
 fn lumber_core_tests() {
-    let result = Lumber::builder()
-        .core(false)
-        .bind("add/3", add)
-        .bind("sub/3", sub)
-        .bind("mul/3", mul)
-        .bind("div/3", div)
-        .bind("rem/3", rem)
-        .bind("bitor/3", bitor)
-        .bind("bitand/3", bitand)
-        .bind("bitxor/3", bitxor)
-        .bind("leq/2", leq)
-        .bind("geq/2", geq)
-        .bind("lt/2", lt)
-        .bind("gt/2", gt)
-        .bind("print/1", print)
-        .test(true)
-        .build(
-            PathBuf::from(file!()).parent().unwrap(),
-            include_str!("core.lumber"),
-        );
+    let result = builder().test(true).build(
+        PathBuf::from(file!()).parent().unwrap(),
+        include_str!("core.lumber"),
+    );
 
     if let Err(error) = result {
         println!("{}", error);
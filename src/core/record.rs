@@ -0,0 +1,140 @@
+//! Implementation of the Lumber @core::record module, providing manipulation of records using
+//! keys that are only known at runtime.
+
+use crate::ast::Atom;
+use crate::{List, Record, Value};
+
+/// Interprets a value as a record key. Keys are atoms.
+fn key(value: &Value) -> Option<Atom> {
+    let structure = value.as_struct()?;
+    if structure.is_atom() {
+        Some(structure.name.clone())
+    } else {
+        None
+    }
+}
+
+/// Interprets a value as a list of `[key, value]` pairs.
+fn from_pairs(pairs: &List) -> Option<Record> {
+    if !pairs.complete {
+        return None;
+    }
+    let mut record = Record::default();
+    for pair in pairs.iter() {
+        let pair = pair.as_ref()?.as_list()?;
+        if !pair.complete || pair.len() != 2 {
+            return None;
+        }
+        let key = key(pair[0].as_ref()?)?;
+        record.fields.insert(key, pair[1].clone());
+    }
+    Some(record)
+}
+
+native_function! {
+    pub(super) fn keys(record, _keys) {
+        match record {
+            Some(Value::Record(record)) if record.complete => {
+                let keys = record.sorted_fields()
+                    .into_iter()
+                    .map(|(key, _)| Some(Value::atom(key.as_ref())))
+                    .collect();
+                let keys = List::new(keys);
+                answer![record, keys];
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn get(record, key, _value) {
+        match (record, key) {
+            (Some(Value::Record(record)), Some(key)) => {
+                match self::key(&key).and_then(|field| record.fields.get(&field)) {
+                    Some(Some(value)) => answer![record, key, value],
+                    Some(None) => answer![record, key, _],
+                    None => {}
+                }
+            }
+            (Some(Value::Record(record)), None) if record.complete => {
+                let entries: Vec<_> = record.sorted_fields()
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                for (key, value) in entries {
+                    match value {
+                        Some(value) => answer![record, Value::atom(key.as_ref()), value],
+                        None => answer![record, Value::atom(key.as_ref()), _],
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn put(record, key, value, _output) {
+        match (record, key) {
+            (Some(Value::Record(record)), Some(key)) if record.complete => {
+                if let Some(field) = self::key(&key) {
+                    let mut updated = record.clone();
+                    updated.fields.insert(field, value);
+                    answer![record, key, _, updated];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn delete(record, key, _output) {
+        match (record, key) {
+            (Some(Value::Record(record)), Some(key)) if record.complete => {
+                if let Some(field) = self::key(&key) {
+                    let mut updated = record.clone();
+                    updated.fields.remove(&field);
+                    answer![record, key, updated];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn merge(lhs, rhs, _output) {
+        match (lhs, rhs) {
+            (Some(Value::Record(lhs)), Some(Value::Record(rhs))) if lhs.complete && rhs.complete => {
+                let mut merged = lhs.clone();
+                merged.fields.extend(rhs.fields.clone());
+                answer![lhs, rhs, merged];
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn pairs(record, pairs) {
+        match (record, pairs) {
+            (Some(Value::Record(record)), _) if record.complete => {
+                let pairs = record.sorted_fields()
+                    .into_iter()
+                    .map(|(key, value)| vec![Some(Value::atom(key.as_ref())), value.clone()])
+                    .map(|pair| Some(Value::List(List::new(pair))))
+                    .collect();
+                let pairs = List::new(pairs);
+                answer![record, pairs];
+            }
+            (None, Some(Value::List(pairs))) => {
+                if let Some(record) = from_pairs(&pairs) {
+                    answer![record, pairs];
+                }
+            }
+            _ => {}
+        }
+    }
+}
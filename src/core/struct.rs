@@ -0,0 +1,65 @@
+//! Implementation of the Lumber @core::struct module, providing introspection and construction
+//! of structs and atoms whose names are only known at runtime.
+
+use crate::{Struct, Value};
+
+/// Interprets a value as the name of a struct. Names are given as atoms, or as strings.
+fn name_of(value: &Value) -> Option<&str> {
+    match value {
+        Value::Struct(structure) => structure.as_atom(),
+        Value::String(string) if !string.is_empty() => Some(string),
+        _ => None,
+    }
+}
+
+native_function! {
+    pub(super) fn name(structure, _name) {
+        match structure {
+            Some(Value::Struct(structure)) => {
+                let name = Value::atom(structure.name());
+                answer![structure, name];
+            }
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn contents(structure, _contents) {
+        match structure {
+            Some(Value::Struct(structure)) => match structure.contents() {
+                Some(Some(contents)) => answer![structure, contents],
+                Some(None) => answer![structure, _],
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn new(name, contents, structure) {
+        match (name, structure) {
+            (Some(name), _) => {
+                if let Some(structure) = name_of(&name).map(|name| Struct::new(name, contents)) {
+                    answer![name, _, structure];
+                }
+            }
+            (None, Some(Value::Struct(structure))) => match structure.contents() {
+                Some(Some(contents)) => answer![Value::atom(structure.name()), contents, structure],
+                Some(None) => answer![Value::atom(structure.name()), _, structure],
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+native_function! {
+    pub(super) fn atom(atom) {
+        match atom {
+            Some(Value::Struct(atom)) if atom.is_atom() => answer![_],
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// How serious a problem described by a [`Diagnostic`][] is.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Severity {
+    /// The problem prevents the program from being created.
+    Error,
+    /// The problem is likely a mistake, but the program can still be created.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A range of the source code of a module. Lines and columns are counted from 1, and the end
+/// is exclusive.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SourceSpan {
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl SourceSpan {
+    pub(crate) fn new(start: (usize, usize), end: (usize, usize)) -> Self {
+        Self { start, end }
+    }
+
+    pub(crate) fn from_pest(span: pest::Span) -> Self {
+        Self::new(span.start_pos().line_col(), span.end_pos().line_col())
+    }
+
+    /// Finds the first occurrence of a word (not part of a longer word) within this span of the
+    /// source, returning the span of just that word.
+    pub(crate) fn find(&self, source: &str, word: &str) -> Option<Self> {
+        let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+        let lines = source.lines().enumerate().skip(self.start.0 - 1);
+        for (index, text) in lines.take(self.end.0 + 1 - self.start.0) {
+            let line = index + 1;
+            let chars: Vec<char> = text.chars().collect();
+            let needle: Vec<char> = word.chars().collect();
+            for column in 0..chars.len() {
+                let position = (line, column + 1);
+                if position < self.start || chars[column..].len() < needle.len() {
+                    continue;
+                }
+                let end = column + needle.len();
+                if (line, end + 1) > self.end {
+                    break;
+                }
+                if chars[column..end] == needle[..]
+                    && (column == 0 || !is_word(chars[column - 1]))
+                    && !chars.get(end).is_some_and(|ch| is_word(*ch))
+                {
+                    return Some(Self::new(position, (line, end + 1)));
+                }
+            }
+        }
+        None
+    }
+
+    /// The line on which the span starts.
+    pub fn start_line(&self) -> usize {
+        self.start.0
+    }
+
+    /// The column at which the span starts.
+    pub fn start_column(&self) -> usize {
+        self.start.1
+    }
+
+    /// The line on which the span ends.
+    pub fn end_line(&self) -> usize {
+        self.end.0
+    }
+
+    /// The column just after the end of the span.
+    pub fn end_column(&self) -> usize {
+        self.end.1
+    }
+}
+
+/// A description of a problem found in the source code of a Lumber program, locating it in
+/// the source where possible. The diagnostics of an error are retrieved with
+/// [`Error::diagnostics`][crate::Error::diagnostics].
+///
+/// Displaying a diagnostic renders it for people to read, showing the line of source code on
+/// which the problem was found with the span of the problem underlined by carets:
+///
+/// ```text
+/// error[singleton_variable]: Singleton variable B in predicate parent/2.
+///  --> family.lumber:3:11
+///   |
+/// 3 | parent(A, B) :- person(A).
+///   |           ^
+///   = help: replace B with _ if its value is not needed
+/// ```
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: &'static str,
+    message: String,
+    path: Option<PathBuf>,
+    span: Option<SourceSpan>,
+    line: Option<String>,
+    notes: Vec<String>,
+    suggestions: Vec<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new<S: Into<String>>(severity: Severity, code: &'static str, message: S) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            path: None,
+            span: None,
+            line: None,
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    pub(crate) fn error<S: Into<String>>(code: &'static str, message: S) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub(crate) fn warning<S: Into<String>>(code: &'static str, message: S) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub(crate) fn with_note<S: Into<String>>(mut self, note: S) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub(crate) fn with_suggestion<S: Into<String>>(mut self, suggestion: S) -> Self {
+        self.suggestions.push(suggestion.into());
+        self
+    }
+
+    pub(crate) fn with_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Fills in the file and span of this diagnostic, if they are not already known, from the
+    /// source code of the module in which it was found.
+    pub(crate) fn locate(&mut self, path: Option<&Path>, span: Option<SourceSpan>, source: &str) {
+        if self.path.is_none() {
+            self.path = path.map(Path::to_owned);
+        }
+        if self.span.is_none() {
+            self.span = span;
+        }
+        if self.line.is_none() {
+            if let Some(span) = self.span {
+                self.line = source.lines().nth(span.start_line() - 1).map(str::to_owned);
+            }
+        }
+    }
+
+    /// How serious the problem is.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// A short name identifying the kind of problem (e.g. `singleton_variable`), which remains
+    /// the same between releases so that it can be used to filter diagnostics.
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    /// A description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The path of the source file in which the problem was found. This is `None` if the file
+    /// is not known, such as for programs created from a source string.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The span of the source code in which the problem was found, if it is known.
+    pub fn span(&self) -> Option<SourceSpan> {
+        self.span
+    }
+
+    /// Additional information about the problem.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Suggestions of how the problem might be fixed.
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        let gutter = self
+            .span
+            .map(|span| span.start_line().to_string().len())
+            .unwrap_or(0);
+        match (&self.path, self.span) {
+            (Some(path), Some(span)) => writeln!(
+                f,
+                "{:gutter$}--> {}:{}:{}",
+                "",
+                path.display(),
+                span.start_line(),
+                span.start_column(),
+                gutter = gutter,
+            )?,
+            (Some(path), None) => writeln!(f, "--> {}", path.display())?,
+            (None, Some(span)) => writeln!(
+                f,
+                "{:gutter$}--> {}:{}",
+                "",
+                span.start_line(),
+                span.start_column(),
+                gutter = gutter,
+            )?,
+            (None, None) => {}
+        }
+        if let (Some(span), Some(line)) = (self.span, &self.line) {
+            let length = line.chars().count();
+            let start = span.start_column().min(length + 1);
+            let end = if span.end_line() == span.start_line() {
+                span.end_column().min(length + 1)
+            } else {
+                length + 1
+            };
+            writeln!(f, "{:gutter$} |", "", gutter = gutter)?;
+            writeln!(f, "{} | {}", span.start_line(), line)?;
+            writeln!(
+                f,
+                "{:gutter$} | {:indent$}{}",
+                "",
+                "",
+                "^".repeat(end.saturating_sub(start).max(1)),
+                gutter = gutter,
+                indent = start - 1,
+            )?;
+        }
+        for note in &self.notes {
+            writeln!(f, "{:gutter$} = note: {}", "", note, gutter = gutter)?;
+        }
+        for suggestion in &self.suggestions {
+            writeln!(f, "{:gutter$} = help: {}", "", suggestion, gutter = gutter)?;
+        }
+        Ok(())
+    }
+}
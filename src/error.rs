@@ -1,5 +1,6 @@
 use crate::ast::Scope;
-use crate::Question;
+use crate::{Diagnostic, SourceSpan, TestOutcome, TestReport, Value};
+use pest::error::LineColLocation;
 #[cfg(feature = "serde")]
 use serde::{de, ser};
 use std::collections::HashMap;
@@ -17,6 +18,9 @@ pub enum ErrorKind {
     Binding,
     /// One or more of the unit tests defined in the source code have failed.
     Test,
+    /// An exception was thrown while answering a question, and was not caught. The thrown value
+    /// can be retrieved with [`Error::exception`][].
+    Runtime,
     /// Contains multiple errors of various sources. This error can be printed to the user to
     /// help with debugging. This error likely cannot be handled programmatically.
     Multiple,
@@ -34,17 +38,45 @@ pub struct Error {
     pub(crate) kind: ErrorKind,
     pub(crate) message: String,
     pub(crate) source: Option<Box<dyn std::error::Error + 'static>>,
+    pub(crate) exception: Option<Value>,
+    pub(crate) diagnostics: Box<[Diagnostic]>,
 }
 
 impl Error {
+    /// The kind of error that has occurred.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The value that was thrown, if this is an [`ErrorKind::Runtime`][] error.
+    pub fn exception(&self) -> Option<&Value> {
+        self.exception.as_ref()
+    }
+
+    /// The problems found in the source code which caused this error, if it is an
+    /// [`ErrorKind::Parse`][] or [`ErrorKind::Multiple`][] error.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
     pub(crate) fn parse<S: ?Sized + ToOwned<Owned = String>>(message: &S) -> Self
     where
         String: std::borrow::Borrow<S>,
     {
+        Self::diagnostic(Diagnostic::error("parse_error", message.to_owned()))
+    }
+
+    pub(crate) fn coded<S: Into<String>>(code: &'static str, message: S) -> Self {
+        Self::diagnostic(Diagnostic::error(code, message))
+    }
+
+    pub(crate) fn diagnostic(diagnostic: Diagnostic) -> Self {
         Self {
             kind: ErrorKind::Parse,
-            message: message.to_owned(),
+            message: diagnostic.message().to_owned(),
             source: None,
+            exception: None,
+            diagnostics: Box::new([diagnostic]),
         }
     }
 
@@ -56,21 +88,45 @@ impl Error {
             kind: ErrorKind::Binding,
             message: message.to_owned(),
             source: None,
+            exception: None,
+            diagnostics: Box::default(),
         }
     }
 
-    pub(crate) fn test(tests: Vec<Question>) -> Self {
-        let count = tests.len();
-        let tests = tests
-            .into_iter()
-            .map(|question| format!("\t{}", question))
+    /// Reports the tests which have failed, along with why each did not pass.
+    pub(crate) fn test(report: &TestReport) -> Self {
+        let tests = report
+            .failures()
+            .map(|result| {
+                let mut message = format!("\t{}", result.name());
+                let reason = match result.outcome() {
+                    TestOutcome::Failed(diagnosis) => diagnosis.as_ref().map(ToString::to_string),
+                    _ => result.message(),
+                };
+                for line in reason.iter().flat_map(|reason| reason.lines()) {
+                    write!(message, "\n\t\t{}", line).unwrap();
+                }
+                message
+            })
             .collect::<Vec<_>>()
             .join("\n");
-        let message = format!("{} tests have failed:\n{}", count, tests);
+        let message = format!("{} tests have failed:\n{}", report.failed(), tests);
         Self {
             kind: ErrorKind::Test,
             message,
             source: None,
+            exception: None,
+            diagnostics: Box::default(),
+        }
+    }
+
+    pub(crate) fn runtime(exception: Value) -> Self {
+        Self {
+            kind: ErrorKind::Runtime,
+            message: format!("Uncaught exception: {}", exception),
+            source: None,
+            exception: Some(exception),
+            diagnostics: Box::default(),
         }
     }
 
@@ -80,6 +136,8 @@ impl Error {
             kind: ErrorKind::Ser,
             message: message.to_string(),
             source: None,
+            exception: None,
+            diagnostics: Box::default(),
         }
     }
 
@@ -93,27 +151,39 @@ impl Error {
                 format!("{} at {}", message, path.join(" -> "))
             },
             source: None,
+            exception: None,
+            diagnostics: Box::default(),
         }
     }
 
     pub(crate) fn multiple_by_module(errors: HashMap<Scope, Vec<Self>>) -> Self {
-        Self {
-            kind: ErrorKind::Multiple,
-            message: errors
-                .into_iter()
-                .map(|(scope, errors)| {
-                    let mut message = String::new();
-                    if errors.is_empty() {
-                        return message;
+        let mut diagnostics = vec![];
+        let message = errors
+            .into_iter()
+            .map(|(scope, errors)| {
+                let mut message = String::new();
+                if errors.is_empty() {
+                    return message;
+                }
+                writeln!(message, "-- {} errors in module {} --", errors.len(), scope).unwrap();
+                for error in errors {
+                    if error.diagnostics.is_empty() {
+                        writeln!(message, "{}", error).unwrap();
                     }
-                    writeln!(message, "-- {} errors in module {} --", errors.len(), scope).unwrap();
-                    for error in &errors {
-                        writeln!(message, "\t{}", error).unwrap();
+                    for diagnostic in error.diagnostics.iter() {
+                        writeln!(message, "{}", diagnostic).unwrap();
                     }
-                    message
-                })
-                .collect::<String>(),
+                    diagnostics.extend(error.diagnostics.into_vec());
+                }
+                message
+            })
+            .collect::<String>();
+        Self {
+            kind: ErrorKind::Multiple,
+            message,
             source: None,
+            exception: None,
+            diagnostics: diagnostics.into_boxed_slice(),
         }
     }
 }
@@ -144,6 +214,8 @@ impl de::Error for Error {
             kind: ErrorKind::De,
             message: msg.to_string(),
             source: None,
+            exception: None,
+            diagnostics: Box::default(),
         }
     }
 }
@@ -160,16 +232,25 @@ impl From<std::io::Error> for Error {
             kind: ErrorKind::Io,
             message: error.to_string(),
             source: Some(Box::new(error)),
+            exception: None,
+            diagnostics: Box::default(),
         }
     }
 }
 
 impl<R: pest::RuleType + 'static> From<pest::error::Error<R>> for Error {
     fn from(error: pest::error::Error<R>) -> Self {
+        let span = match error.line_col {
+            LineColLocation::Pos(start) => SourceSpan::new(start, (start.0, start.1 + 1)),
+            LineColLocation::Span(start, end) => SourceSpan::new(start, end),
+        };
+        let diagnostic = Diagnostic::error("syntax_error", error.variant.message()).with_span(span);
         Self {
             kind: ErrorKind::Parse,
             message: error.to_string(),
             source: Some(Box::new(error)),
+            exception: None,
+            diagnostics: Box::new([diagnostic]),
         }
     }
 }
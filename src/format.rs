@@ -0,0 +1,581 @@
+//! Formatting of Lumber source code.
+
+use crate::ast::Atom;
+use crate::parser::{self, Parser, Rule};
+use crate::Pair;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+/// The width within which a rule is kept on a single line, where it can be.
+const WIDTH: usize = 100;
+
+/// The number of spaces by which each level of a rule body is indented.
+const INDENT: usize = 4;
+
+/// The directives which are moved to the start of a group of consecutive directives, in order.
+/// Directives of the same kind are sorted by their text. Other directives are left in the order
+/// they were written, as they are usually grouped with the definitions they refer to.
+const ORDER: [Rule; 3] = [Rule::allow, Rule::mod_, Rule::use_];
+
+/// Formats the source code of a module in the canonical style, returning the formatted source.
+///
+/// *   In each group of consecutive directives (those not separated by a blank line or another
+///     statement), the `allow`, `mod`, then `use` directives are moved to the start of the
+///     group, and sorted.
+/// *   Rules with more than a single step are broken over lines, with one step per line. The
+///     `;` and `->>` operators are outdented, so that the steps they separate remain aligned.
+/// *   Operators are surrounded by single spaces, and items in lists by a single space after
+///     each comma.
+/// *   Atoms are quoted only where they must be.
+/// *   Comments are preserved, as are single blank lines between statements. Comments within a
+///     statement which are not between the steps of a rule body are moved before it.
+///
+/// # Errors
+///
+/// An error is returned if the source code does not parse.
+///
+/// # Examples
+///
+/// ```rust
+/// # fn main() -> lumber::Result<()> {
+/// let source = lumber::format("
+///     :- pub(grandparent/2).
+///     :- mod(family).
+///     grandparent(A,C):-parent(A,B),parent(B,C).
+/// ")?;
+/// assert_eq!(source, "\
+/// :- mod(family).
+/// :- pub(grandparent/2).
+/// grandparent(A, C) :-
+///     parent(A, B),
+///     parent(B, C).
+/// ");
+/// # Ok(())
+/// # }
+/// ```
+pub fn format(source: &str) -> crate::Result<String> {
+    let module = Parser::parse_module(source)?.next().unwrap();
+    let mut formatter = SourceFormatter {
+        source,
+        comments: parser::comments(source),
+        next: 0,
+    };
+
+    let mut items: Vec<Item> = vec![];
+    let mut pending: Vec<Item> = vec![];
+    let mut end = 0;
+    for statement in module.into_inner() {
+        let span = statement.as_span();
+        let start = if statement.as_rule() == Rule::EOI {
+            source.len()
+        } else {
+            span.start()
+        };
+        while let Some(comment) = formatter.take_comment(start) {
+            if end != 0 && !source[end..comment.start].contains('\n') {
+                let previous = pending.last_mut().or_else(|| items.last_mut()).unwrap();
+                previous
+                    .lines
+                    .last_mut()
+                    .unwrap()
+                    .trail(formatter.text(&comment));
+            } else {
+                pending.push(Item {
+                    blank_before: formatter.blank(end..comment.start),
+                    lines: vec![Line::comment(formatter.text(&comment))],
+                    order: None,
+                });
+            }
+            end = comment.end;
+        }
+        if statement.as_rule() == Rule::EOI {
+            break;
+        }
+
+        let mut item = formatter.statement(statement);
+        item.blank_before = formatter.blank(end..start);
+        if !item.blank_before {
+            let attached = pending
+                .iter()
+                .rposition(|comment| comment.blank_before)
+                .unwrap_or(0);
+            let leading: Vec<Item> = pending.drain(attached..).collect();
+            if let Some(first) = leading.first() {
+                item.blank_before = first.blank_before;
+            }
+            item.lines
+                .splice(0..0, leading.into_iter().flat_map(|item| item.lines));
+        }
+        items.append(&mut pending);
+        items.push(item);
+        end = span.end();
+    }
+    items.append(&mut pending);
+
+    let mut i = 0;
+    while i < items.len() {
+        let mut j = i + 1;
+        if items[i].order.is_some() {
+            while j < items.len() && items[j].order.is_some() && !items[j].blank_before {
+                j += 1;
+            }
+            let blank_before = std::mem::take(&mut items[i].blank_before);
+            items[i..j].sort_by(|lhs, rhs| lhs.order.cmp(&rhs.order));
+            items[i].blank_before = blank_before;
+        }
+        i = j;
+    }
+
+    let mut output = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 && item.blank_before {
+            output.push('\n');
+        }
+        for line in &item.lines {
+            output.push_str(&line.to_string());
+            output.push('\n');
+        }
+    }
+    Ok(output)
+}
+
+/// A top level statement or comment, as it will be written in the formatted source.
+struct Item {
+    blank_before: bool,
+    lines: Vec<Line>,
+    order: Option<(usize, String)>,
+}
+
+/// A line of formatted source, which may be followed by a comment.
+struct Line {
+    text: String,
+    trailing: Option<String>,
+    comment: bool,
+}
+
+impl Line {
+    fn new(text: String) -> Self {
+        Self {
+            text,
+            trailing: None,
+            comment: false,
+        }
+    }
+
+    fn comment(text: String) -> Self {
+        Self {
+            comment: true,
+            ..Self::new(text)
+        }
+    }
+
+    fn trail(&mut self, comment: String) {
+        match &mut self.trailing {
+            Some(trailing) => {
+                trailing.push(' ');
+                trailing.push_str(&comment);
+            }
+            None => self.trailing = Some(comment),
+        }
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)?;
+        if let Some(trailing) = &self.trailing {
+            write!(f, " {}", trailing)?;
+        }
+        Ok(())
+    }
+}
+
+/// The last line of some lines which is not a comment, to which punctuation may be added.
+fn last_code(lines: &mut [Line]) -> &mut Line {
+    lines.iter_mut().rev().find(|line| !line.comment).unwrap()
+}
+
+/// Replaces the indentation of the first line of code (which is at the given indent) with an
+/// operator, outdented such that the line's contents remain aligned.
+fn prefix(lines: &mut [Line], indent: usize, operator: &str) {
+    let line = lines.iter_mut().find(|line| !line.comment).unwrap();
+    line.text = format!(
+        "{:outdent$}{:width$}{}",
+        "",
+        operator,
+        &line.text[indent..],
+        outdent = indent - INDENT,
+        width = INDENT,
+    );
+}
+
+struct SourceFormatter<'a> {
+    source: &'a str,
+    comments: Vec<Range<usize>>,
+    next: usize,
+}
+
+impl SourceFormatter<'_> {
+    /// Takes the next comment, if it starts before the given position.
+    fn take_comment(&mut self, before: usize) -> Option<Range<usize>> {
+        let comment = self.comments.get(self.next)?;
+        if comment.start >= before {
+            return None;
+        }
+        self.next += 1;
+        Some(comment.clone())
+    }
+
+    fn text(&self, comment: &Range<usize>) -> String {
+        self.source[comment.clone()].trim_end().to_owned()
+    }
+
+    /// Whether there is a blank line in the given range of the source.
+    fn blank(&self, range: Range<usize>) -> bool {
+        self.source[range].matches('\n').count() > 1
+    }
+
+    /// Takes the comments before the given position, each on its own line.
+    fn comments_before(&mut self, before: usize, indent: usize) -> Vec<Line> {
+        let mut lines = vec![];
+        while let Some(comment) = self.take_comment(before) {
+            lines.push(Line::comment(format!(
+                "{:indent$}{}",
+                "",
+                self.text(&comment),
+                indent = indent,
+            )));
+        }
+        lines
+    }
+
+    /// Takes the next comment if it follows the given position on the same line, with nothing
+    /// but the punctuation between steps before it, attaching it to the last line.
+    fn trailing(&mut self, lines: &mut [Line], after: usize) {
+        let comment = match self.comments.get(self.next) {
+            Some(comment) if comment.start >= after => comment.clone(),
+            _ => return,
+        };
+        let between = self.source[after..comment.start]
+            .chars()
+            .all(|ch| ch == ',' || ch == ';' || ch == ' ' || ch == '\t');
+        if between {
+            self.next += 1;
+            lines.last_mut().unwrap().trail(self.text(&comment));
+        }
+    }
+
+    fn statement(&mut self, pair: Pair) -> Item {
+        let end = pair.as_span().end();
+        match pair.as_rule() {
+            Rule::directive => {
+                let instruction = pair.into_inner().next().unwrap();
+                let instruction = instruction.into_inner().next().unwrap();
+                let rank = ORDER
+                    .iter()
+                    .position(|rule| *rule == instruction.as_rule())
+                    .unwrap_or(ORDER.len());
+                let is_test = instruction.as_rule() == Rule::test;
+                let directive = format!(":- {}.", text(instruction));
+                let order = match rank {
+                    _ if is_test => None,
+                    rank if rank == ORDER.len() => Some((rank, String::new())),
+                    rank => Some((rank, directive.clone())),
+                };
+                let mut lines = self.comments_before(end, 0);
+                lines.push(Line::new(directive));
+                Item {
+                    blank_before: false,
+                    lines,
+                    order,
+                }
+            }
+            Rule::clause => {
+                let clause = pair.into_inner().next().unwrap();
+                let lines = match clause.as_rule() {
+                    Rule::fact => {
+                        let mut lines = self.comments_before(end, 0);
+                        lines.push(Line::new(format!("{}.", text(clause))));
+                        lines
+                    }
+                    Rule::rule => self.rule(clause),
+                    _ => unreachable!(),
+                };
+                Item {
+                    blank_before: false,
+                    lines,
+                    order: None,
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn rule(&mut self, pair: Pair) -> Vec<Line> {
+        let end = pair.as_span().end();
+        let mut pairs = pair.into_inner();
+        let head = text(pairs.next().unwrap());
+        let kind = pairs.next().unwrap();
+        let mut lines = self.comments_before(kind.as_span().start(), 0);
+        let kind = match kind.as_rule() {
+            Rule::rule_once => "::-",
+            Rule::rule_multi => ":-",
+            _ => unreachable!(),
+        };
+        let body = pairs.next().unwrap();
+        let span = body.as_span();
+        let disjunction = body.into_inner().next().unwrap();
+
+        let inline = format!("{} {} {}.", head, kind, text(disjunction.clone()));
+        let commented = self
+            .comments
+            .get(self.next)
+            .is_some_and(|comment| comment.start < span.end());
+        if is_single_step(disjunction.clone()) && !commented && inline.chars().count() <= WIDTH {
+            lines.push(Line::new(inline));
+        } else {
+            lines.push(Line::new(format!("{} {}", head, kind)));
+            lines.extend(self.disjunction(disjunction, INDENT));
+            last_code(&mut lines).text.push('.');
+        }
+        while let Some(comment) = self.take_comment(end) {
+            lines.last_mut().unwrap().trail(self.text(&comment));
+        }
+        lines
+    }
+
+    fn disjunction(&mut self, pair: Pair, indent: usize) -> Vec<Line> {
+        let mut lines = vec![];
+        for (i, branch) in pair.into_inner().enumerate() {
+            let mut branch = self.branch(branch, indent);
+            if i != 0 {
+                prefix(&mut branch, indent, ";");
+            }
+            lines.extend(branch);
+        }
+        lines
+    }
+
+    fn branch(&mut self, pair: Pair, indent: usize) -> Vec<Line> {
+        let mut pairs = pair.into_inner();
+        let mut lines = self.conjunction(pairs.next().unwrap(), indent);
+        if let Some(conjunction) = pairs.next() {
+            let mut otherwise = self.conjunction(conjunction, indent);
+            prefix(&mut otherwise, indent, "->>");
+            lines.extend(otherwise);
+        }
+        lines
+    }
+
+    fn conjunction(&mut self, pair: Pair, indent: usize) -> Vec<Line> {
+        let mut lines = vec![];
+        let processions: Vec<_> = pair.into_inner().collect();
+        let count = processions.len();
+        for (i, procession) in processions.into_iter().enumerate() {
+            let mut procession = self.procession(procession, indent);
+            if i + 1 != count {
+                last_code(&mut procession).text.push(',');
+            }
+            lines.extend(procession);
+        }
+        lines
+    }
+
+    fn procession(&mut self, pair: Pair, indent: usize) -> Vec<Line> {
+        let end = pair.as_span().end();
+        let steps: Vec<_> = pair.into_inner().collect();
+        if !steps.iter().cloned().any(is_multiline) {
+            let mut lines = self.comments_before(end, indent);
+            let steps: Vec<_> = steps.into_iter().map(text).collect();
+            lines.push(Line::new(format!(
+                "{:indent$}{}",
+                "",
+                steps.join(" -> "),
+                indent = indent,
+            )));
+            self.trailing(&mut lines, end);
+            return lines;
+        }
+
+        let mut lines = vec![];
+        for (i, step) in steps.into_iter().enumerate() {
+            if i != 0 {
+                last_code(&mut lines).text.push_str(" ->");
+            }
+            let end = step.as_span().end();
+            if is_multiline(step.clone()) {
+                let disjunction = step.into_inner().next().unwrap();
+                let mut parenthesized = self.disjunction(disjunction, indent + INDENT);
+                prefix(&mut parenthesized, indent + INDENT, "(");
+                parenthesized.push(Line::new(format!("{:indent$})", "", indent = indent)));
+                lines.extend(parenthesized);
+            } else {
+                lines.extend(self.comments_before(end, indent));
+                lines.push(Line::new(format!(
+                    "{:indent$}{}",
+                    "",
+                    text(step),
+                    indent = indent,
+                )));
+            }
+            self.trailing(&mut lines, end);
+        }
+        lines
+    }
+}
+
+/// Whether a body consists of just one step, so it may be written on the same line as the head.
+fn is_single_step(disjunction: Pair) -> bool {
+    let mut branches = disjunction.into_inner();
+    let mut conjunctions = branches.next().unwrap().into_inner();
+    let mut processions = conjunctions.next().unwrap().into_inner();
+    let mut steps = processions.next().unwrap().into_inner();
+    let step = steps.next().unwrap();
+    branches.next().is_none()
+        && conjunctions.next().is_none()
+        && processions.next().is_none()
+        && steps.next().is_none()
+        && !is_multiline(step)
+}
+
+/// Whether a step is a parenthesized disjunction of multiple branches, which is written across
+/// multiple lines.
+fn is_multiline(step: Pair) -> bool {
+    step.into_inner()
+        .next()
+        .filter(|pair| pair.as_rule() == Rule::disjunction)
+        .is_some_and(|disjunction| disjunction.into_inner().count() > 1)
+}
+
+/// Formats each of the inner pairs, joined by a separator.
+fn join(pair: Pair, separator: &str) -> String {
+    pair.into_inner()
+        .map(text)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Formats a pair on a single line.
+fn text(pair: Pair) -> String {
+    match pair.as_rule() {
+        Rule::atom => Atom::new(pair).to_string(),
+        Rule::literal
+        | Rule::string
+        | Rule::variable
+        | Rule::wildcard
+        | Rule::operator
+        | Rule::integer_10
+        | Rule::up
+        | Rule::root
+        | Rule::left
+        | Rule::right => pair.as_str().to_owned(),
+        Rule::nat => format!("nat({})", join(pair, "")),
+        Rule::mod_ => format!("mod({})", join(pair, "")),
+        Rule::use_ => format!("use({})", join(pair, "")),
+        Rule::pub_ => format!("pub({})", join(pair, "")),
+        Rule::mut_ => format!("mut({})", join(pair, "")),
+        Rule::inc => format!("inc({})", join(pair, "")),
+        Rule::test => format!("test({})", join(pair, ", ")),
+        Rule::allow => format!("allow({})", join(pair, "")),
+        Rule::op => format!("op({})", join(pair, ", ")),
+        Rule::lib => format!("@{}", join(pair, "")),
+        Rule::alias => format!("alias({})", join(pair, ", as: ")),
+        Rule::multi_handle | Rule::head | Rule::predicate => {
+            let mut pairs = pair.into_inner();
+            let name = text(pairs.next().unwrap());
+            match pairs.next() {
+                Some(list) => format!("{}({})", name, text(list)),
+                None => name,
+            }
+        }
+        Rule::struct_ => {
+            let mut pairs = pair.into_inner();
+            let name = text(pairs.next().unwrap());
+            match pairs.next() {
+                Some(pattern) if pattern.as_rule() == Rule::pattern => {
+                    format!("{}({})", name, text(pattern))
+                }
+                Some(contents) => format!("{} {}", name, text(contents)),
+                None => name,
+            }
+        }
+        Rule::arity => pair
+            .into_inner()
+            .map(|pair| match pair.as_rule() {
+                Rule::atom => format!(":{}", text(pair)),
+                _ => format!("/{}", text(pair)),
+            })
+            .collect(),
+        Rule::scope | Rule::scope_prefix => join(pair, "::"),
+        Rule::handles
+        | Rule::params
+        | Rule::named_params
+        | Rule::bare_params
+        | Rule::arguments
+        | Rule::named_arguments
+        | Rule::bare_arguments
+        | Rule::list_entries
+        | Rule::fields => join(pair, ", "),
+        Rule::named_param | Rule::named_argument | Rule::field => join(pair, ": "),
+        Rule::disjunction => join(pair, "; "),
+        Rule::branch => join(pair, " ->> "),
+        Rule::conjunction => join(pair, ", "),
+        Rule::procession => join(pair, " -> "),
+        Rule::unification => join(pair, " =:= "),
+        Rule::aggregation_body => join(pair, " : "),
+        Rule::step | Rule::term => {
+            let inner = pair.into_inner().next().unwrap();
+            match inner.as_rule() {
+                Rule::disjunction | Rule::expression => format!("({})", text(inner)),
+                _ => text(inner),
+            }
+        }
+        Rule::relation | Rule::expression => expression(pair),
+        Rule::handle
+        | Rule::module_path
+        | Rule::body
+        | Rule::fact
+        | Rule::aggregation
+        | Rule::pattern
+        | Rule::value => join(pair, ""),
+        Rule::bound_pattern => format!("!{}", join(pair, "")),
+        Rule::unbound_pattern => format!("?{}", join(pair, "")),
+        Rule::list_aggregation | Rule::list => format!("[{}]", join(pair, "")),
+        Rule::set_aggregation => format!("{{ {} }}", join(pair, "")),
+        Rule::set | Rule::record => match pair.clone().into_inner().next() {
+            Some(..) => format!("{{ {} }}", join(pair, "")),
+            None if pair.as_rule() == Rule::set => "{}".to_owned(),
+            None => "{:}".to_owned(),
+        },
+        Rule::list_tail | Rule::set_tail | Rule::record_tail => format!(", ..{}", join(pair, "")),
+        rule => unreachable!("{:?} is not formatted on its own", rule),
+    }
+}
+
+/// Formats an expression, with infix operators surrounded by spaces and prefix operators
+/// attached to their operands.
+fn expression(pair: Pair) -> String {
+    let needs_space = |ch: char| "-+*/%<>=?$!^&|@\\~".contains(ch);
+    let mut output = String::new();
+    let mut infix = false;
+    let mut prefix = false;
+    for pair in pair.into_inner() {
+        let is_operator = pair.as_rule() == Rule::operator;
+        let token = text(pair);
+        if is_operator {
+            if !output.is_empty() {
+                output.push(' ');
+            }
+            prefix = !infix;
+            infix = false;
+        } else {
+            if !output.is_empty() && (!prefix || token.starts_with(needs_space)) {
+                output.push(' ');
+            }
+            prefix = false;
+            infix = true;
+        }
+        output.push_str(&token);
+    }
+    output
+}
@@ -12,10 +12,14 @@ mod lumber;
 mod ast;
 mod climb;
 mod core;
+mod diagnostic;
 mod error;
+mod format;
 mod parser;
 mod program;
 
+pub mod clpfd;
+
 #[cfg(feature = "serde")]
 pub mod de;
 #[cfg(feature = "serde")]
@@ -25,7 +29,9 @@ type Pairs<'i> = pest::iterators::Pairs<'i, parser::Rule>;
 type Pair<'i> = pest::iterators::Pair<'i, parser::Rule>;
 
 pub use crate::lumber::*;
+pub use diagnostic::{Diagnostic, Severity, SourceSpan};
 pub use error::{Error, ErrorKind};
+pub use format::format;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -0,0 +1,427 @@
+use super::ModuleInfo;
+use crate::ast::*;
+use crate::{Diagnostic, SourceSpan};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// The results of checking the source code of a Lumber program without building it, for use by
+/// editors and other tools. An analysis finds the problems in the program (both errors and
+/// warnings), and can look up the symbols (predicates, operators and modules) named at a
+/// position in its source code.
+///
+/// Unlike when building a program, tests are not run, and each native function which is
+/// declared is assumed to be bound. Only the `@core` library is linked.
+///
+/// Positions are given as a line and column of a source file, both counted from 1, as in a
+/// [`SourceSpan`][].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use lumber::Analysis;
+/// # fn main() -> lumber::Result<()> {
+/// let analysis = Analysis::from_file("main.lumber")?;
+/// for diagnostic in analysis.diagnostics() {
+///     eprintln!("{}", diagnostic);
+/// }
+/// for location in analysis.definitions("main.lumber", 3, 18) {
+///     println!("defined at {}:{}", location.path().display(), location.span().start_line());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Analysis {
+    context: Context<'static>,
+    occurrences: Vec<Occurrence>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A location in the source code of a program, found by an [`Analysis`][].
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SourceLocation {
+    path: PathBuf,
+    span: SourceSpan,
+}
+
+impl SourceLocation {
+    /// The path of the source file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The span of the source file.
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+}
+
+/// The kinds of symbols which may be suggested by [`Analysis::completions`][].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum CompletionKind {
+    /// A predicate, which may be called.
+    Predicate,
+    /// An operator, which may be used in an expression.
+    Operator,
+    /// A submodule, which may be named in a scope.
+    Module,
+}
+
+/// A symbol which is in scope in a module, suggested by [`Analysis::completions`][].
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Completion {
+    name: String,
+    kind: CompletionKind,
+    detail: String,
+}
+
+impl Completion {
+    /// The name by which the symbol is referred to in the module.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The kind of symbol.
+    pub fn kind(&self) -> CompletionKind {
+        self.kind
+    }
+
+    /// The full handle of the predicate (e.g. `animals::cat/1`), or of the predicate by
+    /// which an operator is implemented, or the path of a module.
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+}
+
+impl Analysis {
+    /// Analyses the program whose main module is in the given file. Submodules are found
+    /// relative to it, as when building a program from a file.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned only if the main module's source file cannot be read. Problems with
+    /// the program, including missing submodules, are reported as diagnostics instead.
+    pub fn from_file<P: AsRef<Path>>(source_file: P) -> crate::Result<Self> {
+        let path = source_file.as_ref().canonicalize()?;
+        let source = std::fs::read_to_string(&path)?;
+        let mut context = Context::with_core();
+        let checked = context.parse(path, &source).and_then(|mut module| {
+            let natives: Vec<Handle> = context
+                .modules
+                .values()
+                .filter(|header| header.scope.library().is_empty())
+                .flat_map(|header| header.natives.iter().cloned())
+                .collect();
+            let natives: Vec<&Handle> = natives.iter().collect();
+            context.check(&mut module, &natives)
+        });
+        let mut diagnostics = match checked {
+            Ok(()) => context.take_warnings(),
+            Err(error) if error.diagnostics().is_empty() => {
+                vec![Diagnostic::error("io_error", error.to_string())]
+            }
+            Err(error) => error.diagnostics().to_vec(),
+        };
+        diagnostics.sort_by(|lhs, rhs| {
+            (lhs.path(), lhs.span(), lhs.message()).cmp(&(rhs.path(), rhs.span(), rhs.message()))
+        });
+        let occurrences = context.index();
+        Ok(Self {
+            context,
+            occurrences,
+            diagnostics,
+        })
+    }
+
+    /// Finds the main module of the program which a source file is part of, for when it is not
+    /// known. A module's parent is a file which declares it as a submodule (with `mod`), and
+    /// whose program includes it once analysed, found in the directory above it. Parents are
+    /// followed until a file without one is found, which is taken as the main module.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned only if the source file cannot be found.
+    pub fn find_main<P: AsRef<Path>>(source_file: P) -> crate::Result<PathBuf> {
+        let mut main = source_file.as_ref().canonicalize()?;
+        while let Some(parent) = parent_of(&main) {
+            main = parent;
+        }
+        Ok(main)
+    }
+
+    /// Whether a source file is one of the modules of the program.
+    pub fn contains<P: AsRef<Path>>(&self, source_file: P) -> bool {
+        self.scope_of(source_file.as_ref()).is_some()
+    }
+
+    /// The errors and warnings found in the program, ordered by file and position.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Describes the modules of the program, along with their documentation. Modules which
+    /// could not be parsed are not included.
+    pub fn modules(&self) -> Vec<ModuleInfo> {
+        ModuleInfo::all(&self.context.modules)
+    }
+
+    /// The places where the symbol named at a position is defined. A predicate is defined by
+    /// its clauses (or its `nat` directive), an operator by its `op` directive, and a module by
+    /// its source file.
+    ///
+    /// Libraries (such as `@core`) are not read from source files, so nothing is found for the
+    /// symbols they define, though they can still be described by [`hover`][Self::hover].
+    pub fn definitions<P: AsRef<Path>>(
+        &self,
+        source_file: P,
+        line: usize,
+        column: usize,
+    ) -> Vec<SourceLocation> {
+        let symbol = match self.symbol_at(source_file.as_ref(), line, column) {
+            Some(symbol) => symbol,
+            None => return vec![],
+        };
+        if let Symbol::Module(scope) = symbol {
+            return self
+                .path_of(scope)
+                .map(|path| SourceLocation {
+                    path: path.to_owned(),
+                    span: SourceSpan::new((1, 1), (1, 1)),
+                })
+                .into_iter()
+                .collect();
+        }
+        self.occurrences
+            .iter()
+            .filter(|occurrence| occurrence.definition && occurrence.symbol == *symbol)
+            .filter_map(|occurrence| self.location_of(occurrence))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// The places where the symbol named at a position is named, including where it is
+    /// defined.
+    pub fn references<P: AsRef<Path>>(
+        &self,
+        source_file: P,
+        line: usize,
+        column: usize,
+    ) -> Vec<SourceLocation> {
+        let symbol = match self.symbol_at(source_file.as_ref(), line, column) {
+            Some(symbol) => symbol,
+            None => return vec![],
+        };
+        self.occurrences
+            .iter()
+            .filter(|occurrence| occurrence.symbol == *symbol)
+            .filter_map(|occurrence| self.location_of(occurrence))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// A description of the symbol named at a position: the full handle of a predicate or
+    /// path of a module, and whether it is public, followed by its documentation.
+    pub fn hover<P: AsRef<Path>>(
+        &self,
+        source_file: P,
+        line: usize,
+        column: usize,
+    ) -> Option<String> {
+        let symbol = self.symbol_at(source_file.as_ref(), line, column)?;
+        let description = match symbol {
+            Symbol::Predicate(handle) => {
+                let header = self.context.modules.get(&handle.module())?;
+                let mut kind = String::new();
+                if header.natives.contains(handle) {
+                    kind.push_str("native ");
+                }
+                if header.mutables.contains(handle) {
+                    kind.push_str("mutable ");
+                }
+                if header.incompletes.contains(handle) {
+                    kind.push_str("incomplete ");
+                }
+                let description = format!(
+                    "{} {}predicate {}",
+                    visibility(header.exports.contains(handle)),
+                    kind,
+                    handle,
+                );
+                with_doc(description, header.predicate_docs.get(handle))
+            }
+            Symbol::Operator(scope, name) => {
+                let header = self.context.modules.get(scope)?;
+                let operator = header
+                    .operators
+                    .values()
+                    .find(|operator| operator.key().name() == *name)?;
+                let description = format!(
+                    "{} operator {} of {}",
+                    visibility(header.operator_exports.contains(name)),
+                    name.as_ref(),
+                    operator.handle(),
+                );
+                with_doc(description, header.operator_docs.get(name))
+            }
+            Symbol::Module(scope) => {
+                let doc = self.context.modules.get(scope)?.doc.as_ref();
+                with_doc(format!("module {}", scope), doc)
+            }
+        };
+        Some(description)
+    }
+
+    /// The predicates, operators and submodules which are in scope in the module in a source
+    /// file, sorted by name.
+    pub fn completions<P: AsRef<Path>>(&self, source_file: P) -> Vec<Completion> {
+        let scope = match self.scope_of(source_file.as_ref()) {
+            Some(scope) => scope,
+            None => return vec![],
+        };
+        let modules = &self.context.modules;
+        let header = &modules[scope];
+        let mut completions = BTreeSet::new();
+        let mut predicate = |name: &Handle, handle: &Handle| {
+            completions.insert(Completion {
+                name: name.scope.head().to_string(),
+                kind: CompletionKind::Predicate,
+                detail: handle.to_string(),
+            });
+        };
+        for handle in header
+            .definitions
+            .iter()
+            .chain(&header.natives)
+            .chain(header.aliases.keys())
+        {
+            let resolved = header
+                .resolve(handle, scope, &self.context)
+                .unwrap_or(handle);
+            predicate(handle, resolved);
+        }
+        for glob in header
+            .globbed_modules()
+            .filter_map(|glob| modules.get(glob))
+        {
+            for handle in &glob.exports {
+                predicate(handle, handle);
+            }
+        }
+
+        let names = header
+            .operators
+            .keys()
+            .map(OpKey::name)
+            .chain(header.operator_aliases.keys().cloned())
+            .chain(
+                header
+                    .globbed_modules()
+                    .filter_map(|glob| modules.get(glob))
+                    .flat_map(|glob| glob.operator_exports.iter().cloned()),
+            );
+        for name in names {
+            let resolved = OpKey::all_types(name.clone())
+                .find_map(|key| header.resolve_operator(&key, scope, &self.context).ok());
+            if let Some(operator) = resolved {
+                completions.insert(Completion {
+                    name: name.as_ref().to_owned(),
+                    kind: CompletionKind::Operator,
+                    detail: operator.handle().to_string(),
+                });
+            }
+        }
+
+        let depth = scope.into_iter().count();
+        for submodule in modules.keys() {
+            if scope > submodule && submodule.into_iter().count() == depth + 1 {
+                completions.insert(Completion {
+                    name: submodule.head().to_string(),
+                    kind: CompletionKind::Module,
+                    detail: submodule.to_string(),
+                });
+            }
+        }
+        completions.into_iter().collect()
+    }
+
+    fn scope_of(&self, source_file: &Path) -> Option<&Scope> {
+        let path = source_file
+            .canonicalize()
+            .unwrap_or_else(|_| source_file.to_owned());
+        self.context
+            .sources
+            .iter()
+            .find(|(_, (source_path, _))| source_path.as_deref() == Some(&path))
+            .map(|(scope, _)| scope)
+    }
+
+    fn path_of(&self, scope: &Scope) -> Option<&Path> {
+        self.context.sources.get(scope)?.0.as_deref()
+    }
+
+    fn location_of(&self, occurrence: &Occurrence) -> Option<SourceLocation> {
+        Some(SourceLocation {
+            path: self.path_of(&occurrence.scope)?.to_owned(),
+            span: occurrence.span,
+        })
+    }
+
+    fn symbol_at(&self, source_file: &Path, line: usize, column: usize) -> Option<&Symbol> {
+        let scope = self.scope_of(source_file)?;
+        self.occurrences
+            .iter()
+            .filter(|occurrence| occurrence.scope == *scope)
+            .find(|occurrence| {
+                let span = occurrence.span;
+                (span.start_line(), span.start_column()) <= (line, column)
+                    && (line, column) <= (span.end_line(), span.end_column())
+            })
+            .map(|occurrence| &occurrence.symbol)
+    }
+}
+
+/// Finds the file which declares the module in a source file as a submodule, if there is one.
+/// A module `a::b` is found in `a/b.lumber` or `a/b/mod.lumber`, relative to the directory of
+/// the main module, so its parent is either a file in the directory above it, or the file of
+/// that directory's own module.
+fn parent_of(source_file: &Path) -> Option<PathBuf> {
+    let module = match source_file.file_name()? == "mod.lumber" {
+        true => source_file.parent()?,
+        false => source_file,
+    };
+    let name = module.file_stem()?.to_str()?;
+    let directory = module.parent()?;
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension() == Some("lumber".as_ref()))
+        .filter(|path| path != source_file)
+        .collect();
+    candidates.sort();
+    candidates.push(directory.with_extension("lumber"));
+    candidates.into_iter().find(|candidate| {
+        let declares = std::fs::read_to_string(candidate)
+            .map(|source| source.contains("mod(") && source.contains(name))
+            .unwrap_or(false);
+        declares
+            && Analysis::from_file(candidate)
+                .map(|analysis| analysis.contains(source_file))
+                .unwrap_or(false)
+    })
+}
+
+fn visibility(public: bool) -> &'static str {
+    if public {
+        "public"
+    } else {
+        "private"
+    }
+}
+
+fn with_doc(description: String, doc: Option<&String>) -> String {
+    match doc {
+        Some(doc) => format!("{}\n\n{}", description, doc),
+        None => description,
+    }
+}
@@ -7,9 +7,13 @@ use std::iter::FromIterator;
 
 /// An answer to a question. Contains a single valid binding of the named variables in the question
 /// to values that satisfy the program.
+///
+/// If some constraints (such as `dif/2`) were left unresolved, those residual constraints are
+/// also included, as they restrict the values which the unbound variables may take.
 #[derive(Clone)]
 pub struct Answer {
     variables: HashMap<String, Option<Value>>,
+    residuals: Vec<Value>,
 }
 
 impl Answer {
@@ -32,6 +36,18 @@ impl Answer {
     {
         self.variables.remove(key)?
     }
+
+    /// The constraints which remain on the unbound variables of this answer. In these values,
+    /// each of the unbound variables from the question is replaced by an atom with the same name
+    /// as the variable, while any other unbound variables are left unbound.
+    pub fn residuals(&self) -> &[Value] {
+        &self.residuals
+    }
+
+    pub(crate) fn with_residuals(mut self, residuals: Vec<Value>) -> Self {
+        self.residuals = residuals;
+        self
+    }
 }
 
 impl FromIterator<(String, Option<Value>)> for Answer {
@@ -41,6 +57,7 @@ impl FromIterator<(String, Option<Value>)> for Answer {
     {
         Self {
             variables: HashMap::from_iter(iter),
+            residuals: vec![],
         }
     }
 }
@@ -56,7 +73,7 @@ impl IntoIterator for Answer {
 
 impl Display for Answer {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        if self.variables.is_empty() {
+        if self.variables.is_empty() && self.residuals.is_empty() {
             return write!(f, "true");
         }
         for (i, (var, val)) in self.variables.iter().enumerate() {
@@ -68,6 +85,12 @@ impl Display for Answer {
                 None => write!(f, "{} = _", var)?,
             }
         }
+        for (i, residual) in self.residuals.iter().enumerate() {
+            if i != 0 || !self.variables.is_empty() {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", residual)?;
+        }
         Ok(())
     }
 }
@@ -1,8 +1,9 @@
-use super::{Lumber, Value};
+use super::{Lumber, Tracer, Value};
 use crate::ast::*;
 use crate::program::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::rc::Rc;
 
 /// A builder to customize the construction of a Lumber program.
 ///
@@ -10,8 +11,13 @@ use std::path::Path;
 pub struct LumberBuilder<'p> {
     core: bool,
     test: bool,
+    strict: bool,
+    check_modes: bool,
     context: Context<'p>,
     natives: HashMap<Handle, NativeFunction<'p>>,
+    modes: HashMap<Handle, Vec<Mode>>,
+    suspending: HashSet<Handle>,
+    tracer: Option<Rc<dyn Tracer>>,
 }
 
 impl<'p> LumberBuilder<'p> {
@@ -19,8 +25,13 @@ impl<'p> LumberBuilder<'p> {
         Self {
             core: true,
             test: false,
+            strict: false,
+            check_modes: false,
             context: Context::default(),
             natives: HashMap::default(),
+            modes: HashMap::default(),
+            suspending: HashSet::default(),
+            tracer: None,
         }
     }
 
@@ -41,7 +52,8 @@ impl<'p> LumberBuilder<'p> {
     }
 
     /// Sets whether to run the tests or not. Tests will be run when `build` is called,
-    /// causing an `Err` to be returned if the tests fail.
+    /// causing an `Err` to be returned if the tests fail. To run tests selectively, or to
+    /// see the result of each, use [`Lumber::tests`][] on the built program instead.
     ///
     /// It is recommended that you run the tests as part of your test suit, but build without
     /// tests for release. Tests are not run by default.
@@ -60,6 +72,148 @@ impl<'p> LumberBuilder<'p> {
         self
     }
 
+    /// Sets whether to run the program in strict mode. Strict mode is off by default.
+    ///
+    /// Normally, referencing a predicate which does not exist (or is not public) from a question
+    /// or a goal called at runtime simply has no answers. In strict mode, the question is first
+    /// checked against the program's exports, and any predicate that cannot be found causes an
+    /// `existence_error [procedure, Handle, Suggestions]` exception to be thrown instead, where
+    /// `Suggestions` lists the most similarly named predicates that do exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::Lumber;
+    /// Lumber::builder()
+    ///     .strict(true) // Unknown predicates throw exceptions
+    ///     // ...
+    /// #   ;
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets whether to check the modes of native functions before they are called. Modes are
+    /// not checked by default.
+    ///
+    /// When a native function is called with arguments that do not satisfy any of its declared
+    /// modes (see [`LumberBuilder::modes`][]), an `instantiation_error [Handle, Positions]`
+    /// exception is thrown, where `Positions` lists the (1-based) positions of the arguments
+    /// which needed to be bound. Without checking modes, such a call typically just fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::Lumber;
+    /// Lumber::builder()
+    ///     .check_modes(true) // Under-bound native calls throw exceptions
+    ///     // ...
+    /// #   ;
+    /// ```
+    pub fn check_modes(mut self, check_modes: bool) -> Self {
+        self.check_modes = check_modes;
+        self
+    }
+
+    /// Declare the modes in which a native function may be called. Each mode is a string with
+    /// one character per argument: `+` for an argument which must be bound when the function
+    /// is called, and `-` or `?` for one which need not be (conventionally, `-` marks an
+    /// output). The call is valid if it satisfies any one of the modes.
+    ///
+    /// Modes are only checked if enabled using [`LumberBuilder::check_modes`][].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::Lumber;
+    /// Lumber::builder()
+    ///     .bind("length/2", |_| todo!())
+    ///     .modes("length/2", &["+-"]) // The first argument must be bound
+    ///     // ...
+    /// #   ;
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the handle is not a valid handle, or any of the modes are not valid for that handle.
+    pub fn modes<H>(mut self, handle: H, modes: &[&str]) -> Self
+    where
+        H: AsHandle,
+    {
+        let handle = handle.as_handle().expect("Invalid handle");
+        let modes = modes
+            .iter()
+            .map(|mode| Mode::new(mode).expect("Invalid mode"))
+            .inspect(|mode| {
+                assert_eq!(
+                    mode.len(),
+                    handle.arity.len() as usize,
+                    "Mode does not match the arity of {}",
+                    handle,
+                )
+            })
+            .collect();
+        self.modes.insert(handle, modes);
+        self
+    }
+
+    /// Make calls to a native function which do not satisfy any of its declared modes (see
+    /// [`LumberBuilder::modes`][]) suspend until the first of the missing arguments is bound,
+    /// instead of being called anyway. Once that argument is bound, the modes are checked again,
+    /// and the function is called (or suspended again) as usual.
+    ///
+    /// This allows a native function to be used before its inputs are known, in the same way
+    /// as a goal delayed using `@core::freeze/2`. Modes that are not satisfied are reported
+    /// as normal when [`LumberBuilder::check_modes`][] is enabled, so a suspending function never
+    /// throws an `instantiation_error`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::Lumber;
+    /// Lumber::builder()
+    ///     .bind("length/2", |_| todo!())
+    ///     .modes("length/2", &["+-"])
+    ///     .suspend("length/2") // Waits for the first argument to be bound
+    ///     // ...
+    /// #   ;
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the handle is not a valid handle. When the program is built, if the native function
+    /// has no declared modes.
+    pub fn suspend<H>(mut self, handle: H) -> Self
+    where
+        H: AsHandle,
+    {
+        self.suspending
+            .insert(handle.as_handle().expect("Invalid handle"));
+        self
+    }
+
+    /// Attach a tracer, to which the events of the search for answers to every question asked
+    /// of the program are reported. Tests run while building the program are not traced. See
+    /// [`Tracer`][] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Lumber, TextTracer};
+    /// Lumber::builder()
+    ///     .tracer(TextTracer::new(std::io::stderr())) // Prints a trace of each question
+    ///     // ...
+    /// #   ;
+    /// ```
+    pub fn tracer<T>(mut self, tracer: T) -> Self
+    where
+        T: Tracer + 'static,
+    {
+        self.tracer = Some(Rc::new(tracer));
+        self
+    }
+
     /// Bind a native function to the Lumber program.
     ///
     /// Arbitrary Rust code can be attached to the Lumber program at a particular
@@ -97,13 +251,19 @@ impl<'p> LumberBuilder<'p> {
     /// # }
     /// ```
     ///
+    /// Each item yielded by the native function is either an answer, which is bound to the
+    /// arguments of the predicate, or an exception (`Err`), which is thrown as if by `throw/1`
+    /// and may be caught by `catch/3`. The [`native_function!`][] macro is the most convenient
+    /// way to write such a function.
+    ///
     /// # Panics
     ///
     /// If the handle is not a valid handle.
     pub fn bind<H, F>(mut self, handle: H, native: F) -> Self
     where
         H: AsHandle,
-        F: Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Vec<Option<Value>>>> + 'p, // TODO: this is not the final type
+        F: Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Result<Vec<Option<Value>>, Value>>>
+            + 'p,
     {
         self.natives.insert(
             handle.as_handle().expect("Invalid handle"),
@@ -112,6 +272,19 @@ impl<'p> LumberBuilder<'p> {
         self
     }
 
+    /// Bind one of the solver's builtin predicates to the Lumber program. This works the same
+    /// as [`LumberBuilder::bind`][], but is reserved for `@core`.
+    pub(crate) fn builtin<H>(mut self, handle: H, builtin: Builtin) -> Self
+    where
+        H: AsHandle,
+    {
+        self.natives.insert(
+            handle.as_handle().expect("Invalid handle"),
+            NativeFunction::builtin(builtin),
+        );
+        self
+    }
+
     /// Provide a library which may be referenced by this Lumber program.
     ///
     /// Libraries may contain any Lumber definitions, and can even be bound to native
@@ -211,6 +384,9 @@ impl<'p> LumberBuilder<'p> {
     /// Submodules referenced from the main module are resolved relative to the directory wich is
     /// passed in to this method.
     ///
+    /// Warnings about likely mistakes in the program do not prevent it from being built, and can
+    /// be retrieved from the resulting program using [`Lumber::warnings`][].
+    ///
     /// # Errors
     ///
     /// If the source files of any submodules cannot be found, an error (typically wrapping an
@@ -224,6 +400,23 @@ impl<'p> LumberBuilder<'p> {
         P: AsRef<Path>,
         S: AsRef<str>,
     {
+        for handle in std::mem::take(&mut self.suspending) {
+            assert!(
+                self.modes.contains_key(&handle),
+                "Suspending native function {} has no declared modes",
+                handle,
+            );
+            self.natives
+                .get_mut(&handle)
+                .expect("Suspending native function which is not bound")
+                .set_suspend(true);
+        }
+        for (handle, modes) in std::mem::take(&mut self.modes) {
+            self.natives
+                .get_mut(&handle)
+                .expect("Modes declared for a native function which is not bound")
+                .set_modes(modes);
+        }
         if self.core {
             crate::core::LIB.with(|lib| {
                 let (modules, library) = lib.clone().into_library("core");
@@ -233,6 +426,16 @@ impl<'p> LumberBuilder<'p> {
                 self.context.modules.extend(&mut modules.into_iter());
             });
         }
-        Lumber::new(self.context, root, source, self.natives, self.test)
+        let mut lumber = Lumber::new(
+            self.context,
+            root,
+            source,
+            self.natives,
+            self.test,
+            self.strict,
+            self.check_modes,
+        )?;
+        lumber.database.set_tracer(self.tracer);
+        Ok(lumber)
     }
 }
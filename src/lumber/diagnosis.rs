@@ -0,0 +1,182 @@
+use super::Value;
+use std::fmt::{self, Display, Formatter};
+
+/// A diagnosis of why a question has no answers. Created by
+/// [`Lumber::diagnose`][crate::Lumber::diagnose].
+///
+/// The diagnosis lists the deepest calls which failed while searching for an answer, as the
+/// cause of a failure is most often found at the bottom of the search. For each failed call to
+/// a predicate, the clauses whose heads came closest to unifying with the call are included.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnosis {
+    depth: usize,
+    failures: Vec<Failure>,
+}
+
+/// A call which failed to find any solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Failure {
+    name: String,
+    handle: String,
+    arguments: Vec<Option<Value>>,
+    closest: Vec<ClauseMatch>,
+}
+
+/// A clause whose head came close to unifying with a failed call.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClauseMatch {
+    clause: usize,
+    line: usize,
+    column: usize,
+    matched: usize,
+    arity: usize,
+}
+
+impl Diagnosis {
+    /// Records a failed call made at the given depth. Only the deepest failures are kept.
+    pub(crate) fn record(&mut self, depth: usize, failure: Failure) {
+        if depth > self.depth {
+            self.depth = depth;
+            self.failures.clear();
+        }
+        if depth == self.depth && !self.failures.contains(&failure) {
+            self.failures.push(failure);
+        }
+    }
+
+    /// The depth of the deepest failed calls, counted as the number of nested predicate calls
+    /// made to reach them.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The deepest calls which failed, in the order they were made.
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+}
+
+impl Failure {
+    pub(crate) fn new(
+        name: String,
+        handle: String,
+        arguments: Vec<Option<Value>>,
+        closest: Vec<ClauseMatch>,
+    ) -> Self {
+        Self {
+            name,
+            handle,
+            arguments,
+            closest,
+        }
+    }
+
+    /// The handle of the predicate which was called (e.g. `animals::cat/1`).
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The values of the arguments of the call at the time it was made. Arguments which were
+    /// unbound are `None`.
+    pub fn arguments(&self) -> &[Option<Value>] {
+        &self.arguments
+    }
+
+    /// The clauses whose heads unified with the most arguments of the call. This is empty if
+    /// the predicate is a native function, or has no clauses.
+    pub fn closest(&self) -> &[ClauseMatch] {
+        &self.closest
+    }
+}
+
+impl ClauseMatch {
+    pub(crate) fn new(
+        clause: usize,
+        line: usize,
+        column: usize,
+        matched: usize,
+        arity: usize,
+    ) -> Self {
+        Self {
+            clause,
+            line,
+            column,
+            matched,
+            arity,
+        }
+    }
+
+    /// The index of the clause, counting from 0 in the order the clauses were written.
+    pub fn clause(&self) -> usize {
+        self.clause
+    }
+
+    /// The line of the source file of the predicate's module at which the clause begins.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the source file of the predicate's module at which the clause begins.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The number of arguments of the call which unify with the clause's head. The arguments
+    /// are unified in order, and those which do not unify are skipped.
+    pub fn matched(&self) -> usize {
+        self.matched
+    }
+
+    /// Whether the whole head unified with the call, meaning that it was the clause's body
+    /// which failed.
+    pub fn is_complete(&self) -> bool {
+        self.matched == self.arity
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.failures.is_empty() {
+            return writeln!(f, "No calls were made.");
+        }
+        for failure in &self.failures {
+            failure.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, argument) in self.arguments.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            match argument {
+                Some(value) => write!(f, "{}", value)?,
+                None => write!(f, "_")?,
+            }
+        }
+        writeln!(f, ") failed")?;
+        for clause in &self.closest {
+            writeln!(f, "    {}", clause)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ClauseMatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "clause {} at {}:{} ",
+            self.clause, self.line, self.column
+        )?;
+        if self.is_complete() {
+            write!(f, "matched, but its body failed")
+        } else {
+            write!(f, "matched {} of {} arguments", self.matched, self.arity)
+        }
+    }
+}
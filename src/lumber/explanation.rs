@@ -0,0 +1,149 @@
+use super::Value;
+use crate::program::{Binding, Inference};
+use std::fmt::{self, Display, Formatter};
+
+/// An explanation of how an answer was found, as the tree of clauses that were used to derive
+/// it. Created by [`Lumber::ask_explained`][crate::Lumber::ask_explained].
+///
+/// Each call to a predicate made by the question is the root of a tree, so there may be many.
+/// Calls to native functions are not included, as they are not derived from clauses.
+#[derive(Clone, Debug, Default)]
+pub struct Explanation {
+    derivations: Vec<Derivation>,
+}
+
+/// A single step in an [`Explanation`][]: a clause which was used to prove a call to a
+/// predicate, along with the derivations of each of the calls made by its body.
+#[derive(Clone, Debug)]
+pub struct Derivation {
+    name: String,
+    handle: String,
+    module: String,
+    clause: usize,
+    line: usize,
+    column: usize,
+    arguments: Vec<Option<Value>>,
+    children: Vec<Derivation>,
+}
+
+impl Explanation {
+    /// Builds the explanation from the derivation recorded in a binding, using the binding to
+    /// find the final values of the arguments of each call.
+    pub(crate) fn new(binding: &Binding) -> Self {
+        let mut stack: Vec<Derivation> = vec![];
+        let mut derivations = vec![];
+        for inference in binding.derivation().into_iter().flatten() {
+            match inference {
+                Inference::Enter {
+                    handle,
+                    clause,
+                    location,
+                    args,
+                } => stack.push(Derivation {
+                    name: handle.scope.to_string(),
+                    handle: handle.to_string(),
+                    module: handle.scope.drop().to_string(),
+                    clause: *clause,
+                    line: location.line,
+                    column: location.column,
+                    arguments: args
+                        .iter()
+                        .map(|arg| binding.extract(arg).unwrap())
+                        .collect(),
+                    children: vec![],
+                }),
+                Inference::Exit => {
+                    let derivation = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(derivation),
+                        None => derivations.push(derivation),
+                    }
+                }
+            }
+        }
+        Self { derivations }
+    }
+
+    /// The derivations of each of the calls made by the question, in the order they were made.
+    pub fn derivations(&self) -> &[Derivation] {
+        &self.derivations
+    }
+}
+
+impl Derivation {
+    /// The handle of the predicate which was called, including the module in which it was
+    /// defined (e.g. `animals::cat/1`).
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The path of the module in which the predicate is defined, and so in whose source file
+    /// the clause is written (e.g. `animals`). The main module's path is `~`.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// The index of the clause of the predicate's definition which was used, counting from 0 in
+    /// the order the clauses were written.
+    pub fn clause(&self) -> usize {
+        self.clause
+    }
+
+    /// The line of the source file of the predicate's module at which the clause begins.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the source file of the predicate's module at which the clause begins.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The values of the arguments of the call, as bound in the answer. Arguments which were
+    /// left unbound are `None`.
+    pub fn arguments(&self) -> &[Option<Value>] {
+        &self.arguments
+    }
+
+    /// The derivations of the calls made by the body of the clause, in the order they were made.
+    pub fn children(&self) -> &[Derivation] {
+        &self.children
+    }
+
+    fn fmt_indented(&self, f: &mut Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:width$}{}(", "", self.name, width = depth * 2)?;
+        for (i, argument) in self.arguments.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            match argument {
+                Some(value) => write!(f, "{}", value)?,
+                None => write!(f, "_")?,
+            }
+        }
+        writeln!(
+            f,
+            ") by clause {} in {} at {}:{}",
+            self.clause, self.module, self.line, self.column
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Derivation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for derivation in &self.derivations {
+            derivation.fmt(f)?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use super::Value;
+use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 use std::ops::{Index, IndexMut};
 
@@ -26,6 +28,26 @@ impl PartialEq for List {
     }
 }
 
+impl Eq for List {}
+
+impl PartialOrd for List {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for List {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.values.cmp(&other.values)
+    }
+}
+
+impl Hash for List {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.values.hash(state)
+    }
+}
+
 impl List {
     /// Creates a new Lumber list value from a `Vec` of possibly unbound [`Value`][]s.
     pub fn new(values: Vec<Option<Value>>) -> Self {
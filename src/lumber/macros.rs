@@ -145,7 +145,7 @@ mod test_record {
 /// the function signature. The function should not return any value, and instead emits
 /// answers using the `answer!` macro. Though it is not checked statically, the answers must
 /// be the same length as the parameters, as the emitted values will be bound to the
-/// Lumber patterns.
+/// Lumber patterns. An exception may be raised instead using the `throw!` macro.
 ///
 /// Under the hood, this uses generators, so using it requires the `generators` and
 /// `generator_trait` features to be enabled.
@@ -178,22 +178,22 @@ macro_rules! native_function {
     (
         $vis:vis fn $name:ident($($arg_name:ident),+) $body:block
     ) => {
-        $vis fn $name(args: Vec<Option<$crate::Value>>) -> Box<dyn Iterator<Item = Vec<Option<$crate::Value>>>> {
+        $vis fn $name(args: Vec<Option<$crate::Value>>) -> Box<dyn Iterator<Item = Result<Vec<Option<$crate::Value>>, $crate::Value>>> {
             use std::pin::Pin;
             use std::ops::{Generator, GeneratorState};
 
             let mut args = args.into_iter();
             $( let $arg_name = args.next().unwrap(); )+
-            let generator = || $body;
+            let generator = move || $body;
 
             struct NativeResult<G>
-            where G: Unpin + Generator<Yield = Vec<Option<$crate::Value>>, Return = ()> {
+            where G: Unpin + Generator<Yield = Result<Vec<Option<$crate::Value>>, $crate::Value>, Return = ()> {
                 generator: G
             }
 
             impl<G> Iterator for NativeResult<G>
-            where G: Unpin + Generator<Yield = Vec<Option<$crate::Value>>, Return = ()> {
-                type Item = Vec<Option<$crate::Value>>;
+            where G: Unpin + Generator<Yield = Result<Vec<Option<$crate::Value>>, $crate::Value>, Return = ()> {
+                type Item = Result<Vec<Option<$crate::Value>>, $crate::Value>;
 
                 fn next(&mut self) -> Option<Self::Item> {
                     match Pin::new(&mut self.generator).resume(()) {
@@ -227,5 +227,34 @@ macro_rules! answer {
         answer![@ $($out,)* None, @]
     };
     (@ $($out:expr,)+ @) => { vec![$($out),+] };
-    ($($answer:tt)*) => {{ yield answer![@@ $($answer)*]; }};
+    ($($answer:tt)*) => {{ yield Ok(answer![@@ $($answer)*]); }};
+}
+
+/// Throw an exception from a native function. The value passed to this macro is converted to
+/// a Lumber value using the standard [`Into`][] trait, and is thrown as if by `throw/1`, to be
+/// caught by `catch/3` or reported as an [`ErrorKind::Runtime`][crate::ErrorKind::Runtime]
+/// error. A native function does not produce any further answers after throwing.
+///
+/// # Examples
+///
+/// ```rust
+/// #![feature(generators, generator_trait)]
+/// use lumber::{native_function, answer, throw, Value};
+///
+/// native_function! {
+///     fn length(string, len) {
+///         match string {
+///             Some(Value::String(string)) => answer![string, string.len()],
+///             Some(..) => throw!(Value::atom("type_error")),
+///             None => {}
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! throw {
+    ($exception:expr) => {{
+        yield Err($exception.into());
+        return;
+    }};
 }
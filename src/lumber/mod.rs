@@ -5,24 +5,43 @@ use crate::ast::*;
 use crate::program::*;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 
 #[macro_use]
 mod macros;
 
+mod analysis;
 mod answer;
 mod builder;
+mod diagnosis;
+mod explanation;
 mod list;
+mod module_info;
+mod profile;
 mod question;
 mod record;
+mod strategy;
 mod r#struct;
+mod test_report;
+mod test_runner;
+mod tracer;
 mod value;
 
+pub use analysis::{Analysis, Completion, CompletionKind, SourceLocation};
 pub use answer::Answer;
 pub use builder::LumberBuilder;
+pub use diagnosis::{ClauseMatch, Diagnosis, Failure};
+pub use explanation::{Derivation, Explanation};
 pub use list::List;
+pub use module_info::{Associativity, ModuleInfo, OperatorInfo, PredicateInfo};
+pub use profile::{Profile, ProfileEntry, Profiler};
 pub use question::Question;
 pub use r#struct::Struct;
 pub use record::Record;
+pub use strategy::Strategy;
+pub use test_report::{TestOutcome, TestReport, TestResult};
+pub use test_runner::TestRunner;
+pub use tracer::{Event, Port, TextTracer, Tracer};
 pub use value::Value;
 
 /// A Lumber program, for use either as a full program, or linked to by another Lumber program
@@ -31,6 +50,8 @@ pub use value::Value;
 pub struct Lumber<'p> {
     pub(crate) modules: HashMap<Scope, ModuleHeader>,
     pub(crate) database: Database<'p>,
+    pub(crate) warnings: Vec<crate::Diagnostic>,
+    pub(crate) tests: Vec<UnitTest>,
 }
 
 impl Default for Lumber<'_> {
@@ -64,6 +85,8 @@ impl<'p> Lumber<'p> {
             source_code,
             HashMap::default(),
             false,
+            false,
+            false,
         )
     }
 
@@ -92,6 +115,8 @@ impl<'p> Lumber<'p> {
             source_code,
             HashMap::default(),
             false,
+            false,
+            false,
         )
     }
 
@@ -106,6 +131,8 @@ impl<'p> Lumber<'p> {
         source_code: S,
         natives: HashMap<Handle, NativeFunction<'p>>,
         run_tests: bool,
+        strict: bool,
+        check_modes: bool,
     ) -> crate::Result<Self> {
         let source_str = source_code.as_ref();
         context.compile(
@@ -113,24 +140,320 @@ impl<'p> Lumber<'p> {
             source_str,
             natives,
             run_tests,
+            strict,
+            check_modes,
         )
     }
 
-    pub(crate) fn build(modules: HashMap<Scope, ModuleHeader>, database: Database<'p>) -> Self {
-        Self { modules, database }
+    pub(crate) fn build(
+        modules: HashMap<Scope, ModuleHeader>,
+        database: Database<'p>,
+        warnings: Vec<crate::Diagnostic>,
+        tests: Vec<UnitTest>,
+    ) -> Self {
+        Self {
+            modules,
+            database,
+            warnings,
+            tests,
+        }
+    }
+
+    /// The warnings found by lints while compiling this program, in the order they appear in
+    /// the source code. Warnings point out likely mistakes, which do not prevent the program
+    /// from being created. Each kind of warning can be silenced within a module using the
+    /// `:- allow(lint).` directive, where `lint` is the code of the warning.
+    pub fn warnings(&self) -> &[crate::Diagnostic] {
+        &self.warnings
+    }
+
+    /// Describes the modules of this program, along with their documentation. See
+    /// [`ModuleInfo`][] for how documentation is written.
+    pub fn modules(&self) -> Vec<ModuleInfo> {
+        ModuleInfo::all(&self.modules)
+    }
+
+    /// Prepares to run the unit tests of this program. See [`TestRunner`][] for how tests are
+    /// selected and run.
+    pub fn tests(&self) -> TestRunner<'_, 'p> {
+        TestRunner::new(self)
     }
 
     /// Ask a question, returning an iterator over all possible answers.
+    ///
+    /// If an exception is thrown and not caught while answering the question, the exception is
+    /// dropped and the iterator ends, so it looks the same as there being no more answers. Use
+    /// [`Lumber::try_ask`][] to have it reported as an error of kind
+    /// [`ErrorKind::Runtime`][crate::ErrorKind::Runtime] instead.
     pub fn ask<'a>(&'a self, query: &'a Question) -> impl Iterator<Item = Answer> + 'a {
+        self.ask_with(query, Strategy::DepthFirst)
+    }
+
+    /// Ask a question, returning an iterator over all possible answers, or the exception
+    /// that prevented further answers from being found.
+    ///
+    /// # Errors
+    ///
+    /// If an exception is thrown and not caught while answering the question, an error of
+    /// kind [`ErrorKind::Runtime`][crate::ErrorKind::Runtime] is yielded as the last item,
+    /// from which the thrown value can be retrieved using [`Error::exception`][crate::Error::exception].
+    pub fn try_ask<'a>(
+        &'a self,
+        query: &'a Question,
+    ) -> impl Iterator<Item = crate::Result<Answer>> + 'a {
+        self.try_ask_with(query, Strategy::DepthFirst)
+    }
+
+    /// Ask a question, searching for answers using the given strategy. See [`Strategy`][] for
+    /// the available strategies. As for [`Lumber::ask`][], an uncaught exception is dropped;
+    /// use [`Lumber::try_ask_with`][] to find out what was thrown.
+    ///
+    /// # Examples
+    ///
+    /// A depth-first search would never find an answer here, as the first clause of `loop/1`
+    /// recurses forever.
+    ///
+    /// ```rust
+    /// # use lumber::{Lumber, Question, Strategy, Value};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lumber = Lumber::from_source("
+    ///     :- pub(loop/1).
+    ///     loop(A) :- loop(A).
+    ///     loop(found).
+    /// ")?;
+    /// let question = Question::try_from("loop(A)")?;
+    /// let mut answers = lumber.ask_with(&question, Strategy::IterativeDeepening(usize::MAX));
+    /// assert_eq!(answers.next().unwrap().get("A"), Some(&Value::atom("found")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask_with<'a>(
+        &'a self,
+        query: &'a Question,
+        strategy: Strategy,
+    ) -> impl Iterator<Item = Answer> + 'a {
+        self.try_ask_with(query, strategy)
+            .take_while(Result::is_ok)
+            .filter_map(Result::ok)
+    }
+
+    /// Ask a question, searching for answers using the given strategy, returning the exception
+    /// that prevented further answers from being found as for [`Lumber::try_ask`][].
+    ///
+    /// # Errors
+    ///
+    /// If an exception is thrown and not caught while answering the question, an error of
+    /// kind [`ErrorKind::Runtime`][crate::ErrorKind::Runtime] is yielded as the last item.
+    pub fn try_ask_with<'a>(
+        &'a self,
+        query: &'a Question,
+        strategy: Strategy,
+    ) -> impl Iterator<Item = crate::Result<Answer>> + 'a {
+        self.database
+            .unify_question(query, strategy)
+            .map(move |binding| match binding.exception() {
+                Some(exception) => Err(crate::Error::runtime(exception.clone())),
+                None => Ok(query.answer(&binding)),
+            })
+    }
+
+    /// Ask a question, returning an iterator over all possible answers, each along with an
+    /// explanation of how it was found. The search is performed depth-first, and an uncaught
+    /// exception is dropped, as for [`Lumber::ask`][]; use [`Lumber::try_ask_explained`][] to
+    /// find out what was thrown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Lumber, Question};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lumber = Lumber::from_source("
+    ///     :- pub(grandparent/2).
+    ///     parent(alice, bob).
+    ///     parent(bob, carol).
+    ///     grandparent(A, C) :- parent(A, B), parent(B, C).
+    /// ")?;
+    /// let question = Question::try_from("grandparent(A, carol)")?;
+    /// let (_, explanation) = lumber.ask_explained(&question).next().unwrap();
+    /// let grandparent = &explanation.derivations()[0];
+    /// assert_eq!(grandparent.handle(), "grandparent/2");
+    /// assert_eq!(grandparent.children()[1].clause(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask_explained<'a>(
+        &'a self,
+        query: &'a Question,
+    ) -> impl Iterator<Item = (Answer, Explanation)> + 'a {
+        self.try_ask_explained(query)
+            .take_while(Result::is_ok)
+            .filter_map(Result::ok)
+    }
+
+    /// Ask a question, returning an iterator over all possible answers, each along with an
+    /// explanation of how it was found, or the exception that prevented further answers from
+    /// being found as for [`Lumber::try_ask`][].
+    ///
+    /// # Errors
+    ///
+    /// If an exception is thrown and not caught while answering the question, an error of
+    /// kind [`ErrorKind::Runtime`][crate::ErrorKind::Runtime] is yielded as the last item.
+    pub fn try_ask_explained<'a>(
+        &'a self,
+        query: &'a Question,
+    ) -> impl Iterator<Item = crate::Result<(Answer, Explanation)>> + 'a {
+        self.database
+            .explain_question(query)
+            .map(move |binding| match binding.exception() {
+                Some(exception) => Err(crate::Error::runtime(exception.clone())),
+                None => Ok((query.answer(&binding), Explanation::new(&binding))),
+            })
+    }
+
+    /// Ask a question, returning an iterator over all possible answers, reporting the events
+    /// of the search to a tracer as the answers are found. The search is performed depth-first,
+    /// and an uncaught exception is dropped, as for [`Lumber::ask`][]; use
+    /// [`Lumber::try_ask_traced`][] to find out what was thrown. A tracer given here replaces
+    /// any attached to the program by [`LumberBuilder::tracer`][].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Event, Lumber, Port, Question, Tracer};
+    /// # use std::cell::RefCell;
+    /// # use std::convert::TryFrom;
+    /// # use std::rc::Rc;
+    /// #[derive(Default)]
+    /// struct Calls(RefCell<Vec<String>>);
+    ///
+    /// impl Tracer for Calls {
+    ///     fn trace(&self, event: &Event) {
+    ///         if event.port() == Port::Call {
+    ///             self.0.borrow_mut().push(event.handle().to_owned());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lumber = Lumber::from_source("
+    ///     :- pub(grandparent/2).
+    ///     parent(alice, bob).
+    ///     parent(bob, carol).
+    ///     grandparent(A, C) :- parent(A, B), parent(B, C).
+    /// ")?;
+    /// let question = Question::try_from("grandparent(alice, C)")?;
+    /// let calls = Rc::new(Calls::default());
+    /// assert_eq!(lumber.ask_traced(&question, calls.clone()).count(), 1);
+    /// assert_eq!(*calls.0.borrow(), vec!["grandparent/2", "parent/2", "parent/2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask_traced<'a, T>(
+        &'a self,
+        query: &'a Question,
+        tracer: T,
+    ) -> impl Iterator<Item = Answer> + 'a
+    where
+        T: Tracer + 'static,
+    {
+        self.try_ask_traced(query, tracer)
+            .take_while(Result::is_ok)
+            .filter_map(Result::ok)
+    }
+
+    /// Ask a question, reporting the events of the search to a tracer as the answers are found,
+    /// returning an iterator over all possible answers, or the exception that prevented further
+    /// answers from being found as for [`Lumber::try_ask`][].
+    ///
+    /// # Errors
+    ///
+    /// If an exception is thrown and not caught while answering the question, an error of
+    /// kind [`ErrorKind::Runtime`][crate::ErrorKind::Runtime] is yielded as the last item.
+    pub fn try_ask_traced<'a, T>(
+        &'a self,
+        query: &'a Question,
+        tracer: T,
+    ) -> impl Iterator<Item = crate::Result<Answer>> + 'a
+    where
+        T: Tracer + 'static,
+    {
         self.database
-            .unify_question(query)
-            .map(move |binding| query.answer(&binding))
+            .trace_question(query, Rc::new(tracer))
+            .map(move |binding| match binding.exception() {
+                Some(exception) => Err(crate::Error::runtime(exception.clone())),
+                None => Ok(query.answer(&binding)),
+            })
+    }
+
+    /// Ask a question, returning an iterator over all possible answers along with a profiler
+    /// which measures how often each predicate is called, and how long is spent in each, as the
+    /// answers are found. The search is performed depth-first, and an uncaught exception is
+    /// dropped, as for [`Lumber::ask`][].
+    ///
+    /// The answers are found lazily, so the profile read from the profiler covers only the
+    /// search for the answers taken so far. This way, a question with infinitely many answers
+    /// can still be profiled, by taking only some of them. See [`Profile`][] for the
+    /// measurements collected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Lumber, Question};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lumber = Lumber::from_source("
+    ///     :- pub(grandparent/2).
+    ///     parent(alice, bob).
+    ///     parent(bob, carol).
+    ///     grandparent(A, C) :- parent(A, B), parent(B, C).
+    /// ")?;
+    /// let question = Question::try_from("grandparent(A, carol)")?;
+    /// let (answers, profiler) = lumber.ask_profiled(&question);
+    /// assert_eq!(answers.count(), 1);
+    /// assert_eq!(profiler.profile().entry("parent/2").unwrap().calls(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask_profiled<'a>(
+        &'a self,
+        query: &'a Question,
+    ) -> (impl Iterator<Item = Answer> + 'a, Rc<Profiler>) {
+        let profiler = Rc::new(Profiler::new());
+        (self.ask_traced(query, profiler.clone()), profiler)
+    }
+
+    /// Diagnose why a question has no answers, by searching for an answer while recording the
+    /// calls which fail. If the question does have an answer (or an exception is thrown while
+    /// looking for one), there is nothing to diagnose, and `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Lumber, Question};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lumber = Lumber::from_source("
+    ///     :- pub(grandparent/2).
+    ///     parent(alice, bob).
+    ///     parent(bob, carol).
+    ///     grandparent(A, C) :- parent(A, B), parent(B, C).
+    /// ")?;
+    /// let question = Question::try_from("grandparent(bob, C)")?;
+    /// let diagnosis = lumber.diagnose(&question).unwrap();
+    /// assert_eq!(diagnosis.failures()[0].handle(), "parent/2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diagnose(&self, query: &Question) -> Option<Diagnosis> {
+        self.database.diagnose(query, false, None)
     }
 
     /// Ask a question, checking whether an answer exists. An answer, if it exists, may not
     /// necessarily be fully bound.
     pub fn check<'a>(&'a self, query: &'a Question) -> bool {
-        self.database.unify_question(query).next().is_some()
+        self.ask(query).next().is_some()
     }
 
     pub(crate) fn into_library(self, name: &str) -> (HashMap<Scope, ModuleHeader>, Database<'p>) {
@@ -0,0 +1,253 @@
+use crate::ast::*;
+use crate::climb::Climbable;
+use std::collections::HashMap;
+
+/// A description of a module of a program, including its documentation. Created by
+/// [`Lumber::modules`][crate::Lumber::modules] or [`Analysis::modules`][crate::Analysis::modules].
+///
+/// Documentation is written in comments starting with `///`, on the lines just before the
+/// clause or directive being documented. A module is documented on its `mod` directive, a
+/// predicate on any of its clauses or on a `pub`, `nat`, `mut`, or `inc` directive naming it,
+/// and an operator on its `op` or `pub` directive.
+///
+/// # Examples
+///
+/// ```rust
+/// # use lumber::Lumber;
+/// # fn main() -> lumber::Result<()> {
+/// let lumber = Lumber::from_source("
+///     :- pub(greet/1).
+///
+///     /// Greets someone by name.
+///     greet(Name) :- says(Name, hello).
+///     says(_, _).
+/// ")?;
+/// let modules = lumber.modules();
+/// let greet = &modules[0].predicates()[0];
+/// assert_eq!(greet.handle(), "greet/1");
+/// assert!(greet.is_public());
+/// assert_eq!(greet.doc(), Some("Greets someone by name."));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ModuleInfo {
+    path: String,
+    doc: Option<String>,
+    submodules: Vec<String>,
+    predicates: Vec<PredicateInfo>,
+    operators: Vec<OperatorInfo>,
+}
+
+/// A description of a predicate declared in a module.
+#[derive(Clone, Debug)]
+pub struct PredicateInfo {
+    name: String,
+    handle: String,
+    doc: Option<String>,
+    public: bool,
+    native: bool,
+    mutable: bool,
+    incomplete: bool,
+}
+
+/// A description of an operator defined in a module.
+#[derive(Clone, Debug)]
+pub struct OperatorInfo {
+    name: String,
+    handle: String,
+    doc: Option<String>,
+    public: bool,
+    unary: bool,
+    expression: bool,
+    associativity: Associativity,
+    precedence: usize,
+}
+
+/// The direction in which a sequence of expression operators of the same precedence group.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Associativity {
+    /// Grouped from the left, as in `(a + b) + c`.
+    Left,
+    /// Grouped from the right, as in `a ** (b ** c)`.
+    Right,
+}
+
+impl ModuleInfo {
+    /// Describes each module of a program (but not of the libraries it links), in order of
+    /// their paths.
+    pub(crate) fn all(modules: &HashMap<Scope, ModuleHeader>) -> Vec<Self> {
+        let mut infos: Vec<Self> = modules
+            .values()
+            .filter(|header| header.scope.library().is_empty())
+            .map(|header| Self::new(header, modules))
+            .collect();
+        infos.sort_by(|lhs, rhs| lhs.path.cmp(&rhs.path));
+        infos
+    }
+
+    fn new(header: &ModuleHeader, modules: &HashMap<Scope, ModuleHeader>) -> Self {
+        let depth = header.scope.into_iter().count();
+        let mut submodules: Vec<String> = modules
+            .keys()
+            .filter(|scope| &header.scope > scope && scope.into_iter().count() == depth + 1)
+            .map(ToString::to_string)
+            .collect();
+        submodules.sort();
+
+        let mut predicates: Vec<PredicateInfo> = header
+            .definitions
+            .iter()
+            .chain(&header.natives)
+            .chain(&header.exports)
+            .map(|handle| PredicateInfo {
+                name: handle.scope.head().to_string(),
+                handle: handle.to_string(),
+                doc: header.predicate_docs.get(handle).cloned(),
+                public: header.exports.contains(handle),
+                native: header.natives.contains(handle),
+                mutable: header.mutables.contains(handle),
+                incomplete: header.incompletes.contains(handle),
+            })
+            .collect();
+        predicates.sort_by(|lhs, rhs| lhs.handle.cmp(&rhs.handle));
+        predicates.dedup_by(|lhs, rhs| lhs.handle == rhs.handle);
+
+        let mut operators: Vec<OperatorInfo> = header
+            .operators
+            .iter()
+            .map(|(key, operator)| {
+                let name = key.name();
+                let (expression, arity) = match key {
+                    OpKey::Expression(_, arity) => (true, arity),
+                    OpKey::Relation(_, arity) => (false, arity),
+                };
+                OperatorInfo {
+                    name: name.as_ref().to_owned(),
+                    handle: operator.handle().to_string(),
+                    doc: header.operator_docs.get(&name).cloned(),
+                    public: header.operator_exports.contains(&name),
+                    unary: *arity == OpArity::Unary,
+                    expression,
+                    associativity: match operator.assoc() {
+                        crate::climb::Associativity::Left => Associativity::Left,
+                        crate::climb::Associativity::Right => Associativity::Right,
+                    },
+                    precedence: operator.prec(),
+                }
+            })
+            .collect();
+        operators.sort_by(|lhs, rhs| {
+            (&lhs.name, lhs.expression, lhs.unary).cmp(&(&rhs.name, rhs.expression, rhs.unary))
+        });
+
+        Self {
+            path: header.scope.to_string(),
+            doc: header.doc.clone(),
+            submodules,
+            predicates,
+            operators,
+        }
+    }
+
+    /// The path of this module, such as `animals::cats`. The main module's path is `~`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The documentation of this module, if it has any.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    /// The paths of the modules declared in this module, in order.
+    pub fn submodules(&self) -> &[String] {
+        &self.submodules
+    }
+
+    /// The predicates declared in this module (both public and private), ordered by handle.
+    pub fn predicates(&self) -> &[PredicateInfo] {
+        &self.predicates
+    }
+
+    /// The operators defined in this module (both public and private), ordered by name.
+    pub fn operators(&self) -> &[OperatorInfo] {
+        &self.operators
+    }
+}
+
+impl PredicateInfo {
+    /// The name of this predicate, without its module path or arity.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The full handle of this predicate (e.g. `animals::cat/1`).
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The documentation of this predicate, if it has any.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    /// Whether this predicate is exported from its module.
+    pub fn is_public(&self) -> bool {
+        self.public
+    }
+
+    /// Whether this predicate is a native function.
+    pub fn is_native(&self) -> bool {
+        self.native
+    }
+
+    /// Whether this predicate may be modified at runtime.
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    /// Whether this predicate is incomplete, and may be extended by other modules.
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+}
+
+impl OperatorInfo {
+    /// The name of this operator (e.g. `+`).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The full handle of the predicate by which this operator is implemented.
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The documentation of this operator, if it has any.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    /// Whether this operator is exported from its module.
+    pub fn is_public(&self) -> bool {
+        self.public
+    }
+
+    /// Whether this is a unary (prefix) operator, rather than a binary (infix) one.
+    pub fn is_unary(&self) -> bool {
+        self.unary
+    }
+
+    /// The associativity of this operator, if it is an expression operator. Relation operators
+    /// cannot be chained, so have none.
+    pub fn associativity(&self) -> Option<Associativity> {
+        self.expression.then_some(self.associativity)
+    }
+
+    /// The precedence of this operator, if it is an expression operator. Operators of higher
+    /// precedence bind more tightly.
+    pub fn precedence(&self) -> Option<usize> {
+        self.expression.then_some(self.precedence)
+    }
+}
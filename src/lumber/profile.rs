@@ -0,0 +1,228 @@
+use super::{Event, Port, Tracer};
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// A tracer which measures how often each predicate is called, and how long is spent in each,
+/// while searching for answers. Attach it as any other [`Tracer`][], or use
+/// [`Lumber::ask_profiled`][crate::Lumber::ask_profiled] to profile a single question.
+///
+/// Time is measured from when a predicate is called (or redone) until it exits (or fails), so
+/// the search must be depth-first for the times to be accurate.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    state: RefCell<ProfilerState>,
+}
+
+#[derive(Debug, Default)]
+struct ProfilerState {
+    stack: Vec<Frame>,
+    profile: Profile,
+}
+
+/// A call which has been entered but not yet left.
+#[derive(Debug)]
+struct Frame {
+    handle: String,
+    start: Instant,
+    children: Duration,
+}
+
+/// The measurements collected by a [`Profiler`][].
+///
+/// Displaying a profile prints a table of the measurements of each predicate, with the
+/// predicates in which the most time was spent first.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    entries: BTreeMap<String, ProfileEntry>,
+    stacks: BTreeMap<String, Duration>,
+}
+
+/// The measurements of a single predicate in a [`Profile`][].
+#[derive(Clone, Debug, Default)]
+pub struct ProfileEntry {
+    handle: String,
+    calls: usize,
+    redos: usize,
+    fails: usize,
+    self_time: Duration,
+    total_time: Duration,
+}
+
+impl Profiler {
+    /// Creates a profiler which has not measured anything yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The measurements collected so far.
+    pub fn profile(&self) -> Profile {
+        self.state.borrow().profile.clone()
+    }
+}
+
+impl Tracer for Profiler {
+    fn trace(&self, event: &Event) {
+        let now = Instant::now();
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let entry = state
+            .profile
+            .entries
+            .entry(event.handle().to_owned())
+            .or_insert_with(|| ProfileEntry {
+                handle: event.handle().to_owned(),
+                ..ProfileEntry::default()
+            });
+        match event.port() {
+            Port::Call | Port::Redo => {
+                match event.port() {
+                    Port::Call => entry.calls += 1,
+                    _ => entry.redos += 1,
+                }
+                state.stack.push(Frame {
+                    handle: event.handle().to_owned(),
+                    start: now,
+                    children: Duration::default(),
+                });
+            }
+            Port::Exit | Port::Fail => {
+                if event.port() == Port::Fail {
+                    entry.fails += 1;
+                }
+                let frame = match state.stack.pop() {
+                    Some(frame) => frame,
+                    None => return,
+                };
+                let elapsed = now - frame.start;
+                let self_time = elapsed.checked_sub(frame.children).unwrap_or_default();
+                entry.self_time += self_time;
+                // Time spent in a recursive call is already counted by the outermost call.
+                if state.stack.iter().all(|outer| outer.handle != frame.handle) {
+                    entry.total_time += elapsed;
+                }
+                let mut path: Vec<&str> = state
+                    .stack
+                    .iter()
+                    .map(|outer| outer.handle.as_str())
+                    .collect();
+                path.push(&frame.handle);
+                *state.profile.stacks.entry(path.join(";")).or_default() += self_time;
+                if let Some(parent) = state.stack.last_mut() {
+                    parent.children += elapsed;
+                }
+            }
+        }
+    }
+}
+
+impl Profile {
+    /// The measurements of each predicate which was called, with the predicates in which the
+    /// most time was spent first.
+    pub fn entries(&self) -> Vec<&ProfileEntry> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|entry| Reverse(entry.total_time));
+        entries
+    }
+
+    /// The measurements of a single predicate, given its handle (e.g. `animals::cat/1`).
+    pub fn entry(&self, handle: &str) -> Option<&ProfileEntry> {
+        self.entries.get(handle)
+    }
+
+    /// The total number of inferences made, counted as the number of times any predicate was
+    /// called or redone.
+    pub fn inferences(&self) -> usize {
+        self.entries.values().map(ProfileEntry::inferences).sum()
+    }
+
+    /// Exports the profile in the collapsed stack format used by flamegraph tools, such as
+    /// [`inferno`](https://github.com/jonhoo/inferno). Each line is a stack of handles separated
+    /// by `;`, followed by the time spent in the last of them (not counting the calls it made)
+    /// in microseconds.
+    pub fn collapsed(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(stack, time)| format!("{} {}\n", stack, time.as_micros()))
+            .collect()
+    }
+}
+
+impl ProfileEntry {
+    /// The handle of the predicate (e.g. `animals::cat/1`).
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The number of times the predicate was called.
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    /// The number of times the predicate was asked for another solution after finding one.
+    pub fn redos(&self) -> usize {
+        self.redos
+    }
+
+    /// The number of times the predicate ran out of solutions (or threw an exception).
+    pub fn fails(&self) -> usize {
+        self.fails
+    }
+
+    /// The number of inferences made by the predicate, counted as the number of times it was
+    /// called or redone.
+    pub fn inferences(&self) -> usize {
+        self.calls + self.redos
+    }
+
+    /// The time spent in the predicate itself, not counting the calls it made to others.
+    pub fn self_time(&self) -> Duration {
+        self.self_time
+    }
+
+    /// The time spent in the predicate, including the calls it made to others.
+    pub fn total_time(&self) -> Duration {
+        self.total_time
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let entries = self.entries();
+        let width = entries
+            .iter()
+            .map(|entry| entry.handle.len())
+            .chain(std::iter::once("Predicate".len()))
+            .max()
+            .unwrap();
+        writeln!(
+            f,
+            "{:width$} {:>8} {:>8} {:>8} {:>10} {:>10} {:>10}",
+            "Predicate",
+            "Calls",
+            "Redos",
+            "Fails",
+            "Inferences",
+            "Self (ms)",
+            "Total (ms)",
+            width = width,
+        )?;
+        for entry in entries {
+            writeln!(
+                f,
+                "{:width$} {:>8} {:>8} {:>8} {:>10} {:>10.3} {:>10.3}",
+                entry.handle,
+                entry.calls,
+                entry.redos,
+                entry.fails,
+                entry.inferences(),
+                entry.self_time.as_secs_f64() * 1000.0,
+                entry.total_time.as_secs_f64() * 1000.0,
+                width = width,
+            )?;
+        }
+        Ok(())
+    }
+}
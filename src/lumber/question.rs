@@ -1,7 +1,7 @@
 use super::{Answer, Value};
 use crate::ast::{self, Context};
 use crate::parser::*;
-use crate::program::evaltree::{Body, Variables};
+use crate::program::evaltree::{Body, PatternKind, Variables};
 use crate::program::Binding;
 use std::convert::TryFrom;
 use std::fmt::{self, Display, Formatter};
@@ -64,18 +64,33 @@ impl Question {
 
     /// Uses a binding to extract the answer to this question.
     pub(crate) fn answer(&self, binding: &Binding) -> Answer {
-        self.body
+        let variables: Vec<_> = self
+            .body
             .get_variables()
             .into_iter()
             .filter(|variable| !variable.is_wildcard())
             .map(|var| var.set_current(Some(0)))
+            .collect();
+        let answer: Answer = variables
+            .iter()
             .map(|variable| {
                 (
                     variable.name().to_owned(),
-                    binding.extract(&binding.get(&variable).unwrap()).unwrap(),
+                    binding.extract(&binding.get(variable).unwrap()).unwrap(),
                 )
             })
-            .collect()
+            .collect();
+        if !binding.has_constraints() {
+            return answer;
+        }
+        let names = variables
+            .iter()
+            .filter_map(|variable| match binding.get(variable).unwrap().kind() {
+                PatternKind::Variable(root) => Some((root.clone(), variable.name().to_owned())),
+                _ => None,
+            })
+            .collect();
+        answer.with_residuals(binding.residuals(&names))
     }
 }
 
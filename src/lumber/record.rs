@@ -1,7 +1,9 @@
 use super::Value;
 use crate::program::evaltree::*;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
 /// An implementation of a record which may be incomplete, suitable for Lumber values which
 /// may themselves be unbound.
@@ -26,6 +28,26 @@ impl PartialEq for Record {
     }
 }
 
+impl Eq for Record {}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorted_fields().cmp(&other.sorted_fields())
+    }
+}
+
+impl Hash for Record {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_fields().hash(state)
+    }
+}
+
 impl Record {
     /// Creates a new Lumber record value from a map of possibly unbound Values.
     pub fn new(fields: HashMap<String, Option<Value>>) -> Self {
@@ -54,6 +76,13 @@ impl Record {
         self.fields.iter().map(|(key, value)| (key.as_ref(), value))
     }
 
+    /// The fields of this record, ordered by key.
+    pub(crate) fn sorted_fields(&self) -> Vec<(&Atom, &Option<Value>)> {
+        let mut fields: Vec<_> = self.fields.iter().collect();
+        fields.sort_by_key(|(key, _)| *key);
+        fields
+    }
+
     /// Iterates over the entries of this record, mutably.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Option<Value>)> {
         self.fields
@@ -68,10 +97,7 @@ impl Display for Record {
             write!(f, "{{:}}")
         } else {
             write!(f, "{{ ")?;
-            let mut keys_sorted: Vec<_> = self.fields.keys().collect();
-            keys_sorted.sort();
-            for (i, key) in keys_sorted.iter().enumerate() {
-                let value = self.fields.get(key).unwrap();
+            for (i, (key, value)) in self.sorted_fields().into_iter().enumerate() {
                 if i != 0 {
                     write!(f, ", ")?;
                 }
@@ -0,0 +1,38 @@
+/// The order in which the solver searches for the answers to a question.
+///
+/// Each strategy finds the same answers to a question whose search is finite, but they may be
+/// found in a different order. Where the search is infinite, some answers may never be found by
+/// one strategy but may still be found by another.
+///
+/// Where only the first answer of a goal is kept (the condition of `->>`, or each step of `->`
+/// but the last), or all of its answers are gathered (in an aggregation), that goal is always
+/// searched depth-first and without a bound, so that the outcome is the same as when searching
+/// depth-first. Such a goal which never finishes will prevent answers from being found, whatever
+/// the strategy. Once rules commit to a clause as soon as its head matches, which does not
+/// depend on the strategy.
+///
+/// There is no breadth-first strategy. The solver does not keep a queue of goals which could be
+/// taken in order of depth, but [`Strategy::IterativeDeepening`][] finds answers in the same
+/// order as one would.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Strategy {
+    /// Search each branch fully before moving on to the next, trying the clauses of each
+    /// definition in the order they are written. This is what [`Lumber::ask`][crate::Lumber::ask]
+    /// does, and is the fastest, but a branch which is infinite will prevent any answers after
+    /// it from being found.
+    DepthFirst,
+    /// Search depth-first with an increasing bound on the depth of the search (the number of
+    /// nested predicate calls), never going deeper than the given depth. Answers are found in
+    /// order of the depth of their derivation, as a breadth-first search would find them, and
+    /// the search ends once it is known that no deeper answers exist. With a bound of
+    /// `usize::MAX`, every answer is eventually found.
+    ///
+    /// Each time the bound is increased, the search starts over from the beginning, so native
+    /// functions (and any side effects they have) are run again on each pass.
+    IterativeDeepening(usize),
+    /// Search depth-first, but interleave the answers of each branch of a disjunction or
+    /// definition, and of each binding in a conjunction, so that a branch with infinitely many
+    /// answers does not prevent the answers of the others from being found. A branch which runs
+    /// forever without finding answers will still prevent others from being found.
+    Interleaving,
+}
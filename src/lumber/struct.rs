@@ -4,7 +4,7 @@ use std::fmt::{self, Display, Formatter};
 
 /// A Lumber structure, containing a combination of named and indexed fields. Atoms in Lumber are
 /// the same as structs with no fields.
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct Struct {
     pub(crate) name: Atom,
     pub(crate) contents: Option<Box<Option<Value>>>,
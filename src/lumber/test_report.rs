@@ -0,0 +1,305 @@
+use super::{Diagnosis, Value};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter, Write};
+use std::time::Duration;
+
+/// The results of running the unit tests of a program, created by
+/// [`TestRunner::run`][crate::TestRunner::run].
+///
+/// Displaying a report prints a line for each test, the reasons any tests failed, and a
+/// summary. The report can also be written in the [TAP][] format using [`TestReport::tap`][],
+/// or as JUnit XML using [`TestReport::junit`][], for other tools to consume.
+///
+/// [TAP]: https://testanything.org
+#[derive(Clone, Debug)]
+pub struct TestReport {
+    results: Vec<TestResult>,
+    filtered_out: usize,
+    duration: Duration,
+}
+
+/// The result of running a single unit test.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    pub(crate) name: String,
+    pub(crate) module: String,
+    pub(crate) line: usize,
+    pub(crate) outcome: TestOutcome,
+    pub(crate) duration: Duration,
+}
+
+/// How a unit test turned out.
+#[derive(Clone, Debug)]
+pub enum TestOutcome {
+    /// An answer was found to the body of the test.
+    Passed,
+    /// No answer was found to the body of the test. The diagnosis of why is included, if one
+    /// could be made.
+    Failed(Option<Diagnosis>),
+    /// An exception was thrown, and not caught, while running the test.
+    Exception(Value),
+    /// The test was abandoned after running for longer than the timeout.
+    TimedOut,
+}
+
+impl TestReport {
+    pub(crate) fn new(results: Vec<TestResult>, filtered_out: usize, duration: Duration) -> Self {
+        Self {
+            results,
+            filtered_out,
+            duration,
+        }
+    }
+
+    /// The results of the tests which were run, in the order they were run.
+    pub fn results(&self) -> &[TestResult] {
+        &self.results
+    }
+
+    /// The results of the tests which did not pass.
+    pub fn failures(&self) -> impl Iterator<Item = &TestResult> {
+        self.results.iter().filter(|result| !result.is_passed())
+    }
+
+    /// The number of tests which passed.
+    pub fn passed(&self) -> usize {
+        self.results.len() - self.failed()
+    }
+
+    /// The number of tests which did not pass, including those which threw an exception or
+    /// timed out.
+    pub fn failed(&self) -> usize {
+        self.failures().count()
+    }
+
+    /// The number of tests which were not run, as they were excluded by the module or name
+    /// filters.
+    pub fn filtered_out(&self) -> usize {
+        self.filtered_out
+    }
+
+    /// The time taken to run all of the tests.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether every test which was run passed.
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// Writes this report in version 13 of the Test Anything Protocol. The reason each test
+    /// failed is included as a YAML block.
+    pub fn tap(&self) -> String {
+        let mut output = format!("TAP version 13\n1..{}\n", self.results.len());
+        for (index, result) in self.results.iter().enumerate() {
+            writeln!(
+                output,
+                "{} {} - {}",
+                if result.is_passed() { "ok" } else { "not ok" },
+                index + 1,
+                result.title().replace('\\', "\\\\").replace('#', "\\#"),
+            )
+            .unwrap();
+            output.push_str("  ---\n");
+            writeln!(output, "  module: {:?}", result.module).unwrap();
+            writeln!(output, "  line: {}", result.line).unwrap();
+            writeln!(output, "  duration_ms: {:.3}", millis(result.duration)).unwrap();
+            if let Some(message) = result.message() {
+                output.push_str("  message: |\n");
+                for line in message.lines() {
+                    writeln!(output, "    {}", line).unwrap();
+                }
+            }
+            output.push_str("  ...\n");
+        }
+        output
+    }
+
+    /// Writes this report as JUnit XML, with a test suite for each module which has tests.
+    /// Tests which fail are reported as failures, while those which throw an exception or time
+    /// out are reported as errors.
+    pub fn junit(&self) -> String {
+        let mut suites = BTreeMap::<&str, Vec<&TestResult>>::new();
+        for result in &self.results {
+            suites.entry(&result.module).or_default().push(result);
+        }
+        let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writeln!(
+            output,
+            "<testsuites name=\"lumber\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">",
+            self.results.len(),
+            count(&self.results, is_failure),
+            count(&self.results, is_error),
+            self.duration.as_secs_f64(),
+        )
+        .unwrap();
+        for (module, results) in suites {
+            let time: Duration = results.iter().map(|result| result.duration).sum();
+            writeln!(
+                output,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">",
+                escape(module),
+                results.len(),
+                count(results.iter().copied(), is_failure),
+                count(results.iter().copied(), is_error),
+                time.as_secs_f64(),
+            )
+            .unwrap();
+            for result in results {
+                write!(
+                    output,
+                    "    <testcase name=\"{}\" classname=\"{}\" line=\"{}\" time=\"{:.6}\"",
+                    escape(&result.name),
+                    escape(module),
+                    result.line,
+                    result.duration.as_secs_f64(),
+                )
+                .unwrap();
+                let element = match result.outcome {
+                    TestOutcome::Passed => {
+                        output.push_str("/>\n");
+                        continue;
+                    }
+                    TestOutcome::Failed(..) => "failure",
+                    TestOutcome::Exception(..) | TestOutcome::TimedOut => "error",
+                };
+                let message = result.message().unwrap_or_default();
+                writeln!(
+                    output,
+                    ">\n      <{} message=\"{}\">{}</{}>\n    </testcase>",
+                    element,
+                    escape(message.lines().next().unwrap_or_default()),
+                    escape(&message),
+                    element,
+                )
+                .unwrap();
+            }
+            output.push_str("  </testsuite>\n");
+        }
+        output.push_str("</testsuites>\n");
+        output
+    }
+}
+
+impl TestResult {
+    /// The name of the test.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The path of the module in which the test is defined (e.g. `animals::cats`).
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// The line on which the test is defined, counted from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// How the test turned out.
+    pub fn outcome(&self) -> &TestOutcome {
+        &self.outcome
+    }
+
+    /// The time taken to run the test.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether the test passed.
+    pub fn is_passed(&self) -> bool {
+        matches!(self.outcome, TestOutcome::Passed)
+    }
+
+    /// The name of the test, qualified by its module.
+    fn title(&self) -> String {
+        format!("{}: {}", self.module, self.name)
+    }
+
+    /// Why the test did not pass, if it did not.
+    pub(crate) fn message(&self) -> Option<String> {
+        match &self.outcome {
+            TestOutcome::Passed => None,
+            TestOutcome::Failed(None) => Some("No answer was found.".to_owned()),
+            TestOutcome::Failed(Some(diagnosis)) => Some(format!(
+                "No answer was found.\n{}",
+                diagnosis.to_string().trim_end()
+            )),
+            TestOutcome::Exception(exception) => Some(format!("Uncaught exception: {}", exception)),
+            TestOutcome::TimedOut => Some("Timed out.".to_owned()),
+        }
+    }
+}
+
+impl Display for TestReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "running {} tests", self.results.len())?;
+        for result in &self.results {
+            writeln!(
+                f,
+                "test {} ... {} ({:.3} ms)",
+                result.title(),
+                match result.outcome {
+                    TestOutcome::Passed => "ok",
+                    TestOutcome::Failed(..) => "FAILED",
+                    TestOutcome::Exception(..) => "EXCEPTION",
+                    TestOutcome::TimedOut => "TIMED OUT",
+                },
+                millis(result.duration),
+            )?;
+        }
+        if !self.is_success() {
+            writeln!(f, "\nfailures:")?;
+            for result in self.failures() {
+                writeln!(f, "\n---- {} (line {})", result.title(), result.line)?;
+                for line in result.message().unwrap_or_default().lines() {
+                    writeln!(f, "\t{}", line)?;
+                }
+            }
+        }
+        writeln!(
+            f,
+            "\ntest result: {}. {} passed; {} failed; {} filtered out; finished in {:.3} ms",
+            if self.is_success() { "ok" } else { "FAILED" },
+            self.passed(),
+            self.failed(),
+            self.filtered_out,
+            millis(self.duration),
+        )
+    }
+}
+
+/// Counts the results with the given kind of outcome.
+fn count<'a>(
+    results: impl IntoIterator<Item = &'a TestResult>,
+    kind: fn(&TestOutcome) -> bool,
+) -> usize {
+    results
+        .into_iter()
+        .filter(|result| kind(&result.outcome))
+        .count()
+}
+
+/// Whether the test failed, as opposed to being abandoned with an error.
+fn is_failure(outcome: &TestOutcome) -> bool {
+    matches!(outcome, TestOutcome::Failed(..))
+}
+
+/// Whether the test was abandoned, by throwing an exception or timing out.
+fn is_error(outcome: &TestOutcome) -> bool {
+    matches!(outcome, TestOutcome::Exception(..) | TestOutcome::TimedOut)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
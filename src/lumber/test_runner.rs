@@ -0,0 +1,129 @@
+use super::{Lumber, Question, TestOutcome, TestReport, TestResult};
+use crate::ast::UnitTest;
+use crate::program::Deadline;
+use std::time::{Duration, Instant};
+
+/// Runs the unit tests of a program, defined by the `:- test(...)` directive. Created by
+/// [`Lumber::tests`][].
+///
+/// A test may be given a name, as in `:- test("cats meow", says(cat, meow)).`, otherwise it is
+/// named by its body. Tests can be narrowed down by module or by name, and each can be given a
+/// time limit, before being run.
+///
+/// # Examples
+///
+/// ```rust
+/// # use lumber::Lumber;
+/// # fn main() -> lumber::Result<()> {
+/// let lumber = Lumber::from_source(r#"
+///     says(cat, meow).
+///     :- test("cats meow", says(cat, meow)).
+///     :- test("cats bark", says(cat, woof)).
+/// "#)?;
+/// let report = lumber.tests().filter("meow").run();
+/// assert!(report.is_success());
+/// assert_eq!(report.passed(), 1);
+/// assert_eq!(report.filtered_out(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestRunner<'a, 'p> {
+    program: &'a Lumber<'p>,
+    module: Option<String>,
+    filter: Option<String>,
+    timeout: Option<Duration>,
+}
+
+impl<'a, 'p> TestRunner<'a, 'p> {
+    pub(crate) fn new(program: &'a Lumber<'p>) -> Self {
+        Self {
+            program,
+            module: None,
+            filter: None,
+            timeout: None,
+        }
+    }
+
+    /// Runs only the tests defined in the module at the given path (e.g. `animals::cats`), or
+    /// in its submodules. The main module's path is `~`, which includes every test.
+    pub fn module<S: Into<String>>(mut self, path: S) -> Self {
+        self.module = Some(path.into());
+        self
+    }
+
+    /// Runs only the tests whose names contain the given text.
+    pub fn filter<S: Into<String>>(mut self, text: S) -> Self {
+        self.filter = Some(text.into());
+        self
+    }
+
+    /// Sets the time each test may run for before it is abandoned and reported as having timed
+    /// out. Tests may run for any amount of time by default.
+    ///
+    /// The time is checked each time a predicate is called, and each time a native function
+    /// produces an answer, so a native function which runs for a long time before producing an
+    /// answer is not interrupted.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Runs the selected tests, in the order of their modules, then of their locations in the
+    /// source code.
+    pub fn run(&self) -> TestReport {
+        let start = Instant::now();
+        let mut results = vec![];
+        let mut filtered_out = 0;
+        for test in &self.program.tests {
+            if self.includes(test) {
+                results.push(self.run_test(test));
+            } else {
+                filtered_out += 1;
+            }
+        }
+        TestReport::new(results, filtered_out, start.elapsed())
+    }
+
+    fn includes(&self, test: &UnitTest) -> bool {
+        let in_module = match self.module.as_deref() {
+            None | Some("~") => true,
+            Some(path) => {
+                let module = test.module.to_string();
+                module == path || module.starts_with(&format!("{}::", path))
+            }
+        };
+        let matches = match &self.filter {
+            None => true,
+            Some(text) => test.name.contains(text.as_str()),
+        };
+        in_module && matches
+    }
+
+    fn run_test(&self, test: &UnitTest) -> TestResult {
+        let database = &self.program.database;
+        let question = Question::new(test.body.clone());
+        let start = Instant::now();
+        let deadline = self.timeout.map(|timeout| Deadline::new(start + timeout));
+        let answer = database.unify_test(&question, deadline.clone()).next();
+        let duration = start.elapsed();
+        let timed_out = deadline.as_ref().is_some_and(Deadline::is_reached);
+        let outcome = match answer {
+            // Once abandoned, every branch of the search fails, so any answer found may only
+            // be due to another having failed.
+            _ if timed_out => TestOutcome::TimedOut,
+            Some(binding) => match binding.exception() {
+                Some(exception) => TestOutcome::Exception(exception.clone()),
+                None => TestOutcome::Passed,
+            },
+            None => TestOutcome::Failed(database.diagnose(&question, true, deadline)),
+        };
+        TestResult {
+            name: test.name.clone(),
+            module: test.module.to_string(),
+            line: test.location.line,
+            outcome,
+            duration,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use super::Value;
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::Write;
+use std::rc::Rc;
+
+/// Receives the events of a search for answers, as the solver calls predicates and moves
+/// between their solutions. Attach a tracer to all questions asked of a program using
+/// [`LumberBuilder::tracer`][crate::LumberBuilder::tracer], or to a single question using
+/// [`Lumber::ask_traced`][crate::Lumber::ask_traced].
+///
+/// See [`TextTracer`][] for a tracer which writes each event as a line of text.
+pub trait Tracer {
+    /// Called for each event, in the order they happen.
+    fn trace(&self, event: &Event);
+}
+
+impl<T> Tracer for Rc<T>
+where
+    T: Tracer + ?Sized,
+{
+    fn trace(&self, event: &Event) {
+        self.as_ref().trace(event)
+    }
+}
+
+impl Debug for dyn Tracer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Tracer")
+    }
+}
+
+/// The ways in which a call to a predicate is entered or left.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Port {
+    /// The predicate is called for the first time.
+    Call,
+    /// The predicate has found a solution.
+    Exit,
+    /// The predicate is asked for another solution after having found one.
+    Redo,
+    /// The predicate has no more solutions. A call which throws an exception also fails.
+    Fail,
+}
+
+impl Display for Port {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Port::Call => write!(f, "call"),
+            Port::Exit => write!(f, "exit"),
+            Port::Redo => write!(f, "redo"),
+            Port::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// An event in the search for answers to a question, reported to a [`Tracer`][].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub(crate) port: Port,
+    pub(crate) name: String,
+    pub(crate) handle: String,
+    pub(crate) arguments: Vec<Option<Value>>,
+    pub(crate) bindings: Vec<(String, Option<Value>)>,
+    pub(crate) clause: Option<usize>,
+    pub(crate) depth: usize,
+    pub(crate) abandoned: Rc<Cell<bool>>,
+}
+
+impl Event {
+    /// The port through which the call was entered or left.
+    pub fn port(&self) -> Port {
+        self.port
+    }
+
+    /// The handle of the predicate which was called (e.g. `animals::cat/1`).
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    /// The values of the arguments of the call. On exit, these are the values in the solution
+    /// that was found, and otherwise they are the values at the time the call was made.
+    /// Arguments which are unbound are `None`.
+    pub fn arguments(&self) -> &[Option<Value>] {
+        &self.arguments
+    }
+
+    /// The values of the variables of the question being answered, as they are bound at the
+    /// time of the event, in order of their names. Variables which are unbound are `None`.
+    pub fn bindings(&self) -> &[(String, Option<Value>)] {
+        &self.bindings
+    }
+
+    /// The index of the clause of the predicate's definition which found the solution being
+    /// exited or redone, counting from 0 in the order the clauses were written. This is `None`
+    /// for calls and failures, and for native functions, which do not have clauses.
+    pub fn clause(&self) -> Option<usize> {
+        self.clause
+    }
+
+    /// The depth of the call, counted as the number of nested predicate calls made to reach
+    /// it. Calls made directly by a question are at depth 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Abandons the search, so that no more predicates are called and no more answers are
+    /// found. The search ends as soon as control returns to the solver.
+    pub fn abandon(&self) {
+        self.abandoned.set(true);
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}(", self.port, self.name)?;
+        for (i, argument) in self.arguments.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            match argument {
+                Some(value) => write!(f, "{}", value)?,
+                None => write!(f, "_")?,
+            }
+        }
+        write!(f, ")")?;
+        if let Some(clause) = self.clause {
+            write!(f, " by clause {}", clause)?;
+        }
+        Ok(())
+    }
+}
+
+/// A tracer which writes each event on its own line, indented by the depth of the call.
+/// Errors while writing are ignored, so as not to interrupt the search.
+///
+/// # Examples
+///
+/// ```rust
+/// # use lumber::{Lumber, Question, TextTracer};
+/// # use std::convert::TryFrom;
+/// # use std::rc::Rc;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let lumber = Lumber::from_source("
+///     :- pub(grandparent/2).
+///     parent(alice, bob).
+///     parent(bob, carol).
+///     grandparent(A, C) :- parent(A, B), parent(B, C).
+/// ")?;
+/// let question = Question::try_from("grandparent(alice, C)")?;
+/// let tracer = Rc::new(TextTracer::new(vec![]));
+/// lumber.ask_traced(&question, tracer.clone()).for_each(drop);
+/// let trace = String::from_utf8(Rc::try_unwrap(tracer).ok().unwrap().into_inner())?;
+/// assert!(trace.starts_with("call grandparent(alice, _)\n  call parent(alice, _)\n"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TextTracer<W> {
+    writer: RefCell<W>,
+}
+
+impl<W> TextTracer<W>
+where
+    W: Write,
+{
+    /// Creates a tracer which writes to the given writer (e.g. [`std::io::stderr`][]).
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: RefCell::new(writer),
+        }
+    }
+
+    /// Takes the writer back out of the tracer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+impl<W> Tracer for TextTracer<W>
+where
+    W: Write,
+{
+    fn trace(&self, event: &Event) {
+        let indent = event.depth.saturating_sub(1) * 2;
+        writeln!(
+            self.writer.borrow_mut(),
+            "{:indent$}{}",
+            "",
+            event,
+            indent = indent
+        )
+        .ok();
+    }
+}
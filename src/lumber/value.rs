@@ -3,8 +3,10 @@ use super::{List, Record, Struct};
 use crate::program::evaltree::{Literal, Pattern, PatternKind};
 use ramp::{int::Int, rational::Rational};
 use std::any::Any;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 /// Basic untyped values as understood by Lumber.
@@ -42,6 +44,66 @@ impl PartialEq for Value {
     }
 }
 
+/// Values are totally ordered according to the "standard order of terms". Values of different
+/// types are ordered as follows:
+///
+/// 1.  Numbers, where integers and rationals are compared by value. A rational comes before an
+///     integer of the same value.
+/// 2.  Atoms, alphabetically.
+/// 3.  Strings, lexicographically.
+/// 4.  Structs, by name and then by their contents.
+/// 5.  Lists, element by element, where a prefix comes before any longer list.
+/// 6.  Records, by their fields in order of their keys.
+/// 7.  Opaque Rust values, in an arbitrary (but consistent) order.
+///
+/// Where values may be unbound (`Option<Value>`), unbound values come before all bound values,
+/// and are equal to each other. As only the variables within a value may be unbound, the
+/// `@core::compare/3` predicate throws an instantiation error if either value it is given is
+/// itself unbound.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| match (self, other) {
+                (Value::Integer(lhs), Value::Integer(rhs)) => lhs.cmp(rhs),
+                (Value::Rational(lhs), Value::Rational(rhs)) => lhs.cmp(rhs),
+                (Value::Integer(lhs), Value::Rational(rhs)) => {
+                    Rational::from(lhs.clone()).cmp(rhs).then(Ordering::Greater)
+                }
+                (Value::Rational(lhs), Value::Integer(rhs)) => {
+                    lhs.cmp(&Rational::from(rhs.clone())).then(Ordering::Less)
+                }
+                (Value::String(lhs), Value::String(rhs)) => lhs.cmp(rhs),
+                (Value::List(lhs), Value::List(rhs)) => lhs.cmp(rhs),
+                (Value::Struct(lhs), Value::Struct(rhs)) => lhs.cmp(rhs),
+                (Value::Record(lhs), Value::Record(rhs)) => lhs.cmp(rhs),
+                (Value::Any(lhs), Value::Any(rhs)) => Rc::as_ptr(lhs).cmp(&Rc::as_ptr(rhs)),
+                _ => unreachable!(),
+            })
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Integer(int) => int.hash(state),
+            Value::Rational(rat) => rat.hash(state),
+            Value::String(string) => string.hash(state),
+            Value::List(list) => list.hash(state),
+            Value::Record(record) => record.hash(state),
+            Value::Struct(structure) => structure.hash(state),
+            Value::Any(any) => Rc::as_ptr(any).hash(state),
+        }
+    }
+}
+
 macro_rules! is_variant {
     ($name:ident, $variant:ident) => {
         /// Gets this value as a $ty.
@@ -76,6 +138,19 @@ macro_rules! as_variant_mut {
 }
 
 impl Value {
+    /// The position of this value's type in the standard order of terms.
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Integer(..) | Value::Rational(..) => 0,
+            Value::Struct(structure) if structure.is_atom() => 1,
+            Value::String(..) => 2,
+            Value::Struct(..) => 3,
+            Value::List(..) => 4,
+            Value::Record(..) => 5,
+            Value::Any(..) => 6,
+        }
+    }
+
     /// Constructs an integer value.
     pub fn integer(int: impl Into<Int>) -> Self {
         Self::Integer(int.into())
@@ -112,11 +187,18 @@ impl Value {
     as_variant!(as_struct, Struct, Struct);
     as_variant_mut!(as_struct_mut, Struct, Struct);
 
+    /// Checks whether this value is an atom (a struct with no contents).
+    pub fn is_atom(&self) -> bool {
+        matches!(self, Self::Struct(structure) if structure.is_atom())
+    }
+
     /// Constructs a Lumber value containing an unknown Rust value.
     pub fn any(any: impl Any) -> Self {
         Self::Any(Rc::new(Box::new(any)))
     }
 
+    is_variant!(is_any, Any);
+
     /// Constructs a Lumber value containing a list of other values.
     pub fn list<V>(values: impl IntoIterator<Item = V>) -> Self
     where
@@ -143,6 +225,29 @@ impl Value {
         matches!(self, Self::List(..) | Self::Record(..))
     }
 
+    /// Checks whether this value is fully known, containing no unbound values, and no
+    /// incomplete lists or records.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use lumber::{Value, list};
+    /// assert!(list![1, 2, 3].is_ground());
+    /// assert!(!list![1, _, 3].is_ground());
+    /// ```
+    pub fn is_ground(&self) -> bool {
+        fn is_ground(value: &Option<Value>) -> bool {
+            value.as_ref().map(Value::is_ground).unwrap_or(false)
+        }
+
+        match self {
+            Self::List(list) => list.complete && list.values.iter().all(is_ground),
+            Self::Record(record) => record.complete && record.fields.values().all(is_ground),
+            Self::Struct(structure) => structure.contents().map(is_ground).unwrap_or(true),
+            _ => true,
+        }
+    }
+
     /// Constructs a Lumber value by serializing a Rust value using Serde.
     #[cfg(feature = "serde")]
     pub fn serialize<T: serde::Serialize>(value: &T) -> crate::Result<Self> {
@@ -220,6 +325,20 @@ where
     }
 }
 
+macro_rules! from_variant {
+    ($t:ident) => {
+        impl From<$t> for Value {
+            fn from(inner: $t) -> Self {
+                Self::$t(inner)
+            }
+        }
+    };
+}
+
+from_variant!(List);
+from_variant!(Record);
+from_variant!(Struct);
+
 impl From<Pattern> for Option<Value> {
     fn from(pattern: Pattern) -> Self {
         match pattern.kind().clone() {
@@ -340,4 +459,53 @@ mod test {
             "####'te'###'st'####",
         );
     }
+
+    #[test]
+    fn standard_order() {
+        let mut values = vec![
+            Some(record! { "a" => 1 }),
+            Some(list![1, 2]),
+            Some(Value::Struct(Struct::new("a", Some(Value::from(1))))),
+            Some(Value::string("a")),
+            Some(Value::atom("b")),
+            Some(Value::atom("a")),
+            Some(Value::integer(2)),
+            Some(Value::integer(1)),
+            Some(Value::rational(1)),
+            Some(list![1]),
+            None,
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                None,
+                Some(Value::rational(1)),
+                Some(Value::integer(1)),
+                Some(Value::integer(2)),
+                Some(Value::atom("a")),
+                Some(Value::atom("b")),
+                Some(Value::string("a")),
+                Some(Value::Struct(Struct::new("a", Some(Value::from(1))))),
+                Some(list![1]),
+                Some(list![1, 2]),
+                Some(record! { "a" => 1 }),
+            ],
+        );
+    }
+
+    #[test]
+    fn hash_consistent_with_eq() {
+        use std::collections::HashSet;
+        let values: HashSet<_> = vec![
+            record! { "a" => 1, "b" => 2 },
+            record! { "b" => 2, "a" => 1 },
+            Value::integer(1),
+            Value::rational(1),
+            Value::integer(1),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(values.len(), 3);
+    }
 }
@@ -2,7 +2,10 @@
 
 #![allow(clippy::upper_case_acronyms)]
 
+use pest::error::InputLocation;
 use pest::Parser as _;
+use std::collections::HashMap;
+use std::ops::Range;
 
 /// A PEG parser for the Lumber language.
 #[derive(pest_derive::Parser)]
@@ -14,6 +17,44 @@ impl Parser {
         Ok(Self::parse(Rule::module, source_code)?)
     }
 
+    /// Finds the syntax errors in a module which does not parse, recovering from each by
+    /// blanking out the statement (directive or clause) in which it was found and trying again.
+    /// Returns the source with each erroneous statement blanked out, which parses as a module,
+    /// along with the errors that were found.
+    ///
+    /// Blanking replaces each character with a space (other than line breaks), so that lines and
+    /// columns in the recovered source match those in the original.
+    pub fn recover_module(source_code: &str) -> (String, Vec<crate::Error>) {
+        let mut source = source_code.to_owned();
+        let mut errors = vec![];
+        while let Err(error) = Self::parse(Rule::module, &source) {
+            let position = match error.location {
+                InputLocation::Pos(position) => position,
+                InputLocation::Span((start, _)) => start,
+            };
+            errors.push(error.into());
+            let statements: Vec<_> = statements(&source)
+                .into_iter()
+                .filter(|statement| !source[statement.clone()].trim().is_empty())
+                .collect();
+            // An error at the end of the input, such as a missing final `.`, is in the last
+            // statement.
+            let statement = statements
+                .iter()
+                .find(|statement| position < statement.end)
+                .or_else(|| statements.last())
+                .cloned()
+                .unwrap_or(0..source.len());
+            let blanked = blank(&source[statement.clone()]);
+            if blanked == source[statement.clone()] {
+                source = blank(&source);
+                break;
+            }
+            source.replace_range(statement, &blanked);
+        }
+        (source, errors)
+    }
+
     pub fn parse_handle(source_code: &str) -> crate::Result<crate::Pairs> {
         Ok(Self::parse(Rule::external_handle, source_code)?)
     }
@@ -23,5 +64,141 @@ impl Parser {
     }
 }
 
+/// The statements and comments of source code, found by scanning it without parsing.
+struct Scan {
+    statements: Vec<Range<usize>>,
+    comments: Vec<Range<usize>>,
+}
+
+/// Splits source code into statements, each ending just after the `.` which terminates it. Any
+/// source after the last terminator is included as a final statement. Terminators are found
+/// without parsing, skipping over strings, quoted atoms, and comments, so this works even for
+/// source code which does not parse.
+fn statements(source: &str) -> Vec<Range<usize>> {
+    scan(source).statements
+}
+
+/// Finds the comments in source code, in order. The range of a line comment does not include
+/// the line break which ends it.
+pub(crate) fn comments(source: &str) -> Vec<Range<usize>> {
+    scan(source).comments
+}
+
+/// Finds the documentation comments (lines starting with `///`) in source code, keyed by the
+/// position of the statement that each documents. Consecutive lines of documentation are joined,
+/// and belong to the statement which follows them, as long as nothing but whitespace comes in
+/// between.
+pub(crate) fn doc_comments(source: &str) -> HashMap<usize, String> {
+    let mut docs = HashMap::new();
+    let mut lines = vec![];
+    let mut end = 0;
+    for comment in comments(source) {
+        if !lines.is_empty() && !source[end..comment.start].trim().is_empty() {
+            attach_doc(source, end, &mut lines, &mut docs);
+        }
+        let text = &source[comment.clone()];
+        if text.starts_with("///") && !text.starts_with("////") {
+            let line = text[3..].trim_end();
+            lines.push(line.strip_prefix(' ').unwrap_or(line));
+            end = comment.end;
+        } else {
+            lines.clear();
+        }
+    }
+    if !lines.is_empty() {
+        attach_doc(source, end, &mut lines, &mut docs);
+    }
+    docs
+}
+
+fn attach_doc(source: &str, end: usize, lines: &mut Vec<&str>, docs: &mut HashMap<usize, String>) {
+    let start = source.len() - source[end..].trim_start().len();
+    if start < source.len() {
+        docs.insert(start, lines.join("\n"));
+    }
+    lines.clear();
+}
+
+fn scan(source: &str) -> Scan {
+    let mut statements = vec![];
+    let mut comments = vec![];
+    let mut start = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let rest = &source[index..];
+        if rest.starts_with("//") {
+            let end = chars
+                .find(|&(_, ch)| ch == '\n')
+                .map_or(source.len(), |(end, _)| end);
+            comments.push(index..end);
+        } else if rest.starts_with("/*") {
+            chars.next();
+            let mut depth = 1;
+            let mut end = source.len();
+            while depth > 0 {
+                match chars.next() {
+                    Some((index, '/')) if source[index..].starts_with("/*") => {
+                        chars.next();
+                        depth += 1;
+                    }
+                    Some((index, '*')) if source[index..].starts_with("*/") => {
+                        chars.next();
+                        depth -= 1;
+                        end = index + 2;
+                    }
+                    Some(..) => {}
+                    None => {
+                        end = source.len();
+                        break;
+                    }
+                }
+            }
+            comments.push(index..end);
+        } else if let Some(quote) = rest
+            .trim_start_matches('#')
+            .chars()
+            .next()
+            .filter(|&quote| (quote == '"' || quote == '\'') && (ch == '#' || ch == quote))
+        {
+            let hashes = rest.len() - rest.trim_start_matches('#').len();
+            let closing = format!("{}{}", quote, "#".repeat(hashes));
+            let body = index + hashes + 1;
+            let end = source[body..]
+                .find(&closing)
+                .map_or(source.len(), |end| body + end + closing.len());
+            while chars.next_if(|&(index, _)| index < end).is_some() {}
+        } else if ch == '.' {
+            if rest.starts_with("..") {
+                chars.next();
+                continue;
+            }
+            let after = &rest[1..];
+            let terminates = after.is_empty()
+                || after.starts_with(char::is_whitespace)
+                || after.starts_with("//")
+                || after.starts_with("/*");
+            if terminates {
+                statements.push(start..index + 1);
+                start = index + 1;
+            }
+        }
+    }
+    if start < source.len() {
+        statements.push(start..source.len());
+    }
+    Scan {
+        statements,
+        comments,
+    }
+}
+
+/// Replaces every character of the source, other than line breaks, with a space.
+fn blank(source: &str) -> String {
+    source
+        .chars()
+        .map(|ch| if ch == '\n' || ch == '\r' { ch } else { ' ' })
+        .collect()
+}
+
 #[cfg(test)]
 mod test;
@@ -7,4 +7,6 @@ yes!(directive_pub, Rule::directive, ":- pub(hello/2).");
 yes!(directive_mut, Rule::directive, ":- mut(hello/2).");
 yes!(directive_inc, Rule::directive, ":- inc(hello/2).");
 yes!(directive_test, Rule::directive, ":- test(hello(a, b)).");
+yes!(directive_test_named, Rule::directive, r#":- test("says hello", hello(a, b))."#);
+yes!(directive_allow, Rule::directive, ":- allow(unused_predicate).");
 no!(directive_other, Rule::directive, ":- what.");
@@ -1,9 +1,98 @@
 use super::unification::unify_patterns_new_generation;
 use crate::program::evaltree::*;
-use crate::Value;
+use crate::{Diagnosis, Tracer, Value};
 use im_rc::{HashMap, OrdMap, Vector};
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+use std::time::Instant;
+
+mod constraint;
+mod domain;
+
+pub(crate) use constraint::{Constraint, Relation};
+pub(crate) use domain::Domain;
+
+/// A goal which has been suspended until a variable is bound.
+#[derive(Clone, Debug)]
+pub(crate) struct Suspension {
+    /// The variable which must be bound before the goal is resumed.
+    pub variable: Pattern,
+    pub goal: SuspendedGoal,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum SuspendedGoal {
+    /// A goal value passed to a builtin (such as `freeze/2`), to be called from the predicate
+    /// in which it was suspended.
+    Call {
+        builtin: Handle,
+        goal: Pattern,
+        predicate: Option<Handle>,
+        public: bool,
+    },
+    /// A call to a native function which was not bound enough to satisfy any of its modes.
+    Native { handle: Handle, args: Vec<Pattern> },
+}
+
+/// A bound on the number of nested predicate calls which may be made while searching for a
+/// solution. All bindings in the same search share whether the bound has been reached.
+#[derive(Clone, Debug)]
+pub(crate) struct DepthLimit {
+    depth: usize,
+    reached: Rc<Cell<bool>>,
+}
+
+impl DepthLimit {
+    pub fn new(depth: usize, reached: Rc<Cell<bool>>) -> Self {
+        Self { depth, reached }
+    }
+}
+
+/// A time after which the search is abandoned. All bindings in the same search share whether
+/// the deadline has been reached, so that every branch is abandoned once one finds that it has.
+#[derive(Clone, Debug)]
+pub(crate) struct Deadline {
+    time: Instant,
+    reached: Rc<Cell<bool>>,
+}
+
+impl Deadline {
+    pub fn new(time: Instant) -> Self {
+        Self {
+            time,
+            reached: Rc::default(),
+        }
+    }
+
+    /// Checks whether the deadline has been reached, remembering if it has.
+    pub fn check(&self) -> bool {
+        if !self.reached.get() && Instant::now() >= self.time {
+            self.reached.set(true);
+        }
+        self.reached.get()
+    }
+
+    /// Whether the deadline was found to have been reached during the search.
+    pub fn is_reached(&self) -> bool {
+        self.reached.get()
+    }
+}
+
+/// A step in the derivation of a binding.
+#[derive(Clone, Debug)]
+pub(crate) enum Inference {
+    /// A clause of a definition was used to unify a query with the given arguments.
+    Enter {
+        handle: Handle,
+        clause: usize,
+        location: Location,
+        args: Vec<Pattern>,
+    },
+    /// The body of the most recently entered clause was completed.
+    Exit,
+}
 
 /// A binding of variables. Not all of the variables are necessarily bound, but together they
 /// represent a valid solution to a query.
@@ -12,6 +101,33 @@ pub(crate) struct Binding {
     variables: HashMap<Variable, Pattern>,
     generations: Vec<usize>,
     next_generation: usize,
+    /// The exception that has been thrown, if any. A binding in which an exception has been
+    /// thrown is not a solution, and is passed along unchanged until it is caught.
+    exception: Option<Value>,
+    /// Goals which are waiting for a variable to be bound before they are run.
+    suspended: Vector<Suspension>,
+    /// Constraints which must continue to hold as variables are bound.
+    constraints: Vector<Constraint>,
+    /// The domains of the integer variables which have been constrained.
+    domains: HashMap<Variable, Domain>,
+    /// Whether the branches of disjunctions and definitions should be interleaved, rather than
+    /// being searched one after the other.
+    fair: bool,
+    /// The bound on the depth of the search, if there is one.
+    limit: Option<DepthLimit>,
+    /// The time after which the search is abandoned, if there is one.
+    deadline: Option<Deadline>,
+    /// Whether a tracer has abandoned the search. All bindings in the same search share this,
+    /// so that every branch is abandoned at once.
+    abandoned: Rc<Cell<bool>>,
+    /// The greatest depth of the predicate calls made to reach this binding.
+    depth: usize,
+    /// The derivation of this binding, if it is being recorded.
+    derivation: Option<Vector<Inference>>,
+    /// The record of failed calls shared by all bindings in the search, if it is being kept.
+    diagnosis: Option<Rc<RefCell<Diagnosis>>>,
+    /// The tracer to which the events of the search from this binding are reported, if any.
+    tracer: Option<Rc<dyn Tracer>>,
 }
 
 #[cfg(test)]
@@ -21,6 +137,18 @@ impl Default for Binding {
             variables: HashMap::default(),
             generations: vec![0],
             next_generation: 1,
+            exception: None,
+            suspended: Vector::new(),
+            constraints: Vector::new(),
+            domains: HashMap::new(),
+            fair: false,
+            limit: None,
+            deadline: None,
+            abandoned: Rc::default(),
+            depth: 0,
+            derivation: None,
+            diagnosis: None,
+            tracer: None,
         }
     }
 }
@@ -36,6 +164,18 @@ impl Binding {
                 .collect(),
             generations: vec![0],
             next_generation: 1,
+            exception: None,
+            suspended: Vector::new(),
+            constraints: Vector::new(),
+            domains: HashMap::new(),
+            fair: false,
+            limit: None,
+            deadline: None,
+            abandoned: Rc::default(),
+            depth: 0,
+            derivation: None,
+            diagnosis: None,
+            tracer: None,
         }
     }
 
@@ -51,6 +191,156 @@ impl Binding {
         pattern
     }
 
+    pub fn throw(&self, exception: Value) -> Self {
+        let mut binding = self.clone();
+        binding.exception = Some(exception);
+        binding
+    }
+
+    pub fn exception(&self) -> Option<&Value> {
+        self.exception.as_ref()
+    }
+
+    pub fn is_exception(&self) -> bool {
+        self.exception.is_some()
+    }
+
+    pub fn suspend(&mut self, variable: Pattern, goal: SuspendedGoal) {
+        self.suspended.push_back(Suspension { variable, goal });
+    }
+
+    fn is_woken(&self, suspension: &Suspension) -> bool {
+        !matches!(
+            self.apply(&suspension.variable).unwrap().kind(),
+            PatternKind::Variable(..)
+        )
+    }
+
+    /// Whether any suspended goals are waiting on a variable which has since been bound.
+    pub fn has_woken(&self) -> bool {
+        self.suspended
+            .iter()
+            .any(|suspension| self.is_woken(suspension))
+    }
+
+    /// Removes the suspended goals which are waiting on a variable which has since been bound,
+    /// in the order they were suspended, so they can be resumed.
+    pub fn take_woken(&mut self) -> Vec<Suspension> {
+        let (woken, suspended): (Vector<_>, Vector<_>) = self
+            .suspended
+            .iter()
+            .cloned()
+            .partition(|suspension| self.is_woken(suspension));
+        self.suspended = suspended;
+        woken.into_iter().collect()
+    }
+
+    /// Sets how the search for solutions from this binding is performed.
+    pub fn search(&mut self, fair: bool, limit: Option<DepthLimit>) {
+        self.fair = fair;
+        self.limit = limit;
+    }
+
+    /// Continues the search in the same way as it was from another binding, after a search from
+    /// it found this one depth-first. The calls made in between do not count towards the depth.
+    pub fn resume_search(mut self, from: &Binding) -> Self {
+        self.fair = from.fair;
+        self.limit = from.limit.clone();
+        self.depth = from.depth;
+        self
+    }
+
+    /// Whether the search from this binding is anything other than depth-first.
+    pub fn is_depth_first(&self) -> bool {
+        !self.fair && self.limit.is_none()
+    }
+
+    /// Sets a time after which the search from this binding is abandoned.
+    pub fn set_deadline(&mut self, deadline: Deadline) {
+        self.deadline = Some(deadline);
+    }
+
+    pub fn deadline(&self) -> Option<&Deadline> {
+        self.deadline.as_ref()
+    }
+
+    /// The flag which a tracer sets to abandon the search from this binding.
+    pub fn abandoned(&self) -> &Rc<Cell<bool>> {
+        &self.abandoned
+    }
+
+    /// Whether the search from this binding has been abandoned, either by a tracer or as its
+    /// deadline has passed.
+    pub fn is_abandoned(&self) -> bool {
+        self.abandoned.get() || self.deadline.as_ref().is_some_and(Deadline::check)
+    }
+
+    /// The named variables of the question from which the search started, along with their
+    /// values so far, in order of their names.
+    pub fn question_variables(&self) -> Vec<(String, Option<Value>)> {
+        let mut variables: Vec<_> = self
+            .variables
+            .keys()
+            .filter(|variable| variable.generation() == Some(0) && !variable.is_wildcard())
+            .map(|variable| {
+                let value = self.extract(&self.get(variable).unwrap()).unwrap();
+                (variable.name().to_owned(), value)
+            })
+            .collect();
+        variables.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+        variables
+    }
+
+    pub fn is_fair(&self) -> bool {
+        self.fair
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Starts recording the derivation of this binding.
+    pub fn explain(&mut self) {
+        self.derivation = Some(Vector::new());
+    }
+
+    pub fn is_explained(&self) -> bool {
+        self.derivation.is_some()
+    }
+
+    pub fn infer(&mut self, inference: Inference) {
+        if let Some(derivation) = &mut self.derivation {
+            derivation.push_back(inference);
+        }
+    }
+
+    pub fn derivation(&self) -> Option<&Vector<Inference>> {
+        self.derivation.as_ref()
+    }
+
+    /// Starts recording the calls which fail while searching from this binding.
+    pub fn diagnose(&mut self, diagnosis: Rc<RefCell<Diagnosis>>) {
+        self.diagnosis = Some(diagnosis);
+    }
+
+    pub fn diagnosis(&self) -> Option<&Rc<RefCell<Diagnosis>>> {
+        self.diagnosis.as_ref()
+    }
+
+    /// Starts reporting the events of the search from this binding to a tracer.
+    pub fn trace(&mut self, tracer: Rc<dyn Tracer>) {
+        self.tracer = Some(tracer);
+    }
+
+    pub fn tracer(&self) -> Option<&Rc<dyn Tracer>> {
+        self.tracer.as_ref()
+    }
+
+    /// The number of nested predicate calls which are currently being unified.
+    pub fn call_depth(&self) -> usize {
+        self.generations.len() - 1
+    }
+
     pub fn generation(&self) -> usize {
         *self.generations.last().unwrap()
     }
@@ -66,10 +356,17 @@ impl Binding {
         source: &[Pattern],
         destination: &[Pattern],
     ) -> Option<Cow<'b, Self>> {
+        if let Some(limit) = &self.limit {
+            if self.generations.len() > limit.depth {
+                limit.reached.set(true);
+                return None;
+            }
+        }
         let mut binding = self.clone();
         let generation = binding.next_generation;
         binding.generations.push(generation);
         binding.next_generation += 1;
+        binding.depth = binding.depth.max(binding.call_depth());
         binding.variables.extend(
             destination
                 .iter()
@@ -91,6 +388,7 @@ impl Binding {
 
     pub fn end_generation(mut self) -> Self {
         self.generations.pop();
+        self.infer(Inference::Exit);
         self
     }
 
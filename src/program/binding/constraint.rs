@@ -0,0 +1,471 @@
+use super::{Binding, Domain};
+use crate::program::evaltree::*;
+use crate::program::unification::unify_patterns;
+use crate::{List, Value};
+use ramp::int::Int;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// A constraint on the variables of a binding, which is checked again each time the binding
+/// changes.
+#[derive(Clone, Debug)]
+pub(crate) enum Constraint {
+    /// The two patterns must never be unified.
+    Dif(Pattern, Pattern),
+    /// The sum of each integer multiplied by its coefficient is related to a constant.
+    Linear(Vec<(Int, Pattern)>, Relation, Int),
+    /// The integers must all be different from each other.
+    AllDifferent(Vec<Pattern>),
+}
+
+/// The relation between the sum of a linear constraint and its constant.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Relation {
+    Eq,
+    Neq,
+    Leq,
+}
+
+enum Status {
+    /// The constraint can no longer be violated, so it can be discarded.
+    Satisfied,
+    /// The constraint has been violated, so the binding is not a solution.
+    Violated,
+    /// The constraint may still be violated by some future binding.
+    Pending,
+}
+
+/// Integer division, rounding towards negative infinity.
+fn floor_div(lhs: &Int, rhs: &Int) -> Int {
+    let (quotient, remainder) = lhs.divmod(rhs);
+    if remainder.sign() != 0 && (remainder.sign() < 0) != (rhs.sign() < 0) {
+        quotient - Int::one()
+    } else {
+        quotient
+    }
+}
+
+/// Integer division, rounding towards positive infinity.
+fn ceil_div(lhs: &Int, rhs: &Int) -> Int {
+    let (quotient, remainder) = lhs.divmod(rhs);
+    if remainder.sign() != 0 && (remainder.sign() < 0) == (rhs.sign() < 0) {
+        quotient + Int::one()
+    } else {
+        quotient
+    }
+}
+
+/// The least and greatest values of a term of a linear constraint, where `None` is unbounded.
+fn term_bounds(coefficient: &Int, domain: &Domain) -> (Option<Int>, Option<Int>) {
+    let min = domain.min().map(|min| coefficient.clone() * min);
+    let max = domain.max().map(|max| coefficient.clone() * max);
+    if coefficient.sign() < 0 {
+        (max, min)
+    } else {
+        (min, max)
+    }
+}
+
+/// The sum of some bounds, which is unbounded if any of them are.
+fn sum<'a>(mut bounds: impl Iterator<Item = &'a Option<Int>>) -> Option<Int> {
+    bounds.try_fold(Int::zero(), |sum, bound| Some(sum + bound.as_ref()?))
+}
+
+impl Binding {
+    /// Adds a constraint to this binding. Returns `false` if the constraint is violated, in which
+    /// case the binding is no longer a solution.
+    pub fn constrain(&mut self, constraint: Constraint) -> bool {
+        self.constraints.push_back(constraint);
+        self.check_constraints()
+    }
+
+    /// Restricts an integer to the values of a domain. Returns `false` if it has no values left,
+    /// or is not an integer at all.
+    pub fn restrict(&mut self, pattern: &Pattern, domain: &Domain) -> bool {
+        self.narrow(pattern, domain) && self.check_constraints()
+    }
+
+    /// The values which an integer may take, or `None` if it is not an integer.
+    pub fn domain(&self, pattern: &Pattern) -> Option<Domain> {
+        match self.apply(pattern).unwrap().kind() {
+            PatternKind::Variable(variable) => Some(
+                self.domains
+                    .get(variable)
+                    .cloned()
+                    .unwrap_or_else(Domain::full),
+            ),
+            PatternKind::Literal(Literal::Integer(int)) => Some(Domain::singleton(int.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn has_constraints(&self) -> bool {
+        !self.constraints.is_empty() || !self.domains.is_empty()
+    }
+
+    /// Checks the constraints of this binding again, propagating their effects on the domains
+    /// of its variables until nothing changes, and discarding those which are now satisfied.
+    /// Returns `false` if any constraint has been violated.
+    pub fn check_constraints(&mut self) -> bool {
+        loop {
+            let mut changed = match self.check_domains() {
+                Some(changed) => changed,
+                None => return false,
+            };
+            let mut satisfied = vec![];
+            for (i, constraint) in self.constraints.clone().iter().enumerate() {
+                match self.propagate(constraint, &mut changed) {
+                    Status::Violated => return false,
+                    Status::Satisfied => satisfied.push(i),
+                    Status::Pending => {}
+                }
+            }
+            for i in satisfied.into_iter().rev() {
+                self.constraints.remove(i);
+            }
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Moves each domain to the variable its variable now refers to, and checks the values of
+    /// those which have been bound. A variable whose domain has only one value left is bound to
+    /// that value. Returns whether any variables were bound, or `None` if any domain is violated.
+    fn check_domains(&mut self) -> Option<bool> {
+        for (variable, domain) in std::mem::take(&mut self.domains) {
+            let pattern = self
+                .apply(&Pattern::from(PatternKind::Variable(variable)))
+                .unwrap();
+            match pattern.kind() {
+                PatternKind::Variable(root) => {
+                    let domain = match self.domains.get(root) {
+                        Some(existing) => existing.intersect(&domain),
+                        None => domain,
+                    };
+                    if domain.is_empty() {
+                        return None;
+                    }
+                    self.domains.insert(root.clone(), domain);
+                }
+                PatternKind::Literal(Literal::Integer(int)) if domain.contains(int) => {}
+                _ => return None,
+            }
+        }
+        let singletons: Vec<_> = self
+            .domains
+            .iter()
+            .filter_map(|(variable, domain)| Some((variable.clone(), domain.value()?.clone())))
+            .collect();
+        let changed = !singletons.is_empty();
+        for (variable, value) in singletons {
+            self.domains.remove(&variable);
+            self.set(
+                variable,
+                Pattern::from(PatternKind::Literal(Literal::Integer(value))),
+            );
+        }
+        Some(changed)
+    }
+
+    /// Restricts the domain of an integer, without propagating the change.
+    fn narrow(&mut self, pattern: &Pattern, domain: &Domain) -> bool {
+        match self.apply(pattern).unwrap().kind() {
+            PatternKind::Variable(variable) => {
+                let domain = self
+                    .domains
+                    .get(variable)
+                    .map(|existing| existing.intersect(domain))
+                    .unwrap_or_else(|| domain.clone());
+                if domain.is_empty() {
+                    return false;
+                }
+                self.domains.insert(variable.clone(), domain);
+                true
+            }
+            PatternKind::Literal(Literal::Integer(int)) => domain.contains(int),
+            _ => false,
+        }
+    }
+
+    fn propagate(&mut self, constraint: &Constraint, changed: &mut bool) -> Status {
+        match constraint {
+            Constraint::Dif(lhs, rhs) => self.propagate_dif(lhs, rhs),
+            Constraint::Linear(terms, relation, constant) => {
+                self.propagate_linear(terms, *relation, constant, changed)
+            }
+            Constraint::AllDifferent(terms) => self.propagate_all_different(terms, changed),
+        }
+    }
+
+    fn propagate_dif(&self, lhs: &Pattern, rhs: &Pattern) -> Status {
+        let unified = match unify_patterns(lhs.clone(), rhs.clone(), Cow::Borrowed(self)) {
+            Some(unified) => unified,
+            None => return Status::Satisfied,
+        };
+        // If the patterns could only be unified by binding some of their variables, they are not
+        // yet known to be equal.
+        let variables: HashSet<Variable> = self
+            .apply(lhs)
+            .unwrap()
+            .get_variables()
+            .into_iter()
+            .chain(self.apply(rhs).unwrap().get_variables())
+            .collect();
+        let mut roots = HashSet::new();
+        for variable in &variables {
+            match unified
+                .apply(&Pattern::from(PatternKind::Variable(variable.clone())))
+                .unwrap()
+                .kind()
+            {
+                PatternKind::Variable(root) if roots.insert(root.clone()) => {}
+                _ => return Status::Pending,
+            }
+        }
+        Status::Violated
+    }
+
+    fn propagate_linear(
+        &mut self,
+        terms: &[(Int, Pattern)],
+        relation: Relation,
+        constant: &Int,
+        changed: &mut bool,
+    ) -> Status {
+        // The terms which are already known are moved to the other side.
+        let mut constant = constant.clone();
+        let mut open = vec![];
+        for (coefficient, pattern) in terms {
+            let domain = match self.domain(pattern) {
+                Some(domain) => domain,
+                None => return Status::Violated,
+            };
+            match domain.value() {
+                Some(value) => constant -= coefficient.clone() * value,
+                None => open.push((coefficient.clone(), pattern.clone(), domain)),
+            }
+        }
+        match relation {
+            Relation::Leq => self.propagate_leq(&open, &constant, changed),
+            Relation::Eq => {
+                let lower = self.propagate_leq(&open, &constant, changed);
+                let negated: Vec<_> = open
+                    .into_iter()
+                    .map(|(coefficient, pattern, domain)| (-coefficient, pattern, domain))
+                    .collect();
+                match (lower, self.propagate_leq(&negated, &-constant, changed)) {
+                    (Status::Violated, _) | (_, Status::Violated) => Status::Violated,
+                    (Status::Satisfied, Status::Satisfied) => Status::Satisfied,
+                    _ => Status::Pending,
+                }
+            }
+            Relation::Neq => match open.as_slice() {
+                [] if constant.sign() == 0 => Status::Violated,
+                [] => Status::Satisfied,
+                [(coefficient, pattern, domain)] => {
+                    let (value, remainder) = constant.divmod(coefficient);
+                    if remainder.sign() == 0 && domain.contains(&value) {
+                        if !self.narrow(pattern, &domain.remove(&value)) {
+                            return Status::Violated;
+                        }
+                        *changed = true;
+                    }
+                    Status::Satisfied
+                }
+                _ => Status::Pending,
+            },
+        }
+    }
+
+    /// Propagates the bounds of a constraint that the sum of the terms is at most the constant.
+    fn propagate_leq(
+        &mut self,
+        terms: &[(Int, Pattern, Domain)],
+        constant: &Int,
+        changed: &mut bool,
+    ) -> Status {
+        let bounds: Vec<_> = terms
+            .iter()
+            .map(|(coefficient, _, domain)| term_bounds(coefficient, domain))
+            .collect();
+        if let Some(min) = sum(bounds.iter().map(|(min, _)| min)) {
+            if &min > constant {
+                return Status::Violated;
+            }
+        }
+        if let Some(max) = sum(bounds.iter().map(|(_, max)| max)) {
+            if &max <= constant {
+                return Status::Satisfied;
+            }
+        }
+        for (i, (coefficient, pattern, domain)) in terms.iter().enumerate() {
+            let others = sum(bounds
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, (min, _))| min));
+            let remaining = match others {
+                Some(others) => constant.clone() - others,
+                None => continue,
+            };
+            let narrowed = if coefficient.sign() > 0 {
+                domain.restrict(None, Some(floor_div(&remaining, coefficient)))
+            } else {
+                domain.restrict(Some(ceil_div(&remaining, coefficient)), None)
+            };
+            if &narrowed != domain {
+                if !self.narrow(pattern, &narrowed) {
+                    return Status::Violated;
+                }
+                *changed = true;
+            }
+        }
+        Status::Pending
+    }
+
+    fn propagate_all_different(&mut self, terms: &[Pattern], changed: &mut bool) -> Status {
+        let mut values = HashSet::new();
+        let mut open = vec![];
+        for pattern in terms {
+            let domain = match self.domain(pattern) {
+                Some(domain) => domain,
+                None => return Status::Violated,
+            };
+            match domain.value() {
+                Some(value) if !values.insert(value.clone()) => return Status::Violated,
+                Some(..) => {}
+                None => open.push((pattern, domain)),
+            }
+        }
+        if open.is_empty() {
+            return Status::Satisfied;
+        }
+        // If there are more variables than there are values between all of their bounds, they
+        // cannot all be different.
+        let min = open
+            .iter()
+            .map(|(_, domain)| domain.min())
+            .collect::<Option<Vec<_>>>();
+        let max = open
+            .iter()
+            .map(|(_, domain)| domain.max())
+            .collect::<Option<Vec<_>>>();
+        if let (Some(min), Some(max)) = (min, max) {
+            let span = *max.iter().max().unwrap() - *min.iter().min().unwrap() + Int::one();
+            if open.len() > span {
+                return Status::Violated;
+            }
+        }
+        for (pattern, domain) in open {
+            let narrowed = values
+                .iter()
+                .fold(domain.clone(), |domain, value| domain.remove(value));
+            if narrowed != domain {
+                if !self.narrow(pattern, &narrowed) {
+                    return Status::Violated;
+                }
+                *changed = true;
+            }
+        }
+        Status::Pending
+    }
+
+    /// The constraints which have yet to be satisfied, as values in which each unbound variable
+    /// that has a name is replaced by an atom of that name. This includes the domains of any
+    /// integer variables which are constrained.
+    pub fn residuals(&self, names: &HashMap<Variable, String>) -> Vec<Value> {
+        let named = |pattern: &Pattern| -> Option<Value> {
+            self.extract(&self.name_variables(pattern, names)).unwrap()
+        };
+        let integers = |ints: &mut dyn Iterator<Item = Int>| -> Value {
+            Value::from(List::new(ints.map(Value::Integer).map(Some).collect()))
+        };
+        let domains = self
+            .domains
+            .iter()
+            .filter(|(_, domain)| !domain.is_full())
+            .map(|(variable, domain)| {
+                let variable = named(&Pattern::from(PatternKind::Variable(variable.clone())));
+                let bound = |bound: Option<&Int>, unbounded: &str| match bound {
+                    Some(bound) => Value::Integer(bound.clone()),
+                    None => Value::atom(unbounded),
+                };
+                let intervals: Vec<_> = domain
+                    .intervals()
+                    .map(|(min, max)| (bound(min, "inf"), bound(max, "sup")))
+                    .collect();
+                let contents = match intervals.as_slice() {
+                    [(min, max)] => vec![variable, Some(min.clone()), Some(max.clone())],
+                    _ => {
+                        let intervals = intervals
+                            .into_iter()
+                            .map(|(min, max)| {
+                                Some(Value::from(List::new(vec![Some(min), Some(max)])))
+                            })
+                            .collect();
+                        vec![variable, Some(Value::from(List::new(intervals)))]
+                    }
+                };
+                crate::Struct::new("in", Some(Value::from(List::new(contents))))
+            });
+        let constraints = self.constraints.iter().map(|constraint| match constraint {
+            Constraint::Dif(lhs, rhs) => {
+                let contents = List::new(vec![named(lhs), named(rhs)]);
+                crate::Struct::new("dif", Some(Value::from(contents)))
+            }
+            Constraint::Linear(terms, relation, constant) => {
+                let relation = match relation {
+                    Relation::Eq => "eq",
+                    Relation::Neq => "neq",
+                    Relation::Leq => "leq",
+                };
+                let contents = List::new(vec![
+                    Some(integers(
+                        &mut terms.iter().map(|(coefficient, _)| coefficient.clone()),
+                    )),
+                    Some(Value::from(List::new(
+                        terms.iter().map(|(_, pattern)| named(pattern)).collect(),
+                    ))),
+                    Some(Value::atom(relation)),
+                    Some(Value::Integer(constant.clone())),
+                ]);
+                crate::Struct::new("scalar_product", Some(Value::from(contents)))
+            }
+            Constraint::AllDifferent(terms) => {
+                let contents = List::new(terms.iter().map(&named).collect());
+                crate::Struct::new("all_different", Some(Value::from(contents)))
+            }
+        });
+        domains.chain(constraints).map(Value::from).collect()
+    }
+
+    fn name_variables(&self, pattern: &Pattern, names: &HashMap<Variable, String>) -> Pattern {
+        let pattern = self.apply(pattern).unwrap();
+        match pattern.kind() {
+            PatternKind::Variable(variable) => match names.get(variable) {
+                Some(name) => Pattern::from(PatternKind::Struct(Atom::from(name.as_str()), None)),
+                None => pattern.clone(),
+            },
+            PatternKind::List(items, rest) => Pattern::list(
+                items
+                    .iter()
+                    .map(|item| self.name_variables(item, names))
+                    .collect(),
+                rest.as_ref().map(|rest| self.name_variables(rest, names)),
+            ),
+            PatternKind::Record(fields, rest) => Pattern::record(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), self.name_variables(field, names)))
+                    .collect(),
+                rest.as_ref().map(|rest| self.name_variables(rest, names)),
+            ),
+            PatternKind::Struct(name, Some(contents)) => Pattern::from(PatternKind::Struct(
+                name.clone(),
+                Some(self.name_variables(contents, names)),
+            )),
+            _ => pattern.clone(),
+        }
+    }
+}
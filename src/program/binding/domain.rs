@@ -0,0 +1,224 @@
+use ramp::int::Int;
+use std::cmp::Ordering;
+
+/// A bound of an interval, where `None` is unbounded.
+type Bound = Option<Int>;
+
+/// Compares two lower bounds, where an unbounded lower bound is the least.
+fn cmp_lower(lhs: &Bound, rhs: &Bound) -> Ordering {
+    match (lhs, rhs) {
+        (None, None) => Ordering::Equal,
+        (None, _) => Ordering::Less,
+        (_, None) => Ordering::Greater,
+        (Some(lhs), Some(rhs)) => lhs.cmp(rhs),
+    }
+}
+
+/// Compares two upper bounds, where an unbounded upper bound is the greatest.
+fn cmp_upper(lhs: &Bound, rhs: &Bound) -> Ordering {
+    match (lhs, rhs) {
+        (None, None) => Ordering::Equal,
+        (None, _) => Ordering::Greater,
+        (_, None) => Ordering::Less,
+        (Some(lhs), Some(rhs)) => lhs.cmp(rhs),
+    }
+}
+
+/// Whether an interval with these bounds contains any values.
+fn is_nonempty(min: &Bound, max: &Bound) -> bool {
+    match (min, max) {
+        (Some(min), Some(max)) => min <= max,
+        _ => true,
+    }
+}
+
+/// The set of integers that a constrained variable may take, as an ordered list of disjoint
+/// intervals.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Domain(Vec<(Bound, Bound)>);
+
+impl Domain {
+    /// The domain of all integers.
+    pub fn full() -> Self {
+        Self(vec![(None, None)])
+    }
+
+    pub fn range(min: Bound, max: Bound) -> Self {
+        if is_nonempty(&min, &max) {
+            Self(vec![(min, max)])
+        } else {
+            Self(vec![])
+        }
+    }
+
+    pub fn singleton(value: Int) -> Self {
+        Self::range(Some(value.clone()), Some(value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.0 == [(None, None)]
+    }
+
+    /// The least value of the domain, or `None` if it is unbounded (or empty).
+    pub fn min(&self) -> Option<&Int> {
+        self.0.first()?.0.as_ref()
+    }
+
+    /// The greatest value of the domain, or `None` if it is unbounded (or empty).
+    pub fn max(&self) -> Option<&Int> {
+        self.0.last()?.1.as_ref()
+    }
+
+    /// The bounds of each interval in the domain.
+    pub fn intervals(&self) -> impl Iterator<Item = (Option<&Int>, Option<&Int>)> {
+        self.0.iter().map(|(min, max)| (min.as_ref(), max.as_ref()))
+    }
+
+    /// The number of values in the domain, or `None` if it is unbounded.
+    pub fn size(&self) -> Option<Int> {
+        self.0.iter().try_fold(Int::zero(), |size, (min, max)| {
+            Some(size + (max.as_ref()? - min.as_ref()?) + Int::one())
+        })
+    }
+
+    /// The only value of the domain, if it contains exactly one.
+    pub fn value(&self) -> Option<&Int> {
+        match self.0.as_slice() {
+            [(Some(min), Some(max))] if min == max => Some(min),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, value: &Int) -> bool {
+        let value = Some(value.clone());
+        self.0.iter().any(|(min, max)| {
+            cmp_lower(min, &value) != Ordering::Greater && cmp_upper(max, &value) != Ordering::Less
+        })
+    }
+
+    /// The values which are in both this domain and the other.
+    pub fn intersect(&self, other: &Domain) -> Domain {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.0.len() && j < other.0.len() {
+            let (lmin, lmax) = &self.0[i];
+            let (rmin, rmax) = &other.0[j];
+            let min = std::cmp::max_by(lmin, rmin, |a, b| cmp_lower(a, b));
+            let max = std::cmp::min_by(lmax, rmax, |a, b| cmp_upper(a, b));
+            if is_nonempty(min, max) {
+                intervals.push((min.clone(), max.clone()));
+            }
+            // Whichever interval ends first cannot overlap with anything else.
+            if cmp_upper(lmax, rmax) == Ordering::Less {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Domain(intervals)
+    }
+
+    /// The values of this domain which are between the (inclusive) bounds.
+    pub fn restrict(&self, min: Bound, max: Bound) -> Domain {
+        self.intersect(&Domain::range(min, max))
+    }
+
+    /// This domain without the given value.
+    pub fn remove(&self, value: &Int) -> Domain {
+        let before = Some(value.clone() - Int::one());
+        let after = Some(value.clone() + Int::one());
+        let intervals = self
+            .0
+            .iter()
+            .flat_map(|(min, max)| {
+                let below = std::cmp::min_by(max, &before, |a, b| cmp_upper(a, b));
+                let above = std::cmp::max_by(min, &after, |a, b| cmp_lower(a, b));
+                vec![(min.clone(), below.clone()), (above.clone(), max.clone())]
+            })
+            .filter(|(min, max)| is_nonempty(min, max))
+            .collect();
+        Domain(intervals)
+    }
+
+    /// The values of this domain in ascending or descending order. Returns `None` if the domain
+    /// is unbounded in the direction which the values would start from.
+    pub fn values(&self, ascending: bool) -> Option<Box<dyn Iterator<Item = Int>>> {
+        if ascending {
+            self.min()?;
+            let intervals = self.0.clone().into_iter().flat_map(|(min, max)| {
+                let mut next = min;
+                std::iter::from_fn(move || {
+                    let value = next.take()?;
+                    if max.as_ref().map(|max| &value > max).unwrap_or(false) {
+                        return None;
+                    }
+                    next = Some(value.clone() + Int::one());
+                    Some(value)
+                })
+            });
+            Some(Box::new(intervals))
+        } else {
+            self.max()?;
+            let intervals = self.0.clone().into_iter().rev().flat_map(|(min, max)| {
+                let mut next = max;
+                std::iter::from_fn(move || {
+                    let value = next.take()?;
+                    if min.as_ref().map(|min| &value < min).unwrap_or(false) {
+                        return None;
+                    }
+                    next = Some(value.clone() - Int::one());
+                    Some(value)
+                })
+            });
+            Some(Box::new(intervals))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(value: i64) -> Option<Int> {
+        Some(Int::from(value))
+    }
+
+    #[test]
+    fn domain_intersect() {
+        let lhs = Domain::range(int(1), int(10));
+        let rhs = Domain::range(int(5), None);
+        assert_eq!(lhs.intersect(&rhs), Domain::range(int(5), int(10)));
+        assert_eq!(Domain::full().intersect(&rhs), rhs);
+        assert!(lhs.intersect(&Domain::range(int(11), int(12))).is_empty());
+    }
+
+    #[test]
+    fn domain_remove() {
+        let domain = Domain::range(int(1), int(3)).remove(&Int::from(2));
+        assert_eq!(domain.size(), int(2));
+        assert!(!domain.contains(&Int::from(2)));
+        assert!(domain.contains(&Int::from(3)));
+        assert_eq!(domain.remove(&Int::from(1)).value(), Some(&Int::from(3)));
+        assert!(Domain::full().remove(&Int::zero()).size().is_none());
+    }
+
+    #[test]
+    fn domain_values() {
+        let domain = Domain::range(int(1), int(5)).remove(&Int::from(3));
+        let up: Vec<_> = domain.values(true).unwrap().collect();
+        assert_eq!(
+            up,
+            vec![Int::from(1), Int::from(2), Int::from(4), Int::from(5)]
+        );
+        let down: Vec<_> = domain.values(false).unwrap().collect();
+        assert_eq!(
+            down,
+            vec![Int::from(5), Int::from(4), Int::from(2), Int::from(1)]
+        );
+        assert!(Domain::range(int(1), None).values(false).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 use super::evaltree::*;
 use super::*;
 use crate::ast::ModuleHeader;
+use crate::Tracer;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 mod definition;
 mod entry;
@@ -15,6 +17,16 @@ pub(crate) struct Database<'p> {
     /// were when the program was created, due to mutable definitions.
     pub(super) definitions: HashMap<Handle, DatabaseEntry<'p>>,
     pub(super) operators: HashMap<Scope, HashMap<OpKey, Operator>>,
+    /// The modules from which each module imports by glob, used to resolve goals which are
+    /// not known until runtime.
+    pub(super) globs: HashMap<Scope, Vec<Scope>>,
+    /// Whether referencing a predicate that cannot be found throws an exception, rather than
+    /// simply failing.
+    pub(super) strict: bool,
+    /// Whether native functions are checked against their declared modes before being called.
+    pub(super) check_modes: bool,
+    /// The tracer to which the events of the search for answers to questions are reported.
+    pub(super) tracer: Option<Rc<dyn Tracer>>,
 }
 
 impl<'p> Database<'p> {
@@ -53,6 +65,10 @@ impl<'p> Database<'p> {
         Self {
             definitions,
             operators: operators.into_iter().collect(),
+            globs: HashMap::default(),
+            strict: false,
+            check_modes: false,
+            tracer: None,
         }
     }
 
@@ -61,6 +77,10 @@ impl<'p> Database<'p> {
         header: &ModuleHeader,
         natives: &HashMap<Handle, NativeFunction<'p>>,
     ) {
+        self.globs.insert(
+            header.scope.clone(),
+            header.globbed_modules().cloned().collect(),
+        );
         for (output, input) in &header.aliases {
             self.definitions.insert(
                 output.clone(),
@@ -99,28 +119,76 @@ impl<'p> Database<'p> {
         }
     }
 
-    pub fn lookup(&self, handle: &Handle, public: bool) -> Option<&DatabaseDefinition<'p>> {
-        let entry = self.definitions.get(handle)?;
+    pub fn resolve(&self, handle: &Handle, public: bool) -> Option<&Handle> {
+        let (handle, entry) = self.definitions.get_key_value(handle)?;
         if public && !entry.public {
             return None;
         }
         match &entry.definition {
-            DatabaseDefinition::Alias(handle) => self.lookup(handle, false),
-            definition => Some(definition),
+            DatabaseDefinition::Alias(handle) => self.resolve(handle, false),
+            _ => Some(handle),
         }
     }
 
-    pub fn resolve<'a>(&'a self, handle: &'a Handle, public: bool) -> Option<&'a Handle> {
-        let entry = self.definitions.get(handle)?;
-        if public && !entry.public {
-            return None;
+    /// Resolves a handle that is referenced from within a module at runtime, in the same way as
+    /// it would have been resolved had it been written in the source of that module.
+    pub fn resolve_in_module(
+        &self,
+        handle: &Handle,
+        module: &Scope,
+        public: bool,
+    ) -> Option<&Handle> {
+        if let Some(resolved) = self.resolve(handle, public) {
+            return Some(resolved);
         }
-        match &entry.definition {
-            DatabaseDefinition::Alias(handle) => self.resolve(handle, false),
-            _ => Some(handle),
+        let mut candidates = self
+            .globs
+            .get(module)
+            .into_iter()
+            .flatten()
+            .filter_map(|glob| self.resolve(&handle.relocate(glob), true));
+        let candidate = candidates.next()?;
+        if candidates.all(|other| other == candidate) {
+            Some(candidate)
+        } else {
+            None
         }
     }
 
+    /// Finds the handles most similar to one that could not be found, to help correct typos.
+    /// Only handles in the same module, which could have been referenced instead, are
+    /// considered.
+    pub fn similar_handles(&self, handle: &Handle, public: bool) -> Vec<&Handle> {
+        let module = handle.module();
+        let target = handle.to_string();
+        let max_distance = std::cmp::max(2, target.len() / 3);
+        let mut candidates: Vec<_> = self
+            .definitions
+            .iter()
+            .filter(|(candidate, entry)| candidate.module() == module && (!public || entry.public))
+            .map(|(candidate, _)| (edit_distance(&target, &candidate.to_string()), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        candidates.sort_by_key(|(distance, candidate)| (*distance, candidate.to_string()));
+        candidates
+            .into_iter()
+            .take(3)
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn set_check_modes(&mut self, check_modes: bool) {
+        self.check_modes = check_modes;
+    }
+
+    pub fn set_tracer(&mut self, tracer: Option<Rc<dyn Tracer>>) {
+        self.tracer = tracer;
+    }
+
     pub fn resolve_operator<'a>(&'a self, key: &OpKey) -> Option<&'a Operator> {
         self.operators
             .get(&Default::default())
@@ -157,12 +225,38 @@ impl<'p> Database<'p> {
                 (scope, operators)
             })
             .collect();
+        self.globs = self
+            .globs
+            .into_iter()
+            .map(|(mut scope, mut globs)| {
+                scope.add_lib(lib.clone());
+                globs.iter_mut().for_each(|glob| glob.add_lib(lib.clone()));
+                (scope, globs)
+            })
+            .collect();
         self
     }
 
     pub fn merge(mut self, library: Self) -> Self {
         self.definitions.extend(library.definitions);
         self.operators.extend(library.operators);
+        self.globs.extend(library.globs);
         self
     }
 }
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut row: Vec<usize> = (0..=rhs.len()).collect();
+    for (i, lchar) in lhs.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, rchar) in rhs.iter().enumerate() {
+            let substitution = diagonal + if lchar == *rchar { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[rhs.len()]
+}
@@ -5,15 +5,17 @@ use std::iter::FromIterator;
 /// The definition of a rule. A predicate may be defined multiple times with disjoint
 /// heads and distinct bodies.
 #[derive(Default, Clone, Debug)]
-pub(crate) struct Definition(Vec<(Head, RuleKind, Option<Body>)>);
+pub(crate) struct Definition(Vec<(Head, RuleKind, Option<Body>, Location)>);
 
 impl Definition {
-    pub fn iter(&self) -> impl Iterator<Item = &(Head, RuleKind, Option<Body>)> {
+    pub fn iter(&self) -> impl Iterator<Item = &(Head, RuleKind, Option<Body>, Location)> {
         self.0.iter()
     }
 
     pub fn bodies_mut(&mut self) -> impl Iterator<Item = &mut Body> {
-        self.0.iter_mut().filter_map(|(_, _, body)| body.as_mut())
+        self.0
+            .iter_mut()
+            .filter_map(|(_, _, body, _)| body.as_mut())
     }
 
     pub fn merge(&mut self, mut other: Definition) {
@@ -25,7 +27,9 @@ impl From<ast::Definition> for Definition {
     fn from(ast: ast::Definition) -> Self {
         Self(
             ast.into_iter()
-                .map(|(head, kind, body)| (head.into(), kind, body.map(Into::into)))
+                .map(|(head, kind, body, location)| {
+                    (head.into(), kind, body.map(Into::into), location)
+                })
                 .collect(),
         )
     }
@@ -34,6 +34,7 @@ pub(crate) use variables::Variables;
 pub(crate) use crate::ast::Atom;
 pub(crate) use crate::ast::Handle;
 pub(crate) use crate::ast::Literal;
+pub(crate) use crate::ast::Location;
 pub(crate) use crate::ast::RuleKind;
 pub(crate) use crate::ast::Scope;
 pub(crate) use crate::ast::{OpArity, OpKey, Operator};
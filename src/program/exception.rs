@@ -0,0 +1,50 @@
+//! Constructors for the exceptions which are thrown by the solver and the `@core` library.
+
+use crate::ast::Handle;
+use crate::{List, Struct, Value};
+use std::fmt::Display;
+
+/// Thrown when arguments are required to be bound, but are not:
+/// `instantiation_error [handle, positions]`, where the positions of the arguments start at 1.
+pub(crate) fn instantiation_error(handle: impl Display, positions: &[usize]) -> Value {
+    let positions = positions
+        .iter()
+        .map(|position| Some(Value::from(*position)))
+        .collect();
+    let contents = List::new(vec![
+        Some(Value::from(handle.to_string())),
+        Some(Value::from(List::new(positions))),
+    ]);
+    Value::from(Struct::new(
+        "instantiation_error",
+        Some(Value::from(contents)),
+    ))
+}
+
+/// Thrown when an argument is of the wrong type: `type_error [expected, culprit]`.
+pub(crate) fn type_error(expected: &str, culprit: Option<Value>) -> Value {
+    let contents = List::new(vec![Some(Value::atom(expected)), culprit]);
+    Value::from(Struct::new("type_error", Some(Value::from(contents))))
+}
+
+/// Thrown in strict mode when a predicate cannot be found, listing the most similar predicates
+/// which do exist: `existence_error [procedure, handle, suggestions]`.
+pub(crate) fn existence_error(handle: &Handle, suggestions: &[&Handle]) -> Value {
+    let suggestions = suggestions
+        .iter()
+        .map(|suggestion| Some(Value::from(suggestion.to_string())))
+        .collect();
+    let contents = List::new(vec![
+        Some(Value::atom("procedure")),
+        Some(Value::from(handle.to_string())),
+        Some(Value::from(List::new(suggestions))),
+    ]);
+    Value::from(Struct::new("existence_error", Some(Value::from(contents))))
+}
+
+/// Thrown when an argument is of the right type, but is not one of the accepted values:
+/// `domain_error [expected, culprit]`.
+pub(crate) fn domain_error(expected: &str, culprit: Option<Value>) -> Value {
+    let contents = List::new(vec![Some(Value::atom(expected)), culprit]);
+    Value::from(Struct::new("domain_error", Some(Value::from(contents))))
+}
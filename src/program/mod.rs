@@ -1,9 +1,13 @@
 mod binding;
 mod database;
 pub(crate) mod evaltree;
+pub(crate) mod exception;
 mod native_function;
 pub(crate) mod unification;
 
-pub(crate) use binding::Binding;
+pub(crate) use binding::{
+    Binding, Constraint, Deadline, DepthLimit, Domain, Inference, Relation, SuspendedGoal,
+};
 pub(crate) use database::{Database, DatabaseDefinition};
 pub use native_function::NativeFunction;
+pub(crate) use native_function::{Builtin, Mode};
@@ -2,34 +2,165 @@ use crate::Value;
 use std::fmt::{self, Debug, Formatter};
 use std::rc::Rc;
 
-type NativeFn<'p> =
-    Rc<dyn Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Vec<Option<Value>>>> + 'p>;
+type NativeFn<'p> = Rc<
+    dyn Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Result<Vec<Option<Value>>, Value>>> + 'p,
+>;
+
+/// Predicates which are implemented directly by the solver, as they require access to the
+/// program rather than just the values of their arguments.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Builtin {
+    /// `call/N`: calls a goal, with any additional arguments appended to its own.
+    Call,
+    /// `catch/3`: calls a goal, recovering from exceptions which unify with the catcher.
+    Catch,
+    /// `dif/2`: constrains two values to never be unified.
+    Dif,
+    /// `freeze/2`: calls a goal once a variable is bound, suspending it until then.
+    Freeze,
+    /// `@clpfd::in/3`: restricts an integer to a range of values.
+    In,
+    /// `@clpfd::scalar_product/4`: constrains a linear combination of integers.
+    ScalarProduct,
+    /// `@clpfd::all_different/1`: constrains integers to be different from each other.
+    AllDifferent,
+    /// `@clpfd::labeling/2`: enumerates the values of constrained integers.
+    Labeling,
+    /// `@clpfd::fd_inf/2`: the least value an integer may take.
+    FdInf,
+    /// `@clpfd::fd_sup/2`: the greatest value an integer may take.
+    FdSup,
+    /// `@clpfd::fd_size/2`: the number of values an integer may take.
+    FdSize,
+}
+
+/// A way in which a native function may be called, recording for each argument whether it is
+/// required to be bound.
+#[derive(Clone, Debug)]
+pub(crate) struct Mode(Vec<bool>);
+
+impl Mode {
+    /// Parses a mode from a string with one character per argument: `+` for an argument that
+    /// must be bound, or `-` or `?` for one which need not be.
+    pub(crate) fn new(mode: &str) -> Option<Self> {
+        mode.chars()
+            .map(|ch| match ch {
+                '+' => Some(true),
+                '-' | '?' => Some(false),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .map(Mode)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The (1-based) positions of the arguments which are required to be bound but are not.
+    fn unbound(&self, values: &[Option<Value>]) -> Vec<usize> {
+        self.0
+            .iter()
+            .zip(values)
+            .enumerate()
+            .filter(|(_, (required, value))| **required && value.is_none())
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+enum Implementation<'p> {
+    Function(NativeFn<'p>),
+    Builtin(Builtin),
+}
 
 #[derive(Clone)]
 pub struct NativeFunction<'p> {
-    function: NativeFn<'p>,
+    implementation: Implementation<'p>,
+    modes: Vec<Mode>,
+    suspend: bool,
 }
 
 impl<'p> NativeFunction<'p> {
     pub(crate) fn new<F>(function: F) -> Self
     where
-        F: Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Vec<Option<Value>>>> + 'p,
+        F: Fn(Vec<Option<Value>>) -> Box<dyn Iterator<Item = Result<Vec<Option<Value>>, Value>>>
+            + 'p,
     {
         Self {
-            function: Rc::new(function),
+            implementation: Implementation::Function(Rc::new(function)),
+            modes: vec![],
+            suspend: false,
+        }
+    }
+
+    pub(crate) fn builtin(builtin: Builtin) -> Self {
+        Self {
+            implementation: Implementation::Builtin(builtin),
+            modes: vec![],
+            suspend: false,
+        }
+    }
+
+    pub(crate) fn set_modes(&mut self, modes: Vec<Mode>) {
+        self.modes = modes;
+    }
+
+    pub(crate) fn set_suspend(&mut self, suspend: bool) {
+        self.suspend = suspend;
+    }
+
+    /// Whether a call to this function which does not satisfy any of its modes should be
+    /// suspended until its arguments are bound, rather than being run anyway.
+    pub(crate) fn suspends(&self) -> bool {
+        self.suspend
+    }
+
+    /// Checks that the arguments satisfy at least one of the declared modes of this function.
+    /// If none are satisfied, the positions of the arguments which must be bound to satisfy
+    /// the closest mode are returned. A function with no declared modes accepts anything.
+    pub(crate) fn check_modes(&self, values: &[Option<Value>]) -> Result<(), Vec<usize>> {
+        match self
+            .modes
+            .iter()
+            .map(|mode| mode.unbound(values))
+            .min_by_key(Vec::len)
+        {
+            Some(closest) if !closest.is_empty() => Err(closest),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn as_builtin(&self) -> Option<Builtin> {
+        match self.implementation {
+            Implementation::Builtin(builtin) => Some(builtin),
+            Implementation::Function(..) => None,
         }
     }
 
     pub(crate) fn call(
         &self,
         values: Vec<Option<Value>>,
-    ) -> Box<dyn Iterator<Item = Vec<Option<Value>>>> {
-        (self.function)(values)
+    ) -> Box<dyn Iterator<Item = Result<Vec<Option<Value>>, Value>>> {
+        match &self.implementation {
+            Implementation::Function(function) => function(values),
+            Implementation::Builtin(builtin) => {
+                unreachable!("Builtin {:?} cannot be called as a function", builtin)
+            }
+        }
     }
 }
 
 impl Debug for NativeFunction<'_> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "NativeFunction {{ function: {:p} }}", self.function)
+        match &self.implementation {
+            Implementation::Function(function) => {
+                write!(f, "NativeFunction {{ function: {:p} }}", function)
+            }
+            Implementation::Builtin(builtin) => {
+                write!(f, "NativeFunction {{ builtin: {:?} }}", builtin)
+            }
+        }
     }
 }
@@ -0,0 +1,138 @@
+//! Implementations of the builtin predicates, which are provided by the solver itself rather
+//! than by native functions.
+
+use super::super::exception::{instantiation_error, type_error};
+use super::super::{Builtin, Constraint, Database, SuspendedGoal};
+use super::evaltree::*;
+use super::{unify_patterns, until_exception, Binding, Bindings, Frame};
+use crate::ast::Arity;
+use std::borrow::Cow;
+
+impl Database<'_> {
+    pub(super) fn unify_builtin<'a>(
+        &'a self,
+        handle: &'a Handle,
+        builtin: Builtin,
+        args: Vec<Pattern>,
+        binding: Cow<'a, Binding>,
+        frame: Frame<'a>,
+    ) -> Bindings<'a> {
+        let mut args = match builtin {
+            Builtin::In
+            | Builtin::ScalarProduct
+            | Builtin::AllDifferent
+            | Builtin::Labeling
+            | Builtin::FdInf
+            | Builtin::FdSup
+            | Builtin::FdSize => return self.unify_clpfd(handle, builtin, args, binding),
+            _ => args.into_iter(),
+        };
+        let goal = args.next().unwrap();
+        match builtin {
+            Builtin::Call => self.unify_goal((handle, 1), &goal, args.collect(), binding, frame),
+            Builtin::Dif => {
+                let lhs = binding.apply(&goal).unwrap();
+                let rhs = binding.apply(&args.next().unwrap()).unwrap();
+                let mut binding = binding;
+                if binding.to_mut().constrain(Constraint::Dif(lhs, rhs)) {
+                    Box::new(std::iter::once(binding))
+                } else {
+                    Box::new(std::iter::empty())
+                }
+            }
+            Builtin::Freeze => {
+                let variable = binding.apply(&goal).unwrap();
+                let goal = args.next().unwrap();
+                if !matches!(variable.kind(), PatternKind::Variable(..)) {
+                    return self.unify_goal((handle, 2), &goal, vec![], binding, frame);
+                }
+                let goal = binding.apply(&goal).unwrap();
+                let mut binding = binding;
+                binding.to_mut().suspend(
+                    variable,
+                    SuspendedGoal::Call {
+                        builtin: handle.clone(),
+                        goal,
+                        predicate: frame.predicate.cloned(),
+                        public: frame.public,
+                    },
+                );
+                Box::new(std::iter::once(binding))
+            }
+            Builtin::Catch => {
+                let catcher = args.next().unwrap();
+                let recovery = args.next().unwrap();
+                let original = binding.clone();
+                let bindings =
+                    until_exception(self.unify_goal((handle, 1), &goal, vec![], binding, frame));
+                Box::new(bindings.flat_map(move |binding| -> Bindings<'a> {
+                    let exception = match binding.exception() {
+                        Some(exception) => exception.clone(),
+                        None => return Box::new(std::iter::once(binding)),
+                    };
+                    // Any bindings made by the goal are discarded before recovering.
+                    let mut recovering = original.clone();
+                    let exception = recovering.to_mut().associate_value(Some(exception));
+                    match unify_patterns(catcher.clone(), exception, recovering) {
+                        Some(recovering) => {
+                            self.unify_goal((handle, 3), &recovery, vec![], recovering, frame)
+                        }
+                        None => Box::new(std::iter::once(binding)),
+                    }
+                }))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Calls a goal which is provided as a value, resolving it from the module in which it was
+    /// called, with the extra arguments added after its own. An atom is called as a predicate
+    /// with no arguments of its own, and a struct with its contents (whatever they are) as its
+    /// only argument. A list is called as `call/N` would be, with its first element as the goal
+    /// and the rest as the extra arguments.
+    ///
+    /// The goal is identified as the argument at a position of a builtin predicate, for error
+    /// reporting.
+    pub(super) fn unify_goal<'a>(
+        &'a self,
+        (builtin, position): (&Handle, usize),
+        goal: &Pattern,
+        extra_args: Vec<Pattern>,
+        binding: Cow<'a, Binding>,
+        frame: Frame<'a>,
+    ) -> Bindings<'a> {
+        let goal = binding.apply(goal).unwrap();
+        let (name, mut args) = match goal.kind() {
+            PatternKind::Struct(name, None) => (name.clone(), vec![]),
+            PatternKind::Struct(name, Some(contents)) => (name.clone(), vec![contents.clone()]),
+            PatternKind::List(items, None) if !items.is_empty() => {
+                let mut items = items.iter().cloned();
+                let goal = items.next().unwrap();
+                let args = items.chain(extra_args).collect();
+                return self.unify_goal((builtin, position), &goal, args, binding, frame);
+            }
+            PatternKind::Variable(..) | PatternKind::List(_, Some(..)) => {
+                return Box::new(std::iter::once(Cow::Owned(
+                    binding.throw(instantiation_error(builtin, &[position])),
+                )));
+            }
+            _ => {
+                let culprit = binding.extract(&goal).unwrap();
+                return Box::new(std::iter::once(Cow::Owned(
+                    binding.throw(type_error("callable", culprit)),
+                )));
+            }
+        };
+        args.extend(extra_args);
+        let module = frame.module();
+        let arity = Arity {
+            len: args.len() as u32,
+            fields: vec![],
+        };
+        let handle = Handle::from_parts(module.join(name), arity);
+        match self.resolve_in_module(&handle, &module, frame.public) {
+            Some(handle) => self.unify_query(handle, args, binding, frame),
+            None => self.unknown_predicate(&handle, binding, frame),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Implementations of the builtin predicates of the `@clpfd` library, which constrain integers
+//! to finite domains.
+
+use super::super::exception::{domain_error, instantiation_error, type_error};
+use super::super::{Builtin, Constraint, Database, Domain, Relation};
+use super::evaltree::*;
+use super::{unify_patterns, Binding, Bindings};
+use crate::Value;
+use ramp::int::Int;
+use std::borrow::Cow;
+use std::rc::Rc;
+
+/// How the next variable to be labeled is chosen.
+#[derive(Copy, Clone)]
+enum Selection {
+    /// The first variable which is not yet bound.
+    Leftmost,
+    /// The variable with the fewest values left.
+    FirstFail,
+    /// The variable with the least lower bound.
+    Min,
+    /// The variable with the greatest upper bound.
+    Max,
+}
+
+/// Reads the arguments of a builtin, producing the exception to be thrown if any of them are
+/// not of the expected form.
+struct Arguments<'b> {
+    handle: &'b Handle,
+    binding: &'b Binding,
+}
+
+impl Arguments<'_> {
+    fn list(&self, position: usize, pattern: &Pattern) -> Result<Vec<Pattern>, Value> {
+        let pattern = self.binding.apply(pattern).unwrap();
+        match pattern.kind() {
+            PatternKind::List(items, None) => Ok(items.iter().cloned().collect()),
+            PatternKind::List(..) | PatternKind::Variable(..) => {
+                Err(instantiation_error(self.handle, &[position]))
+            }
+            _ => Err(type_error("list", self.binding.extract(&pattern).unwrap())),
+        }
+    }
+
+    fn integer(&self, position: usize, pattern: &Pattern) -> Result<Int, Value> {
+        let pattern = self.binding.apply(pattern).unwrap();
+        match pattern.kind() {
+            PatternKind::Literal(Literal::Integer(int)) => Ok(int.clone()),
+            PatternKind::Variable(..) => Err(instantiation_error(self.handle, &[position])),
+            _ => Err(type_error(
+                "integer",
+                self.binding.extract(&pattern).unwrap(),
+            )),
+        }
+    }
+
+    /// An integer, or an atom standing for an unbounded end of a range.
+    fn bound(
+        &self,
+        position: usize,
+        pattern: &Pattern,
+        unbounded: &str,
+    ) -> Result<Option<Int>, Value> {
+        match self.binding.apply(pattern).unwrap().kind() {
+            PatternKind::Struct(name, None) if name.as_ref() == unbounded => Ok(None),
+            _ => self.integer(position, pattern).map(Some),
+        }
+    }
+
+    fn atom(&self, position: usize, pattern: &Pattern) -> Result<Atom, Value> {
+        let pattern = self.binding.apply(pattern).unwrap();
+        match pattern.kind() {
+            PatternKind::Struct(name, None) => Ok(name.clone()),
+            PatternKind::Variable(..) => Err(instantiation_error(self.handle, &[position])),
+            _ => Err(type_error("atom", self.binding.extract(&pattern).unwrap())),
+        }
+    }
+
+    /// A pattern which is either an integer or a variable, which may be constrained.
+    fn term(&self, pattern: &Pattern) -> Result<Pattern, Value> {
+        match self.binding.domain(pattern) {
+            Some(..) => Ok(self.binding.apply(pattern).unwrap()),
+            None => Err(type_error(
+                "integer",
+                self.binding.extract(pattern).unwrap(),
+            )),
+        }
+    }
+
+    fn terms(&self, position: usize, pattern: &Pattern) -> Result<Vec<Pattern>, Value> {
+        self.list(position, pattern)?
+            .iter()
+            .map(|pattern| self.term(pattern))
+            .collect()
+    }
+}
+
+impl Database<'_> {
+    pub(super) fn unify_clpfd<'a>(
+        &'a self,
+        handle: &'a Handle,
+        builtin: Builtin,
+        args: Vec<Pattern>,
+        binding: Cow<'a, Binding>,
+    ) -> Bindings<'a> {
+        let arguments = Arguments {
+            handle,
+            binding: &binding,
+        };
+        let outcome = match builtin {
+            Builtin::Labeling => {
+                let labeling = arguments
+                    .list(1, &args[0])
+                    .and_then(|options| {
+                        options.iter().try_fold(
+                            (Selection::Leftmost, true),
+                            |(selection, ascending), option| {
+                                let option = arguments.atom(1, option)?;
+                                Ok(match option.as_ref() {
+                                    "leftmost" => (Selection::Leftmost, ascending),
+                                    "ff" => (Selection::FirstFail, ascending),
+                                    "min" => (Selection::Min, ascending),
+                                    "max" => (Selection::Max, ascending),
+                                    "up" => (selection, true),
+                                    "down" => (selection, false),
+                                    _ => {
+                                        return Err(domain_error(
+                                            "labeling_option",
+                                            Some(Value::atom(option.as_ref())),
+                                        ))
+                                    }
+                                })
+                            },
+                        )
+                    })
+                    .and_then(|strategy| Ok((strategy, arguments.terms(2, &args[1])?)));
+                return match labeling {
+                    Ok((strategy, variables)) => {
+                        self.label(handle, strategy, Rc::new(variables), binding)
+                    }
+                    Err(exception) => {
+                        Box::new(std::iter::once(Cow::Owned(binding.throw(exception))))
+                    }
+                };
+            }
+            Builtin::FdInf | Builtin::FdSup | Builtin::FdSize => {
+                let domain = match binding.domain(&args[0]) {
+                    Some(domain) => domain,
+                    None => {
+                        let culprit = binding.extract(&args[0]).unwrap();
+                        let exception = type_error("integer", culprit);
+                        return Box::new(std::iter::once(Cow::Owned(binding.throw(exception))));
+                    }
+                };
+                let value = match builtin {
+                    Builtin::FdInf => domain.min().cloned().map(Value::Integer),
+                    Builtin::FdSup => domain.max().cloned().map(Value::Integer),
+                    _ => domain.size().map(Value::Integer),
+                };
+                let value = value.unwrap_or_else(|| match builtin {
+                    Builtin::FdInf => Value::atom("inf"),
+                    _ => Value::atom("sup"),
+                });
+                let mut binding = binding;
+                let value = binding.to_mut().associate_value(Some(value));
+                return Box::new(
+                    unify_patterns(args[1].clone(), value, binding)
+                        .into_iter()
+                        .flat_map(move |binding| self.resume(binding)),
+                );
+            }
+            Builtin::In => arguments.term(&args[0]).and_then(|term| {
+                let min = arguments.bound(2, &args[1], "inf")?;
+                let max = arguments.bound(3, &args[2], "sup")?;
+                Ok(Err((term, Domain::range(min, max))))
+            }),
+            Builtin::AllDifferent => arguments
+                .terms(1, &args[0])
+                .map(|terms| Ok(Constraint::AllDifferent(terms))),
+            Builtin::ScalarProduct => scalar_product(&arguments, &args).map(Ok),
+            _ => unreachable!(),
+        };
+        let mut binding = binding;
+        let consistent = match outcome {
+            Ok(Ok(constraint)) => binding.to_mut().constrain(constraint),
+            Ok(Err((term, domain))) => binding.to_mut().restrict(&term, &domain),
+            Err(exception) => {
+                return Box::new(std::iter::once(Cow::Owned(binding.throw(exception))));
+            }
+        };
+        if consistent {
+            self.resume(binding)
+        } else {
+            Box::new(std::iter::empty())
+        }
+    }
+
+    /// Binds each of the variables to each of the values in its domain in turn, choosing the
+    /// variables and ordering the values by the labeling strategy.
+    fn label<'a>(
+        &'a self,
+        handle: &'a Handle,
+        strategy: (Selection, bool),
+        variables: Rc<Vec<Pattern>>,
+        binding: Cow<'a, Binding>,
+    ) -> Bindings<'a> {
+        let (selection, ascending) = strategy;
+        let candidates = variables.iter().filter_map(|variable| {
+            let domain = binding.domain(variable)?;
+            match domain.value() {
+                Some(..) => None,
+                None => Some((variable, domain)),
+            }
+        });
+        let chosen = match selection {
+            Selection::Leftmost => candidates.into_iter().next(),
+            Selection::FirstFail => {
+                candidates.min_by(|(_, lhs), (_, rhs)| match (lhs.size(), rhs.size()) {
+                    (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+                    (lhs, rhs) => rhs.is_none().cmp(&lhs.is_none()).reverse(),
+                })
+            }
+            Selection::Min => {
+                candidates.min_by(|(_, lhs), (_, rhs)| match (lhs.min(), rhs.min()) {
+                    (Some(lhs), Some(rhs)) => lhs.cmp(rhs),
+                    (lhs, rhs) => lhs.is_some().cmp(&rhs.is_some()),
+                })
+            }
+            Selection::Max => {
+                candidates.max_by(|(_, lhs), (_, rhs)| match (lhs.max(), rhs.max()) {
+                    (Some(lhs), Some(rhs)) => lhs.cmp(rhs),
+                    (lhs, rhs) => rhs.is_some().cmp(&lhs.is_some()),
+                })
+            }
+        };
+        let (variable, domain) = match chosen {
+            Some((variable, domain)) => (variable.clone(), domain),
+            None => return Box::new(std::iter::once(binding)),
+        };
+        let values = match domain.values(ascending) {
+            Some(values) => values,
+            None => {
+                let exception = instantiation_error(handle, &[2]);
+                return Box::new(std::iter::once(Cow::Owned(binding.throw(exception))));
+            }
+        };
+        Box::new(values.flat_map(move |value| {
+            let mut binding = binding.clone();
+            let value = binding
+                .to_mut()
+                .associate_value(Some(Value::Integer(value)));
+            let variables = variables.clone();
+            unify_patterns(variable.clone(), value, binding)
+                .into_iter()
+                .flat_map(move |binding| self.resume(binding))
+                .flat_map(move |binding| self.label(handle, strategy, variables.clone(), binding))
+        }))
+    }
+}
+
+/// Reads the arguments of `scalar_product(Coefficients, Integers, Relation, Value)` into a
+/// linear constraint.
+fn scalar_product(arguments: &Arguments, args: &[Pattern]) -> Result<Constraint, Value> {
+    let coefficients = arguments
+        .list(1, &args[0])?
+        .iter()
+        .map(|coefficient| arguments.integer(1, coefficient))
+        .collect::<Result<Vec<_>, _>>()?;
+    let terms = arguments.terms(2, &args[1])?;
+    if coefficients.len() != terms.len() {
+        let culprit = arguments.binding.extract(&args[1]).unwrap();
+        return Err(domain_error("same_length", culprit));
+    }
+    let mut terms: Vec<_> = coefficients.into_iter().zip(terms).collect();
+    let relation = arguments.atom(3, &args[2])?;
+    let mut constant = Int::zero();
+    match arguments.term(&args[3])?.kind() {
+        PatternKind::Literal(Literal::Integer(int)) => constant = int.clone(),
+        _ => terms.push((-Int::one(), arguments.binding.apply(&args[3]).unwrap())),
+    }
+    // All relations are rewritten in terms of equality, inequality, and less-than-or-equal.
+    let negate = |terms: Vec<(Int, Pattern)>| -> Vec<(Int, Pattern)> {
+        terms
+            .into_iter()
+            .map(|(coefficient, term)| (-coefficient, term))
+            .collect()
+    };
+    Ok(match relation.as_ref() {
+        "eq" => Constraint::Linear(terms, Relation::Eq, constant),
+        "neq" => Constraint::Linear(terms, Relation::Neq, constant),
+        "leq" => Constraint::Linear(terms, Relation::Leq, constant),
+        "lt" => Constraint::Linear(terms, Relation::Leq, constant - Int::one()),
+        "geq" => Constraint::Linear(negate(terms), Relation::Leq, -constant),
+        "gt" => Constraint::Linear(negate(terms), Relation::Leq, -constant - Int::one()),
+        _ => {
+            let culprit = Some(Value::atom(relation.as_ref()));
+            return Err(domain_error("clpfd_relation", culprit));
+        }
+    })
+}
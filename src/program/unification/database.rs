@@ -1,8 +1,14 @@
-use super::super::{Database, DatabaseDefinition};
+use super::super::exception::{existence_error, instantiation_error};
+use super::super::{Database, DatabaseDefinition, Deadline, DepthLimit, Inference, SuspendedGoal};
 use super::evaltree::*;
-use super::{unify_patterns, Binding, Bindings};
-use crate::Question;
+use super::{
+    flatten, on_failure, trace, unify_patterns, unify_patterns_new_generation, until_exception,
+    Binding, Bindings, Frame,
+};
+use crate::{ClauseMatch, Diagnosis, Event, Failure, Port, Question, Strategy, Tracer};
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 #[cfg(feature = "test-perf")]
 use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -49,11 +55,82 @@ impl Database<'_> {
     pub(crate) fn unify_question<'a>(
         &'a self,
         question: &'a Question,
+        strategy: Strategy,
+    ) -> Box<dyn Iterator<Item = Binding> + 'a> {
+        let mut binding = question.initial_binding.clone();
+        match strategy {
+            Strategy::DepthFirst => Box::new(self.search(question, binding)),
+            Strategy::Interleaving => {
+                binding.search(true, None);
+                Box::new(self.search(question, binding))
+            }
+            Strategy::IterativeDeepening(max) => self.deepen(question, binding, max),
+        }
+    }
+
+    /// Answers a question depth-first, recording the clauses used to reach each answer.
+    pub(crate) fn explain_question<'a>(
+        &'a self,
+        question: &'a Question,
+    ) -> impl Iterator<Item = Binding> + 'a {
+        let mut binding = question.initial_binding.clone();
+        binding.explain();
+        self.search(question, binding)
+    }
+
+    /// Searches for answers with an increasing bound on the depth of the search, producing
+    /// only those answers found at exactly that depth, so that no answer is found twice. The
+    /// search ends once the bound is no longer reached, as there are no deeper answers.
+    fn deepen<'a>(
+        &'a self,
+        question: &'a Question,
+        binding: Binding,
+        max: usize,
+    ) -> Box<dyn Iterator<Item = Binding> + 'a> {
+        let reached = Rc::new(Cell::new(true));
+        let answers = (0..=max)
+            .take_while({
+                let reached = reached.clone();
+                move |_| reached.replace(false)
+            })
+            .flat_map(move |depth| {
+                let mut binding = binding.clone();
+                binding.search(false, Some(DepthLimit::new(depth, reached.clone())));
+                self.search(question, binding)
+                    .filter(move |binding| binding.depth() == depth)
+            });
+        Box::new(until_exception(answers))
+    }
+
+    /// Answers a question depth-first, reporting the events of the search to a tracer.
+    pub(crate) fn trace_question<'a>(
+        &'a self,
+        question: &'a Question,
+        tracer: Rc<dyn Tracer>,
+    ) -> impl Iterator<Item = Binding> + 'a {
+        let mut binding = question.initial_binding.clone();
+        binding.trace(tracer);
+        self.search(question, binding)
+    }
+
+    fn search<'a>(
+        &'a self,
+        question: &'a Question,
+        mut binding: Binding,
     ) -> impl Iterator<Item = Binding> + 'a {
+        if let (None, Some(tracer)) = (binding.tracer(), &self.tracer) {
+            binding.trace(tracer.clone());
+        }
         let body = question.as_ref();
-        let answers = self
-            .unify_body(body, Cow::Borrowed(&question.initial_binding), true)
-            .map(|cow| cow.into_owned()); // TODO: do we even need to owned it here?
+        let answers: Bindings = match self.validate_question(question) {
+            Some(exception) => Box::new(std::iter::once(Cow::Owned(binding.throw(exception)))),
+            None => Box::new(until_exception(self.unify_body(
+                body,
+                Cow::Owned(binding),
+                Frame::question(),
+            ))),
+        };
+        let answers = answers.map(|cow| cow.into_owned()); // TODO: do we even need to owned it here?
         #[cfg(feature = "test-perf")]
         {
             FlameIterator(
@@ -69,27 +146,105 @@ impl Database<'_> {
         }
     }
 
-    /// Runs a test. A test does not need to reference public predicates only.
+    /// Searches for an answer to a question, recording the calls which fail along the way. If
+    /// there is no answer, the deepest of those failures are returned, unless the search was
+    /// abandoned at the deadline before it could finish.
+    pub(crate) fn diagnose(
+        &self,
+        question: &Question,
+        test: bool,
+        deadline: Option<Deadline>,
+    ) -> Option<Diagnosis> {
+        let frame = if test {
+            Frame::test()
+        } else {
+            if self.validate_question(question).is_some() {
+                return None;
+            }
+            Frame::question()
+        };
+        let diagnosis = Rc::new(RefCell::new(Diagnosis::default()));
+        let mut binding = question.initial_binding.clone();
+        binding.diagnose(diagnosis.clone());
+        if let Some(deadline) = &deadline {
+            binding.set_deadline(deadline.clone());
+        }
+        if self
+            .unify_body(question.as_ref(), Cow::Owned(binding), frame)
+            .next()
+            .is_some()
+        {
+            return None;
+        }
+        // A search which was abandoned part way has not recorded every failure.
+        if deadline.is_some_and(|deadline| deadline.is_reached()) {
+            return None;
+        }
+        let diagnosis = diagnosis.borrow().clone();
+        Some(diagnosis)
+    }
+
+    /// In strict mode, checks that every predicate referenced by a question is exported before
+    /// it is run, producing the exception to be thrown if not.
+    fn validate_question(&self, question: &Question) -> Option<crate::Value> {
+        if !self.strict {
+            return None;
+        }
+        let mut body = question.as_ref().clone();
+        let unknown = body
+            .handles_mut()
+            .find(|handle| self.resolve(handle, true).is_none())?;
+        Some(existence_error(
+            unknown,
+            &self.similar_handles(unknown, true),
+        ))
+    }
+
+    /// Handles a reference to a predicate which cannot be found. In strict mode this is an
+    /// exception, and otherwise it is simply a failure.
+    pub(super) fn unknown_predicate<'a>(
+        &'a self,
+        handle: &Handle,
+        binding: Cow<'a, Binding>,
+        frame: Frame<'a>,
+    ) -> Bindings<'a> {
+        if !self.strict {
+            return Box::new(std::iter::empty());
+        }
+        let exception = existence_error(handle, &self.similar_handles(handle, frame.public));
+        Box::new(std::iter::once(Cow::Owned(binding.throw(exception))))
+    }
+
+    /// Runs a test, abandoning it once the deadline has passed if one is given. A test does not
+    /// need to reference public predicates only.
     #[cfg_attr(feature = "test-perf", flamer::flame)]
     pub(crate) fn unify_test<'a>(
         &'a self,
         question: &'a Question,
+        deadline: Option<Deadline>,
     ) -> impl Iterator<Item = Binding> + 'a {
         let body = question.as_ref();
-        let answers = self
-            .unify_body(body, Cow::Borrowed(&question.initial_binding), false)
+        let binding = match deadline {
+            Some(deadline) => {
+                let mut binding = question.initial_binding.clone();
+                binding.set_deadline(deadline);
+                Cow::Owned(binding)
+            }
+            None => Cow::Borrowed(&question.initial_binding),
+        };
+        let answers = until_exception(self.unify_body(body, binding, Frame::test()))
             .map(|cow| cow.into_owned());
         answers
     }
 
     #[cfg_attr(feature = "test-perf", flamer::flame)]
-    fn unify_body<'a>(
+    pub(super) fn unify_body<'a>(
         &'a self,
         body: &'a Body,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
-        self.unify_disjunction(&body.0, binding, public)
+        self.unify_disjunction(&body.0, binding, frame)
     }
 
     #[cfg_attr(feature = "test-perf", flamer::flame)]
@@ -97,17 +252,20 @@ impl Database<'_> {
         &'a self,
         disjunction: &'a Disjunction,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
-        Box::new(
+        flatten(
+            binding.is_fair(),
             disjunction
                 .cases
                 .iter()
-                .map(move |(head, tail)| {
-                    let head_bindings = self.unify_conjunction(head, binding.clone(), public);
-                    match tail {
-                        None => (Box::new(head_bindings), None),
-                        Some(tail) => (Box::new(head_bindings), Some(tail)),
+                .map(move |(head, tail)| match tail {
+                    None => (self.unify_conjunction(head, binding.clone(), frame), None),
+                    Some(tail) => {
+                        let head_bindings = self.unify_depth_first(binding.clone(), |binding| {
+                            self.unify_conjunction(head, binding, frame)
+                        });
+                        (head_bindings, Some(tail))
                     }
                 })
                 .scan(false, |skip_rest, (mut head, tail)| {
@@ -130,12 +288,14 @@ impl Database<'_> {
                     }
                 })
                 .fuse()
-                .flat_map(move |(head_bindings, tail)| -> Bindings<'a> {
+                .map(move |(head_bindings, tail)| -> Bindings<'a> {
                     match tail {
                         None => Box::new(head_bindings),
-                        Some(tail) => Box::new(head_bindings.flat_map(move |binding| {
-                            self.unify_conjunction(tail, binding, public)
-                        })),
+                        Some(tail) => {
+                            Box::new(head_bindings.flat_map(move |binding| {
+                                self.unify_conjunction(tail, binding, frame)
+                            }))
+                        }
                     }
                 }),
         )
@@ -146,11 +306,15 @@ impl Database<'_> {
         &'a self,
         conjunction: &'a Conjunction,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
+        let fair = binding.is_fair();
         let bindings = Box::new(std::iter::once(binding));
         conjunction.terms.iter().fold(bindings, |bindings, term| {
-            Box::new(bindings.flat_map(move |binding| self.unify_procession(term, binding, public)))
+            flatten(
+                fair,
+                bindings.map(move |binding| self.unify_procession(term, binding, frame)),
+            )
         })
     }
 
@@ -159,30 +323,60 @@ impl Database<'_> {
         &'a self,
         procession: &'a Procession,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
         let bindings = Box::new(std::iter::once(binding));
+        let last = procession.steps.len() - 1;
         procession
             .steps
             .iter()
-            .fold(bindings, |mut bindings, step| match bindings.next() {
-                Some(binding) => self.perform_step(step, binding, public),
-                None => Box::new(std::iter::empty()),
+            .enumerate()
+            .fold(bindings, |mut bindings, (index, step)| {
+                match bindings.next() {
+                    Some(binding) if index == last => self.perform_step(step, binding, frame),
+                    Some(binding) => self.unify_depth_first(binding, |binding| {
+                        self.perform_step(step, binding, frame)
+                    }),
+                    None => Box::new(std::iter::empty()),
+                }
             })
     }
 
+    /// Finds the bindings of a goal depth-first and without a bound on the depth, whatever the
+    /// strategy of the search. Where only the first binding is kept (by `->>` and `->`), or all
+    /// are gathered (by aggregation), they must be the same as when searching depth-first.
+    fn unify_depth_first<'a>(
+        &'a self,
+        binding: Cow<'a, Binding>,
+        unify: impl FnOnce(Cow<'a, Binding>) -> Bindings<'a>,
+    ) -> Bindings<'a> {
+        if binding.is_depth_first() {
+            return unify(binding);
+        }
+        let from = binding.into_owned();
+        let mut binding = from.clone();
+        binding.search(false, None);
+        Box::new(
+            unify(Cow::Owned(binding))
+                .map(move |binding| Cow::Owned(binding.into_owned().resume_search(&from))),
+        )
+    }
+
     #[cfg_attr(feature = "test-perf", flamer::flame)]
     fn perform_step<'a>(
         &'a self,
         unification: &'a Step,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
+        if binding.is_exception() {
+            return Box::new(std::iter::once(binding));
+        }
         match unification {
             Step::Query(query) => Box::new(
-                self.evaluate_expressions(query.args(), binding, public)
+                self.evaluate_expressions(query.args(), binding, frame)
                     .flat_map(move |(arguments, binding)| {
-                        self.unify_query(query.handle(), arguments, binding, public)
+                        self.unify_query(query.handle(), arguments, binding, frame)
                     }),
             ),
             Step::Relation(None, op, rhs) => {
@@ -190,7 +384,7 @@ impl Database<'_> {
                     Some(operator) => {
                         let handle = operator.handle();
                         Box::new(
-                            self.evaluate_term(rhs, binding, public)
+                            self.evaluate_term(rhs, binding, frame)
                                 .into_iter()
                                 .flat_map(move |(pattern, bindings)| {
                                     bindings.flat_map(move |binding| {
@@ -198,7 +392,7 @@ impl Database<'_> {
                                             handle,
                                             vec![pattern.clone()],
                                             binding,
-                                            false,
+                                            frame.private(),
                                         )
                                     })
                                 }),
@@ -212,12 +406,12 @@ impl Database<'_> {
                     Some(operator) => {
                         let handle = operator.handle();
                         Box::new(
-                            self.evaluate_term(lhs, binding, public)
+                            self.evaluate_term(lhs, binding, frame)
                                 .into_iter()
                                 .flat_map(move |(lvar, bindings)| {
                                     bindings.into_iter().flat_map(move |binding| {
                                         let lvar = lvar.clone();
-                                        self.evaluate_term(rhs, binding, public)
+                                        self.evaluate_term(rhs, binding, frame)
                                             .into_iter()
                                             .flat_map(move |(rvar, bindings)| {
                                                 bindings.flat_map({
@@ -227,7 +421,7 @@ impl Database<'_> {
                                                             handle,
                                                             vec![lvar.clone(), rvar.clone()],
                                                             binding,
-                                                            false,
+                                                            frame.private(),
                                                         )
                                                     }
                                                 })
@@ -239,21 +433,32 @@ impl Database<'_> {
                     None => Box::new(std::iter::empty()),
                 }
             }
-            Step::Body(body) => self.unify_body(body, binding, public),
+            Step::Body(body) => self.unify_body(body, binding, frame),
             Step::Unification(lhs, rhs) => Box::new(
-                self.evaluate_expression(lhs, binding, public)
+                self.evaluate_expression(lhs, binding, frame)
                     .into_iter()
                     .flat_map(move |(lvar, bindings)| {
                         bindings.flat_map(move |binding| {
-                            self.evaluate_expression(rhs, binding, public)
+                            self.evaluate_expression(rhs, binding, frame)
                                 .into_iter()
                                 .flat_map({
                                     let lvar = lvar.clone();
                                     move |(rvar, bindings)| {
                                         bindings.flat_map({
                                             let lvar = lvar.clone();
-                                            move |binding| {
-                                                unify_patterns(lvar.clone(), rvar.clone(), binding)
+                                            move |binding| -> Bindings<'a> {
+                                                if binding.is_exception() {
+                                                    return Box::new(std::iter::once(binding));
+                                                }
+                                                Box::new(
+                                                    unify_patterns(
+                                                        lvar.clone(),
+                                                        rvar.clone(),
+                                                        binding,
+                                                    )
+                                                    .into_iter()
+                                                    .flat_map(move |binding| self.resume(binding)),
+                                                )
                                             }
                                         })
                                     }
@@ -269,13 +474,13 @@ impl Database<'_> {
         &'a self,
         expressions: &'a [Expression],
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> impl Iterator<Item = MultipleEvaluations<'a>> {
         expressions.iter().fold(
             Box::new(std::iter::once((vec![], binding))) as Box<dyn Iterator<Item = _>>,
             move |bindings: Box<dyn Iterator<Item = MultipleEvaluations<'a>>>, expression| {
                 Box::new(bindings.flat_map(move |(outputs, binding)| {
-                    self.evaluate_expression(expression, binding, public)
+                    self.evaluate_expression(expression, binding, frame)
                         .into_iter()
                         .flat_map(move |(var, bindings)| {
                             let mut outputs = outputs.clone();
@@ -288,36 +493,182 @@ impl Database<'_> {
     }
 
     #[cfg_attr(feature = "test-perf", flamer::flame)]
-    fn unify_query<'a>(
+    pub(super) fn unify_query<'a>(
         &'a self,
         handle: &Handle,
         args: Vec<Pattern>,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Bindings<'a> {
         assert_eq!(handle.arity.len() as usize, args.len());
-        let definition = match self.lookup(handle, public) {
-            Some(definition) => definition,
-            None => return Box::new(std::iter::empty()),
+        if binding.is_exception() {
+            return Box::new(std::iter::once(binding));
+        }
+        if binding.is_abandoned() {
+            return Box::new(std::iter::empty());
+        }
+        let handle = match self.resolve(handle, frame.public) {
+            Some(handle) => handle,
+            None => return self.unknown_predicate(handle, binding, frame),
+        };
+        let diagnosis = match (binding.diagnosis(), &self.definitions[handle].definition) {
+            (Some(..), DatabaseDefinition::Native(native)) if native.as_builtin().is_some() => None,
+            (diagnosis, _) => diagnosis.cloned(),
+        };
+        let traced = binding
+            .tracer()
+            .map(|tracer| (tracer.clone(), args.clone(), binding.clone()));
+        let bindings = match diagnosis {
+            Some(diagnosis) => {
+                let depth = binding.call_depth() + 1;
+                let bindings = self.unify_predicate(handle, args.clone(), binding.clone(), frame);
+                on_failure(bindings, move || {
+                    let failure = self.failure(handle, &args, &binding);
+                    diagnosis.borrow_mut().record(depth, failure);
+                })
+            }
+            None => self.unify_predicate(handle, args, binding, frame),
+        };
+        match traced {
+            Some((tracer, args, binding)) => {
+                // The exits and redos of a definition are reported by the clause that found
+                // the solution instead, so they can include its index.
+                let native = !matches!(
+                    self.definitions[handle].definition,
+                    DatabaseDefinition::Static(..)
+                );
+                let depth = binding.call_depth() + 1;
+                trace(bindings, move |port, output| {
+                    if native || matches!(port, Port::Call | Port::Fail) {
+                        let binding = output.unwrap_or(&binding);
+                        tracer.trace(&event(port, handle, &args, binding, None, depth));
+                    }
+                })
+            }
+            None => bindings,
+        }
+    }
+
+    /// Describes a call which has failed, along with the clauses which came closest to
+    /// unifying with it.
+    fn failure(&self, handle: &Handle, args: &[Pattern], binding: &Binding) -> Failure {
+        let arguments = args
+            .iter()
+            .map(|arg| binding.extract(arg).unwrap())
+            .collect();
+        let closest = match &self.definitions[handle].definition {
+            DatabaseDefinition::Static(definition) => {
+                let matches: Vec<_> = definition
+                    .iter()
+                    .enumerate()
+                    .map(|(clause, (head, _, _, location))| {
+                        // Each argument is unified in turn, skipping those which do not unify,
+                        // so that variables shared between arguments are accounted for.
+                        let binding = binding.start_generation(None, &[], &head.patterns).unwrap();
+                        let (matched, _) =
+                            args.iter().zip(&head.patterns).fold(
+                                (0, binding),
+                                |(matched, binding), (arg, pattern)| {
+                                    match unify_patterns_new_generation(
+                                        arg.clone(),
+                                        pattern.clone(),
+                                        binding.clone(),
+                                    ) {
+                                        Some(binding) => (matched + 1, binding),
+                                        None => (matched, binding),
+                                    }
+                                },
+                            );
+                        ClauseMatch::new(
+                            clause,
+                            location.line,
+                            location.column,
+                            matched,
+                            args.len(),
+                        )
+                    })
+                    .collect();
+                let best = matches.iter().map(ClauseMatch::matched).max();
+                matches
+                    .into_iter()
+                    .filter(|clause| Some(clause.matched()) == best)
+                    .collect()
+            }
+            _ => vec![],
         };
-        match definition {
+        Failure::new(
+            handle.scope.to_string(),
+            handle.to_string(),
+            arguments,
+            closest,
+        )
+    }
+
+    fn unify_predicate<'a>(
+        &'a self,
+        handle: &'a Handle,
+        args: Vec<Pattern>,
+        binding: Cow<'a, Binding>,
+        frame: Frame<'a>,
+    ) -> Bindings<'a> {
+        match &self.definitions[handle].definition {
             DatabaseDefinition::Static(definition) => {
-                self.unify_definition(definition, args, binding)
+                self.unify_definition(handle, definition, args, binding)
             }
             DatabaseDefinition::Mutable(_definition) => {
                 todo!("Not sure yet how mutable definitions can be handled soundly")
             }
             DatabaseDefinition::Native(native_function) => {
-                let values = args.iter().map(|p| binding.extract(p).unwrap()).collect();
-                Box::new(native_function.call(values).filter_map(move |values| {
-                    args.iter().cloned().zip(values.into_iter()).try_fold(
-                        binding.clone(),
-                        |mut binding, (lhs, rhs)| {
-                            let rhs = binding.to_mut().associate_value(rhs);
-                            unify_patterns(lhs, rhs, binding)
-                        },
-                    )
-                }))
+                if let Some(builtin) = native_function.as_builtin() {
+                    return self.unify_builtin(handle, builtin, args, binding, frame);
+                }
+                let values: Vec<_> = args.iter().map(|p| binding.extract(p).unwrap()).collect();
+                if self.check_modes || native_function.suspends() {
+                    if let Err(positions) = native_function.check_modes(&values) {
+                        if native_function.suspends() {
+                            let variable = binding.apply(&args[positions[0] - 1]).unwrap();
+                            let args = args.iter().map(|arg| binding.apply(arg).unwrap()).collect();
+                            let mut binding = binding;
+                            binding.to_mut().suspend(
+                                variable,
+                                SuspendedGoal::Native {
+                                    handle: handle.clone(),
+                                    args,
+                                },
+                            );
+                            return Box::new(std::iter::once(binding));
+                        }
+                        let exception = instantiation_error(handle, &positions);
+                        return Box::new(std::iter::once(Cow::Owned(binding.throw(exception))));
+                    }
+                }
+                // A native function may produce any number of answers, so whether the search has
+                // been abandoned is checked between each.
+                let deadline = binding.deadline().cloned();
+                let abandoned = binding.abandoned().clone();
+                Box::new(
+                    native_function
+                        .call(values)
+                        .take_while(move |_| {
+                            !abandoned.get() && !deadline.as_ref().is_some_and(Deadline::check)
+                        })
+                        .filter_map(move |values| {
+                            let values = match values {
+                                Ok(values) => values,
+                                Err(exception) => {
+                                    return Some(Cow::Owned(binding.throw(exception)))
+                                }
+                            };
+                            args.iter().cloned().zip(values.into_iter()).try_fold(
+                                binding.clone(),
+                                |mut binding, (lhs, rhs)| {
+                                    let rhs = binding.to_mut().associate_value(rhs);
+                                    unify_patterns(lhs, rhs, binding)
+                                },
+                            )
+                        })
+                        .flat_map(move |binding| self.resume(binding)),
+                )
             }
             _ => unreachable!(),
         }
@@ -326,45 +677,79 @@ impl Database<'_> {
     #[cfg_attr(feature = "test-perf", flamer::flame)]
     fn unify_definition<'a>(
         &'a self,
+        handle: &'a Handle,
         definition: &'a Definition,
         expressions: Vec<Pattern>,
         input_binding: Cow<'a, Binding>,
     ) -> Bindings<'a> {
-        Box::new(
+        let traced = input_binding.tracer().map(|tracer| {
+            let binding = input_binding.clone().into_owned();
+            (tracer.clone(), Rc::new(expressions.clone()), binding)
+        });
+        flatten(
+            input_binding.is_fair(),
             definition
                 .iter()
+                .enumerate()
                 .map({
                     let input_binding = input_binding.clone();
-                    move |(head, kind, body)| {
-                        let output_binding = input_binding.start_generation(
+                    move |(clause, (head, kind, body, location))| {
+                        let mut output_binding = input_binding.start_generation(
                             body.as_ref(),
                             &expressions,
                             &head.patterns.to_vec(),
                         );
-                        (output_binding, *kind, body)
+                        if let Some(binding) = &mut output_binding {
+                            if binding.is_explained() {
+                                let args = expressions
+                                    .iter()
+                                    .map(|pattern| input_binding.apply(pattern).unwrap())
+                                    .collect();
+                                binding.to_mut().infer(Inference::Enter {
+                                    handle: handle.clone(),
+                                    clause,
+                                    location: *location,
+                                    args,
+                                });
+                            }
+                        }
+                        (clause, output_binding, *kind, body)
                     }
                 })
-                .scan(false, |skip_rest, (binding, kind, body)| {
+                .scan(false, |skip_rest, (clause, binding, kind, body)| {
                     if *skip_rest {
                         return None;
                     }
                     if binding.is_some() && kind == RuleKind::Once {
                         *skip_rest = true;
                     }
-                    Some((binding, body))
+                    Some((clause, binding, body))
                 })
                 .fuse()
-                .flat_map(move |(binding, body)| {
-                    Box::new(
+                .map(move |(clause, binding, body)| -> Bindings<'a> {
+                    let bindings = Box::new(
                         binding
-                            .map(move |binding| match body {
-                                Some(body) => self.unify_body(body, binding, false),
+                            .into_iter()
+                            .flat_map(move |binding| self.resume(binding))
+                            .flat_map(move |binding| match body {
+                                Some(body) => {
+                                    self.unify_body(body, binding, Frame::predicate(handle))
+                                }
                                 None => Box::new(std::iter::once(binding)),
                             })
-                            .into_iter()
-                            .flatten()
                             .map(|binding| Cow::Owned(binding.into_owned().end_generation())),
-                    )
+                    );
+                    let (tracer, args, binding) = match &traced {
+                        Some(traced) => traced.clone(),
+                        None => return bindings,
+                    };
+                    let depth = binding.call_depth() + 1;
+                    trace(bindings, move |port, output| {
+                        if matches!(port, Port::Exit | Port::Redo) {
+                            let binding = output.unwrap_or(&binding);
+                            tracer.trace(&event(port, handle, &args, binding, Some(clause), depth));
+                        }
+                    })
                 }),
         )
     }
@@ -374,12 +759,12 @@ impl Database<'_> {
         &'a self,
         expression: &'a Expression,
         binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Option<Evaluation<'a>> {
         let eval = expression
             .climb_operators::<Box<dyn Fn(Cow<'a, Binding>) -> Option<Evaluation<'a>>>, _, _, _, _, _>(
                 |operator| self.resolve_operator(operator),
-                move |term| Box::new(move |binding| self.evaluate_term(term, binding, public)),
+                move |term| Box::new(move |binding| self.evaluate_term(term, binding, frame)),
                 move |term, operator| {
                     Box::new(move |mut binding| {
                         let dest = Pattern::from(PatternKind::Variable(binding.to_mut().fresh_variable()));
@@ -390,7 +775,7 @@ impl Database<'_> {
                                     operator.handle(),
                                     vec![out.clone(), dest.clone()],
                                     binding,
-                                    public,
+                                    frame,
                                 )
                             }}));
                         Some((
@@ -426,7 +811,7 @@ impl Database<'_> {
                                                             dest.clone(),
                                                         ],
                                                         binding,
-                                                        public,
+                                                        frame,
                                                     )
                                                 }
                                             })
@@ -446,13 +831,13 @@ impl Database<'_> {
         &'a self,
         term: &'a Term,
         mut binding: Cow<'a, Binding>,
-        public: bool,
+        frame: Frame<'a>,
     ) -> Option<Evaluation<'a>> {
         match term {
-            Term::Expression(expression) => self.evaluate_expression(expression, binding, public),
+            Term::Expression(expression) => self.evaluate_expression(expression, binding, frame),
             Term::PrefixOp(op, rhs) => {
                 let dest = Pattern::from(PatternKind::Variable(binding.to_mut().fresh_variable()));
-                let (rvar, bindings) = self.evaluate_term(rhs, binding, public)?;
+                let (rvar, bindings) = self.evaluate_term(rhs, binding, frame)?;
                 let bindings = Box::new(bindings.flat_map({
                     let dest = dest.clone();
                     move |binding| {
@@ -460,7 +845,7 @@ impl Database<'_> {
                             op.handle(),
                             vec![rvar.clone(), dest.clone()],
                             binding,
-                            public,
+                            frame,
                         )
                     }
                 }));
@@ -468,11 +853,11 @@ impl Database<'_> {
             }
             Term::InfixOp(lhs, op, rhs) => {
                 let dest = Pattern::from(PatternKind::Variable(binding.to_mut().fresh_variable()));
-                let (lvar, bindings) = self.evaluate_term(lhs, binding, public)?;
+                let (lvar, bindings) = self.evaluate_term(lhs, binding, frame)?;
                 let bindings = Box::new(bindings.flat_map({
                     let dest = dest.clone();
                     move |binding| {
-                        self.evaluate_term(rhs, binding, public)
+                        self.evaluate_term(rhs, binding, frame)
                             .into_iter()
                             .flat_map({
                                 let dest = dest.clone();
@@ -486,7 +871,7 @@ impl Database<'_> {
                                                 op.handle(),
                                                 vec![lvar.clone(), rvar.clone(), dest.clone()],
                                                 binding,
-                                                public,
+                                                frame,
                                             )
                                         }
                                     })
@@ -498,16 +883,47 @@ impl Database<'_> {
             }
             Term::Value(pattern) => Some((pattern.clone(), Box::new(std::iter::once(binding)))),
             Term::ListAggregation(pattern, body) => {
-                let solutions = self
-                    .unify_body(body, binding.clone(), public)
-                    .map(move |binding| binding.extract(&pattern).unwrap())
-                    .map(|value| binding.to_mut().associate_value(value))
-                    .collect();
+                let mut solutions = vec![];
+                let found = self.unify_depth_first(binding.clone(), |binding| {
+                    self.unify_body(body, binding, frame)
+                });
+                for solution in found {
+                    if solution.is_exception() {
+                        return Some((pattern.clone(), Box::new(std::iter::once(solution))));
+                    }
+                    let value = solution.extract(&pattern).unwrap();
+                    solutions.push(binding.to_mut().associate_value(value));
+                }
                 Some((
-                    Pattern::list(solutions, None),
+                    Pattern::list(solutions.into(), None),
                     Box::new(std::iter::once(binding)),
                 ))
             }
         }
     }
 }
+
+/// Describes a call to a predicate passing through a port, for a tracer.
+fn event(
+    port: Port,
+    handle: &Handle,
+    args: &[Pattern],
+    binding: &Binding,
+    clause: Option<usize>,
+    depth: usize,
+) -> Event {
+    let arguments = args
+        .iter()
+        .map(|arg| binding.extract(arg).unwrap())
+        .collect();
+    Event {
+        port,
+        name: handle.scope.to_string(),
+        handle: handle.to_string(),
+        arguments,
+        bindings: binding.question_variables(),
+        clause,
+        depth,
+        abandoned: binding.abandoned().clone(),
+    }
+}
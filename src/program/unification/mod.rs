@@ -1,9 +1,221 @@
 use super::{evaltree, Binding};
-use std::borrow::Cow;
+use crate::ast::{Handle, Scope};
+use crate::Port;
+use std::borrow::{Borrow, Cow};
+use std::collections::VecDeque;
 
+mod builtin;
+mod clpfd;
 mod database;
 mod patterns;
+mod suspension;
 
 type Bindings<'a> = Box<dyn Iterator<Item = Cow<'a, Binding>> + 'a>;
 
 pub(crate) use patterns::{unify_patterns, unify_patterns_new_generation};
+
+/// The context in which a body is being unified.
+#[derive(Copy, Clone, Debug)]
+struct Frame<'a> {
+    /// Whether only public predicates may be referenced, as is the case for questions.
+    public: bool,
+    /// The predicate whose body is being unified, if it is not a question or test.
+    predicate: Option<&'a Handle>,
+}
+
+impl<'a> Frame<'a> {
+    fn question() -> Self {
+        Self {
+            public: true,
+            predicate: None,
+        }
+    }
+
+    fn test() -> Self {
+        Self {
+            public: false,
+            predicate: None,
+        }
+    }
+
+    fn predicate(handle: &'a Handle) -> Self {
+        Self {
+            public: false,
+            predicate: Some(handle),
+        }
+    }
+
+    fn resumed(public: bool, predicate: Option<&'a Handle>) -> Self {
+        Self { public, predicate }
+    }
+
+    fn private(self) -> Self {
+        Self {
+            public: false,
+            ..self
+        }
+    }
+
+    /// The module in which the body being unified was written.
+    fn module(&self) -> Scope {
+        self.predicate.map(Handle::module).unwrap_or_default()
+    }
+}
+
+/// Ends a sequence of bindings after the first exception, as no further bindings should be
+/// produced once an exception has been thrown.
+fn until_exception<I, B>(bindings: I) -> impl Iterator<Item = B>
+where
+    I: Iterator<Item = B>,
+    B: Borrow<Binding>,
+{
+    bindings.scan(false, |thrown, binding| {
+        if *thrown {
+            return None;
+        }
+        *thrown = binding.borrow().is_exception();
+        Some(binding)
+    })
+}
+
+/// Combines sequences of bindings, either one after the other, or fairly by taking a binding
+/// from each sequence in turn.
+fn flatten<'a, I>(fair: bool, streams: I) -> Bindings<'a>
+where
+    I: Iterator<Item = Bindings<'a>> + 'a,
+{
+    if fair {
+        Box::new(Interleave {
+            streams: Some(streams),
+            active: VecDeque::new(),
+        })
+    } else {
+        Box::new(streams.flatten())
+    }
+}
+
+/// Takes one binding from each sequence in turn, starting a new sequence between each, so
+/// that no sequence with infinitely many bindings can starve the others.
+struct Interleave<'a, I> {
+    streams: Option<I>,
+    active: VecDeque<Bindings<'a>>,
+}
+
+impl<'a, I> Iterator for Interleave<'a, I>
+where
+    I: Iterator<Item = Bindings<'a>>,
+{
+    type Item = Cow<'a, Binding>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(streams) = &mut self.streams {
+                match streams.next() {
+                    Some(stream) => self.active.push_back(stream),
+                    None => self.streams = None,
+                }
+            }
+            let mut stream = match self.active.pop_front() {
+                Some(stream) => stream,
+                None if self.streams.is_none() => return None,
+                None => continue,
+            };
+            if let Some(binding) = stream.next() {
+                self.active.push_back(stream);
+                return Some(binding);
+            }
+        }
+    }
+}
+
+/// Calls a function if a sequence of bindings ends without producing any.
+fn on_failure<'a, F>(bindings: Bindings<'a>, failed: F) -> Bindings<'a>
+where
+    F: FnOnce() + 'a,
+{
+    Box::new(OnFailure {
+        bindings,
+        failed: Some(failed),
+    })
+}
+
+struct OnFailure<'a, F> {
+    bindings: Bindings<'a>,
+    failed: Option<F>,
+}
+
+impl<'a, F> Iterator for OnFailure<'a, F>
+where
+    F: FnOnce(),
+{
+    type Item = Cow<'a, Binding>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let binding = self.bindings.next();
+        match binding {
+            Some(..) => self.failed = None,
+            None => {
+                if let Some(failed) = self.failed.take() {
+                    failed();
+                }
+            }
+        }
+        binding
+    }
+}
+
+/// Reports the ports through which a call passes as its bindings are requested: the call
+/// before the first, an exit with each binding produced, a redo before each binding requested
+/// after that, and a failure once there are no more. A binding in which an exception has been
+/// thrown is reported as a failure rather than an exit.
+fn trace<'a, F>(bindings: Bindings<'a>, tracer: F) -> Bindings<'a>
+where
+    F: FnMut(Port, Option<&Binding>) + 'a,
+{
+    Box::new(Traced {
+        bindings,
+        tracer,
+        called: false,
+        exited: false,
+        failed: false,
+    })
+}
+
+struct Traced<'a, F> {
+    bindings: Bindings<'a>,
+    tracer: F,
+    called: bool,
+    exited: bool,
+    failed: bool,
+}
+
+impl<'a, F> Iterator for Traced<'a, F>
+where
+    F: FnMut(Port, Option<&Binding>),
+{
+    type Item = Cow<'a, Binding>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if !self.called {
+            self.called = true;
+            (self.tracer)(Port::Call, None);
+        } else if self.exited {
+            (self.tracer)(Port::Redo, None);
+        }
+        let binding = self.bindings.next();
+        match &binding {
+            Some(binding) if !binding.is_exception() => {
+                self.exited = true;
+                (self.tracer)(Port::Exit, Some(binding));
+            }
+            _ => {
+                self.failed = true;
+                (self.tracer)(Port::Fail, None);
+            }
+        }
+        binding
+    }
+}
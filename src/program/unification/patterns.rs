@@ -103,11 +103,18 @@ fn unify_patterns_inner(
             let rhs_pat = binding.get(&rhs_var).unwrap();
             let (pattern, mut binding) = match (lhs_pat.kind(), rhs_pat.kind()) {
                 (PatternKind::Variable(lvar), PatternKind::Variable(rvar)) => {
-                    if lvar < rvar {
-                        (lhs_pat, binding)
+                    let pattern = if lvar < rvar {
+                        lhs_pat.clone()
                     } else {
-                        (rhs_pat, binding)
-                    }
+                        rhs_pat.clone()
+                    };
+                    // The variables may have been references to other variables, in which case
+                    // those must also be referenced to the new source of truth, or they would be
+                    // left behind.
+                    let mut binding = binding;
+                    binding.to_mut().set(lvar.clone(), pattern.clone());
+                    binding.to_mut().set(rvar.clone(), pattern.clone());
+                    (pattern, binding)
                 }
                 _ => unify_patterns_inner(lhs_pat, rhs_pat, binding)?,
             };
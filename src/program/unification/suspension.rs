@@ -0,0 +1,55 @@
+//! Resuming goals which were suspended until a variable was bound.
+
+use super::super::{Database, SuspendedGoal};
+use super::{Binding, Bindings, Frame};
+use std::borrow::Cow;
+
+impl Database<'_> {
+    /// Checks the constraints of the binding, and then runs the suspended goals whose variables
+    /// have been bound since they were suspended. This is done wherever variables are bound, so
+    /// that constraints are enforced and goals are woken as soon as possible. The goals are run
+    /// in the order in which they were suspended, and may in turn wake others.
+    pub(super) fn resume<'a>(&'a self, mut binding: Cow<'a, Binding>) -> Bindings<'a> {
+        if binding.is_exception() {
+            return Box::new(std::iter::once(binding));
+        }
+        if binding.has_constraints() && !binding.to_mut().check_constraints() {
+            return Box::new(std::iter::empty());
+        }
+        if !binding.has_woken() {
+            return Box::new(std::iter::once(binding));
+        }
+        let woken = binding.to_mut().take_woken();
+        woken.into_iter().fold(
+            Box::new(std::iter::once(binding)),
+            |bindings, suspension| {
+                Box::new(bindings.flat_map(move |binding| {
+                    self.unify_suspended(suspension.goal.clone(), binding)
+                }))
+            },
+        )
+    }
+
+    fn unify_suspended<'a>(
+        &'a self,
+        goal: SuspendedGoal,
+        binding: Cow<'a, Binding>,
+    ) -> Bindings<'a> {
+        match goal {
+            SuspendedGoal::Call {
+                builtin,
+                goal,
+                predicate,
+                public,
+            } => {
+                let predicate = predicate.and_then(|handle| self.resolve(&handle, false));
+                let frame = Frame::resumed(public, predicate);
+                self.unify_goal((&builtin, 2), &goal, vec![], binding, frame)
+            }
+            SuspendedGoal::Native { handle, args } => {
+                let handle = self.resolve(&handle, false).unwrap();
+                self.unify_query(handle, args, binding, Frame::resumed(false, None))
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use super::*;
+use std::path::Path;
+
+fn fixture(name: &str) -> PathBuf {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    here.join(name).canonicalize().unwrap()
+}
+
+fn locations(locations: Vec<SourceLocation>) -> Vec<(String, usize, usize)> {
+    locations
+        .into_iter()
+        .map(|location| {
+            (
+                location
+                    .path()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned(),
+                location.span().start_line(),
+                location.span().start_column(),
+            )
+        })
+        .collect()
+}
+
+fn analyse(name: &str) -> (Analysis, PathBuf) {
+    let main = fixture(name).join("main.lumber");
+    (Analysis::from_file(&main).unwrap(), main)
+}
+
+#[test]
+fn analysis_warnings() {
+    let (analysis, _) = analyse("analysis_program");
+    let diagnostics: Vec<_> = analysis
+        .diagnostics()
+        .iter()
+        .map(Diagnostic::code)
+        .collect();
+    assert_eq!(diagnostics, vec!["unused_glob", "unused_predicate"]);
+}
+
+#[test]
+fn analysis_errors() {
+    let (analysis, main) = analyse("analysis_errors");
+    let diagnostics = analysis.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "unresolved_predicate");
+    assert_eq!(diagnostics[0].path(), Some(main.as_path()));
+    assert_eq!(diagnostics[0].span().unwrap().start_line(), 2);
+}
+
+#[test]
+fn analysis_indexes_program_with_errors() {
+    let (analysis, main) = analyse("analysis_errors");
+    assert_eq!(
+        locations(analysis.definitions(&main, 3, 6)),
+        vec![("main.lumber".to_owned(), 2, 1)],
+    );
+    assert_eq!(
+        analysis.hover(&main, 3, 6).unwrap(),
+        "private predicate a/0\n\nThe first.",
+    );
+}
+
+#[test]
+fn analysis_definition_of_imported_predicate() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 6, 11)),
+        vec![("animals.lumber".to_owned(), 7, 1)],
+    );
+}
+
+#[test]
+fn analysis_definition_of_alias() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 7, 12)),
+        vec![("animals.lumber".to_owned(), 8, 1)],
+    );
+}
+
+#[test]
+fn analysis_definition_of_scoped_predicate() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 8, 20)),
+        vec![("animals.lumber".to_owned(), 9, 1)],
+    );
+}
+
+#[test]
+fn analysis_definition_of_operator() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 9, 20)),
+        vec![("animals.lumber".to_owned(), 5, 7)],
+    );
+}
+
+#[test]
+fn analysis_definition_of_module() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 2, 8)),
+        vec![("animals.lumber".to_owned(), 1, 1)],
+    );
+}
+
+#[test]
+fn analysis_definitions_of_local_predicate() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.definitions(&main, 4, 8)),
+        vec![
+            ("main.lumber".to_owned(), 6, 1),
+            ("main.lumber".to_owned(), 7, 1),
+            ("main.lumber".to_owned(), 8, 1),
+        ],
+    );
+}
+
+#[test]
+fn analysis_references() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        locations(analysis.references(&main, 6, 11)),
+        vec![
+            ("animals.lumber".to_owned(), 1, 8),
+            ("animals.lumber".to_owned(), 7, 1),
+            ("main.lumber".to_owned(), 3, 16),
+            ("main.lumber".to_owned(), 6, 11),
+        ],
+    );
+}
+
+#[test]
+fn analysis_nothing_at_position() {
+    let (analysis, main) = analyse("analysis_program");
+    assert!(analysis.definitions(&main, 5, 1).is_empty());
+    assert!(analysis.references(&main, 6, 5).is_empty());
+    assert!(analysis.hover(&main, 6, 5).is_none());
+}
+
+#[test]
+fn analysis_hover() {
+    let (analysis, main) = analyse("analysis_program");
+    assert_eq!(
+        analysis.hover(&main, 7, 11).unwrap(),
+        "public predicate animals::dog/1",
+    );
+    assert_eq!(
+        analysis.hover(&main, 9, 1).unwrap(),
+        "private predicate both/1",
+    );
+    assert_eq!(
+        analysis.hover(&main, 9, 21).unwrap(),
+        "public operator <+> of animals::combine/3",
+    );
+    assert_eq!(analysis.hover(&main, 2, 8).unwrap(), "module animals");
+}
+
+#[test]
+fn analysis_completions() {
+    let (analysis, main) = analyse("analysis_program");
+    let completions: Vec<_> = analysis
+        .completions(&main)
+        .into_iter()
+        .map(|completion| {
+            (
+                completion.kind(),
+                completion.name().to_owned(),
+                completion.detail().to_owned(),
+            )
+        })
+        .collect();
+    for expected in [
+        (CompletionKind::Predicate, "cat", "animals::cat/1"),
+        (CompletionKind::Predicate, "hound", "animals::dog/1"),
+        (CompletionKind::Predicate, "pet", "pet/1"),
+        (CompletionKind::Predicate, "add", "@core::add/3"),
+        (CompletionKind::Operator, "<+>", "animals::combine/3"),
+        (CompletionKind::Operator, "+", "@core::add/3"),
+        (CompletionKind::Module, "animals", "animals"),
+    ] {
+        let expected = (expected.0, expected.1.to_owned(), expected.2.to_owned());
+        assert!(completions.contains(&expected), "missing {:?}", expected);
+    }
+}
+
+#[test]
+fn analysis_unknown_file() {
+    let (analysis, _) = analyse("analysis_program");
+    assert!(analysis.completions(Path::new("nowhere.lumber")).is_empty());
+}
+
+#[test]
+fn analysis_missing_module() {
+    let (analysis, main) = analyse("analysis_missing_module");
+    let diagnostics: Vec<_> = analysis
+        .diagnostics()
+        .iter()
+        .map(Diagnostic::code)
+        .collect();
+    assert_eq!(diagnostics, vec!["unlinked_library", "unresolved_module"]);
+    assert!(analysis.definitions(&main, 4, 6).is_empty());
+    assert!(analysis.hover(&main, 4, 1).is_some());
+}
+
+#[test]
+fn analysis_finds_main_of_submodule() {
+    let main = fixture("analysis_nested").join("main.lumber");
+    let flower = fixture("analysis_nested").join("garden/flower.lumber");
+    assert_eq!(Analysis::find_main(&flower).unwrap(), main);
+    assert_eq!(Analysis::find_main(&main).unwrap(), main);
+
+    let analysis = Analysis::from_file(&main).unwrap();
+    assert!(analysis.contains(&flower));
+    assert!(analysis.diagnostics().is_empty());
+    assert_eq!(
+        locations(analysis.definitions(&flower, 3, 27)),
+        vec![("soil.lumber".to_owned(), 3, 1)],
+    );
+}
+
+#[test]
+fn analysis_submodule_alone() {
+    let flower = fixture("analysis_nested").join("garden/flower.lumber");
+    let analysis = Analysis::from_file(&flower).unwrap();
+    let diagnostics: Vec<_> = analysis
+        .diagnostics()
+        .iter()
+        .map(Diagnostic::code)
+        .collect();
+    assert!(diagnostics.contains(&"negative_scope"));
+}
+
+#[test]
+fn analysis_no_definition_of_library() {
+    let (analysis, main) = analyse("analysis_program");
+    assert!(analysis.definitions(&main, 1, 8).is_empty());
+    assert!(analysis
+        .hover(&main, 1, 8)
+        .unwrap()
+        .starts_with("module @core"));
+}
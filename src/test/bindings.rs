@@ -76,3 +76,12 @@ test! {
     ?- "test(1, struct { field: [element { a: 1, b: 3, c: 5 }, element { a: 1, b: 2, c: _ }] }, B)"
         B = Value::integer(2);
 }
+
+test! {
+    bind_aliased_variables => r#"
+    :- pub(alias/2).
+    alias(A, B) :- @core::equal(A, B), @core::equal(A, 1).
+    "#
+    ?- "alias(A, B)"
+        A = Value::integer(1), B = Value::integer(1);
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+fn program(src: &str) -> Lumber<'static> {
+    Lumber::builder()
+        .link("clpfd", crate::clpfd::library())
+        .build_from_str(src)
+        .unwrap()
+}
+
+#[test]
+fn clpfd_send_more_money() {
+    let program = program(
+        "
+        :- use(@clpfd).
+        :- pub(puzzle/1).
+
+        puzzle([S, E, N, D, M, O, R, Y]) :-
+            ins([S, E, N, D, M, O, R, Y], 0, 9),
+            all_different([S, E, N, D, M, O, R, Y]),
+            S $\\= 0,
+            M $\\= 0,
+            scalar_product([1000, 100, 10, 1, 1000, 100, 10, 1], [S, E, N, D, M, O, R, E], eq, Sum),
+            scalar_product([10000, 1000, 100, 10, 1], [M, O, N, E, Y], eq, Sum),
+            labeling([ff], [S, E, N, D, M, O, R, Y]).
+        ",
+    );
+    let question = Question::try_from("puzzle(Digits)").unwrap();
+    let mut answers = program.ask(&question);
+    let mut answer = answers.next().unwrap();
+    assert_eq!(
+        answer.remove("Digits").unwrap(),
+        list![9, 5, 6, 7, 1, 0, 8, 2],
+    );
+    assert!(answers.next().is_none());
+}
+
+#[test]
+fn clpfd_schedule() {
+    let program = program(
+        "
+        :- use(@clpfd).
+        :- pub(schedule/3).
+
+        // Three one-hour meetings in a four-hour day, where the first must come before the
+        // second, and the third may not be in the first hour.
+        schedule(A, B, C) :-
+            ins([A, B, C], 1, 4),
+            all_different([A, B, C]),
+            A $< B,
+            C $\\= 1,
+            label([A, B, C]).
+        ",
+    );
+    let question = Question::try_from("schedule(A, B, C)").unwrap();
+    let answers: Vec<_> = program
+        .ask(&question)
+        .map(|mut answer| {
+            vec![
+                answer.remove("A").unwrap(),
+                answer.remove("B").unwrap(),
+                answer.remove("C").unwrap(),
+            ]
+        })
+        .collect();
+    assert_eq!(
+        answers,
+        vec![
+            vec![Value::from(1), Value::from(2), Value::from(3)],
+            vec![Value::from(1), Value::from(2), Value::from(4)],
+            vec![Value::from(1), Value::from(3), Value::from(2)],
+            vec![Value::from(1), Value::from(3), Value::from(4)],
+            vec![Value::from(1), Value::from(4), Value::from(2)],
+            vec![Value::from(1), Value::from(4), Value::from(3)],
+            vec![Value::from(2), Value::from(3), Value::from(4)],
+            vec![Value::from(2), Value::from(4), Value::from(3)],
+            vec![Value::from(3), Value::from(4), Value::from(2)],
+        ],
+    );
+}
+
+#[test]
+fn clpfd_residuals() {
+    let program = program(
+        "
+        :- use(@clpfd).
+        :- pub(range/1).
+        :- pub(holes/1).
+        :- pub(less/2).
+
+        range(A) :- in(A, 1, 5).
+        holes(A) :- in(A, 1, 5), A $\\= 3.
+        less(A, B) :- A $< B.
+        ",
+    );
+
+    let question = Question::try_from("range(X)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert_eq!(
+        answer.residuals(),
+        &[Value::from(Struct::new(
+            "in",
+            Some(list![Value::atom("X"), 1, 5]),
+        ))],
+    );
+
+    let question = Question::try_from("holes(X)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert_eq!(
+        answer.residuals(),
+        &[Value::from(Struct::new(
+            "in",
+            Some(list![Value::atom("X"), list![list![1, 2], list![4, 5]]]),
+        ))],
+    );
+
+    let question = Question::try_from("less(X, Y)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert_eq!(
+        answer.residuals(),
+        &[Value::from(Struct::new(
+            "scalar_product",
+            Some(list![
+                list![1, -1],
+                list![Value::atom("X"), Value::atom("Y")],
+                Value::atom("leq"),
+                -1
+            ]),
+        ))],
+    );
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+const SRC: &str = "
+:- use(@core).
+:- pub(access/2).
+:- pub(admin/1).
+
+role(alice, admin).
+role(bob, viewer).
+
+grants(admin, write).
+grants(viewer, read).
+
+access(User, Permission) :- role(User, Role), grants(Role, Permission).
+admin(User) :- role(User, Role), equal(Role, admin).
+";
+
+#[test]
+fn diagnosis_closest_heads() {
+    let program = Lumber::builder().build_from_str(SRC).unwrap();
+    let question = Question::try_from("access(bob, write)").unwrap();
+    let diagnosis = program.diagnose(&question).unwrap();
+    assert_eq!(diagnosis.depth(), 2);
+    assert_eq!(diagnosis.failures().len(), 1);
+    let failure = &diagnosis.failures()[0];
+    assert_eq!(failure.handle(), "grants/2");
+    assert_eq!(
+        failure.arguments(),
+        &[Some(Value::atom("viewer")), Some(Value::atom("write"))],
+    );
+    let closest: Vec<_> = failure
+        .closest()
+        .iter()
+        .map(|clause| (clause.clause(), clause.line(), clause.matched()))
+        .collect();
+    assert_eq!(closest, vec![(0, 9, 1), (1, 10, 1)]);
+    assert_eq!(
+        diagnosis.to_string(),
+        "grants(viewer, write) failed
+    clause 0 at 9:1 matched 1 of 2 arguments
+    clause 1 at 10:1 matched 1 of 2 arguments
+",
+    );
+}
+
+#[test]
+fn diagnosis_not_needed() {
+    let program = Lumber::builder().build_from_str(SRC).unwrap();
+    let question = Question::try_from("access(bob, read)").unwrap();
+    assert!(program.diagnose(&question).is_none());
+}
+
+#[test]
+fn diagnosis_of_natives() {
+    let program = Lumber::builder().build_from_str(SRC).unwrap();
+    let question = Question::try_from("admin(bob)").unwrap();
+    let diagnosis = program.diagnose(&question).unwrap();
+    let failure = &diagnosis.failures()[0];
+    assert_eq!(failure.handle(), "@core::equal/2");
+    assert_eq!(failure.closest().len(), 1);
+    assert!(!failure.closest()[0].is_complete());
+}
+
+#[test]
+fn diagnosis_in_tests() {
+    let error = Lumber::builder()
+        .test(true)
+        .build_from_str(
+            "
+:- use(@core).
+:- test(likes(bob, A)).
+
+likes(alice, cats).
+likes(alice, dogs) :- likes(alice, cats).
+",
+        )
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::Test);
+    assert_eq!(
+        error.to_string(),
+        "1 tests have failed:
+\tlikes(bob, A)
+\t\tlikes(bob, _) failed
+\t\t    clause 0 at 5:1 matched 1 of 2 arguments
+\t\t    clause 1 at 6:1 matched 1 of 2 arguments",
+    );
+}
@@ -0,0 +1,152 @@
+use super::*;
+use std::path::Path;
+
+fn diagnostics(src: &str) -> Vec<Diagnostic> {
+    Lumber::from_source(src).unwrap_err().diagnostics().to_vec()
+}
+
+#[test]
+fn diagnostic_singleton_variable() {
+    let diagnostics = diagnostics("person(alice).\nparent(A, B) :- person(A).\n");
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity(), Severity::Error);
+    assert_eq!(diagnostic.code(), "singleton_variable");
+    let span = diagnostic.span().unwrap();
+    assert_eq!((span.start_line(), span.start_column()), (2, 11));
+    assert_eq!((span.end_line(), span.end_column()), (2, 12));
+    assert_eq!(
+        diagnostic.to_string(),
+        "\
+error[singleton_variable]: Singleton variable B in predicate parent/2.
+ --> 2:11
+  |
+2 | parent(A, B) :- person(A).
+  |           ^
+  = help: replace B with _ if its value is not needed
+"
+    );
+}
+
+#[test]
+fn diagnostic_unresolved_predicate() {
+    let diagnostics = diagnostics("person(alice).\n\n  parent(A) :- person(A), missing(A).\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "unresolved_predicate");
+    let span = diagnostics[0].span().unwrap();
+    assert_eq!((span.start_line(), span.start_column()), (3, 3));
+}
+
+#[test]
+fn diagnostic_unresolved_module() {
+    let diagnostics = diagnostics(":- use(missing).\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "unresolved_module");
+    assert!(diagnostics[0].span().is_none());
+}
+
+#[test]
+fn diagnostic_syntax_error() {
+    let diagnostics = diagnostics("person(alice).\nparent(A, B :- person(A).\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "syntax_error");
+    let span = diagnostics[0].span().unwrap();
+    assert_eq!((span.start_line(), span.start_column()), (2, 11));
+    assert!(diagnostics[0]
+        .to_string()
+        .contains("2 | parent(A, B :- person(A).\n  |           ^\n"));
+}
+
+#[test]
+fn diagnostic_in_module() {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    let path = here.join("diagnostic_in_module");
+    let error = Lumber::builder()
+        .build(path.clone(), ":- mod(inner).\n")
+        .unwrap_err();
+    let diagnostics = error.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "singleton_variable");
+    assert_eq!(
+        diagnostics[0].path(),
+        Some(path.join("inner.lumber").as_path())
+    );
+    assert!(diagnostics[0]
+        .to_string()
+        .contains("3 | parent(A, B) :- parent(A, C), parent(C, A).\n  |           ^\n"));
+}
+
+#[test]
+fn diagnostic_syntax_errors_recovered() {
+    let error = Lumber::from_source(
+        "
+        // a comment. with a dot
+        good(\"a string. with dots\", 1.5) :- good([1, 2, ..T], T).
+        bad(a b).
+        bad(a) :- good(a
+        also_bad(x).
+        /* a block comment. */ good(x.
+        ",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Multiple);
+    let diagnostics = error.diagnostics();
+    assert_eq!(diagnostics.len(), 3);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.code() == "syntax_error"));
+    let lines: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.span().unwrap().start_line())
+        .collect();
+    assert_eq!(lines, vec![4, 6, 7]);
+}
+
+#[test]
+fn diagnostic_syntax_error_at_end_recovered() {
+    let error = Lumber::from_source("good(A).\nbad(a) :- good(a)\n").unwrap_err();
+    let diagnostics: Vec<_> = error
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| (diagnostic.code(), diagnostic.span().unwrap().start_line()))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        vec![("syntax_error", 2), ("singleton_variable", 1)]
+    );
+}
+
+#[test]
+fn diagnostic_syntax_errors_in_modules() {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    let path = here.join("diagnostic_syntax_in_module");
+    let error = Lumber::builder()
+        .build(path.clone(), ":- mod(inner).\nroot(a b).\n")
+        .unwrap_err();
+    let mut diagnostics: Vec<_> = error
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.path().map(Path::to_owned),
+                diagnostic.span().unwrap().start_line(),
+            )
+        })
+        .collect();
+    diagnostics.sort();
+    assert_eq!(
+        diagnostics,
+        vec![
+            (None, 2),
+            (Some(path.join("inner.lumber")), 2),
+            (Some(path.join("inner.lumber")), 3),
+        ],
+    );
+}
+
+#[test]
+fn diagnostic_import_from_missing_module() {
+    let diagnostics = diagnostics(":- use(nowhere(a/0)).\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code(), "unresolved_module");
+}
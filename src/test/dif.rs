@@ -0,0 +1,58 @@
+use super::*;
+
+test! {
+    dif_constrains => "
+    :- use(@core).
+    :- pub(pair/2).
+    :- pub(distinct/2).
+
+    pair(A, B) :- dif(A, B), between(1, 2, A), between(1, 2, B).
+    distinct(A, B) :- dif(A, B), equal(A, B).
+    "
+    ?- "pair(A, B)"
+        A = Value::from(1), B = Value::from(2);
+        A = Value::from(2), B = Value::from(1);
+    ?- "distinct(A, B)"
+}
+
+#[test]
+fn dif_residuals() {
+    let program = Lumber::builder()
+        .build_from_str(
+            "
+            :- use(@core).
+            :- pub(differ/2).
+            :- pub(partial/2).
+            :- pub(done/1).
+
+            differ(A, B) :- dif(A, B).
+            partial(A, B) :- dif([A, 1], [2, B]), equal(A, 2).
+            done(A) :- dif(A, 1), equal(A, 2).
+            ",
+        )
+        .unwrap();
+
+    let question = Question::try_from("differ(X, Y)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert_eq!(
+        answer.residuals(),
+        &[Value::from(Struct::new(
+            "dif",
+            Some(list![Value::atom("X"), Value::atom("Y")]),
+        ))],
+    );
+
+    let question = Question::try_from("partial(X, Y)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert_eq!(
+        answer.residuals(),
+        &[Value::from(Struct::new(
+            "dif",
+            Some(list![list![2, 1], list![2, Value::atom("Y")]]),
+        ))],
+    );
+
+    let question = Question::try_from("done(X)").unwrap();
+    let answer = program.ask(&question).next().unwrap();
+    assert!(answer.residuals().is_empty());
+}
@@ -0,0 +1,148 @@
+use super::*;
+
+fn predicate<'a>(module: &'a ModuleInfo, handle: &str) -> &'a PredicateInfo {
+    module
+        .predicates()
+        .iter()
+        .find(|predicate| predicate.handle() == handle)
+        .unwrap()
+}
+
+#[test]
+fn documentation_on_clauses() {
+    let program = Lumber::from_source(
+        "
+:- pub(greet/1).
+
+/// Greets someone by name.
+///
+/// Says hello.
+greet(Name) :- says(Name, hello).
+/// Not the first clause.
+greet(_).
+says(_, _).
+",
+    )
+    .unwrap();
+    let modules = program.modules();
+    assert_eq!(modules.len(), 1);
+    assert_eq!(modules[0].path(), "~");
+    let greet = predicate(&modules[0], "greet/1");
+    assert!(greet.is_public());
+    assert_eq!(
+        greet.doc(),
+        Some("Greets someone by name.\n\nSays hello.\n\nNot the first clause."),
+    );
+    let says = predicate(&modules[0], "says/2");
+    assert!(!says.is_public());
+    assert_eq!(says.doc(), None);
+}
+
+#[test]
+fn documentation_on_directives() {
+    let program = Lumber::builder()
+        .bind("native/0", |_| Box::new(std::iter::empty()))
+        .build(
+            "",
+            "
+/// Exported.
+:- pub(a/0).
+/// Native.
+:- nat(native/0).
+:- pub(native/0).
+/// Mutable.
+:- mut(b/0).
+/// Defined.
+a.
+",
+        )
+        .unwrap();
+    let modules = program.modules();
+    assert_eq!(
+        predicate(&modules[0], "a/0").doc(),
+        Some("Exported.\n\nDefined.")
+    );
+    let native = predicate(&modules[0], "native/0");
+    assert!(native.is_native());
+    assert_eq!(native.doc(), Some("Native."));
+    let b = predicate(&modules[0], "b/0");
+    assert!(b.is_mutable());
+    assert_eq!(b.doc(), Some("Mutable."));
+}
+
+#[test]
+fn documentation_not_attached() {
+    let program = Lumber::from_source(
+        "
+:- pub(a/0).
+:- pub(b/0).
+:- pub(c/0).
+
+/// Separated by a comment.
+// Not documentation.
+a.
+//// Not documentation either.
+b.
+/// Inside a clause.
+c :- /// also inside.
+    a.
+",
+    )
+    .unwrap();
+    let modules = program.modules();
+    assert_eq!(predicate(&modules[0], "a/0").doc(), None);
+    assert_eq!(predicate(&modules[0], "b/0").doc(), None);
+    assert_eq!(
+        predicate(&modules[0], "c/0").doc(),
+        Some("Inside a clause.")
+    );
+}
+
+#[test]
+fn documentation_modules() {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    let program = Lumber::builder()
+        .build(
+            here.join("documentation_modules"),
+            "
+/// Measuring shapes.
+:- mod(shapes).
+",
+        )
+        .unwrap();
+    let modules = program.modules();
+    let paths: Vec<_> = modules.iter().map(ModuleInfo::path).collect();
+    assert_eq!(paths, vec!["shapes", "~"]);
+    assert_eq!(modules[1].submodules(), ["shapes"]);
+    let shapes = &modules[0];
+    assert_eq!(shapes.doc(), Some("Measuring shapes."));
+    assert_eq!(
+        predicate(shapes, "shapes::area/2").doc(),
+        Some("The area of a square."),
+    );
+    let operator = &shapes.operators()[0];
+    assert_eq!(operator.name(), "<>");
+    assert_eq!(operator.handle(), "shapes::same_area/2");
+    assert!(operator.is_public());
+    assert!(!operator.is_unary());
+    assert_eq!(operator.precedence(), None);
+    assert_eq!(operator.doc(), Some("Compares the areas of two shapes."));
+}
+
+#[test]
+fn documentation_operators() {
+    let program = Lumber::from_source(
+        "
+:- pub(~~).
+/// Combines two values.
+:- op(~~, combine/3, right, 4).
+combine(A, B, [A, B]).
+",
+    )
+    .unwrap();
+    let modules = program.modules();
+    let operator = &modules[0].operators()[0];
+    assert_eq!(operator.associativity(), Some(Associativity::Right));
+    assert_eq!(operator.precedence(), Some(4));
+    assert_eq!(operator.doc(), Some("Combines two values."));
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+test! {
+    enumerate_between => "
+    :- use(@core).
+    :- pub(evens/1).
+
+    evens(X) :- between(1, 6, X), rem(X, 2, 0).
+    "
+    ?- "evens(X)"
+        X = Value::from(2);
+        X = Value::from(4);
+        X = Value::from(6);
+}
+
+#[test]
+#[rustfmt::skip]
+fn enumerate_nat_lazily() {
+    let program = Lumber::builder().build_from_str(r#"
+        :- use(@core).
+        :- pub(square/1).
+
+        square(S) :- nat(N), S =:= N * N.
+    "#).unwrap();
+    let question = Question::try_from("square(S)").unwrap();
+    let squares: Vec<_> = program
+        .ask(&question)
+        .take(4)
+        .map(|mut answer| answer.remove("S").unwrap())
+        .collect();
+    assert_eq!(squares, vec![Value::from(0), Value::from(1), Value::from(4), Value::from(9)]);
+}
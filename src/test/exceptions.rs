@@ -0,0 +1,128 @@
+use super::*;
+
+test! {
+    exception_caught => "
+    :- use(@core).
+    :- pub(safe_div/3).
+
+    safe_div(A, B, C) :- catch([div, A, B, C], type_error [_, _], [equal, C, nan]).
+    "
+    ?- "safe_div(6, 3, C)"
+        C = Value::from(2);
+    ?- "safe_div(6, a, C)"
+        C = Value::atom("nan");
+}
+
+test! {
+    exception_goal_resolved_in_module => "
+    :- use(@core).
+    :- mod(inner).
+    :- use(inner(attempt/1)).
+    :- pub(attempt/1).
+    "
+    ?- "attempt(X)"
+        X = Value::atom("failed");
+}
+
+test! {
+    exception_ends_answers => "
+    :- use(@core).
+    :- pub(items/1).
+
+    items(1).
+    items(2) :- throw(stop).
+    items(3).
+    "
+    ?- "items(X)"
+        X = Value::from(1);
+}
+
+#[test]
+fn exception_uncaught() {
+    let program = Lumber::builder()
+        .build_from_str(
+            "
+            :- use(@core).
+            :- pub(items/1).
+
+            items(1).
+            items(X) :- add(X, a, 3).
+            ",
+        )
+        .unwrap();
+    let question = Question::try_from("items(X)").unwrap();
+    let mut answers = program.try_ask(&question);
+    let mut answer = answers.next().unwrap().unwrap();
+    assert_eq!(answer.remove("X"), Some(Value::from(1)));
+    let error = match answers.next() {
+        Some(Err(error)) => error,
+        _ => panic!("expected an exception"),
+    };
+    assert_eq!(error.kind(), ErrorKind::Runtime);
+    assert_eq!(
+        error.exception(),
+        Some(&Value::from(Struct::new(
+            "type_error",
+            Some(list![Value::atom("number"), Value::atom("a")])
+        ))),
+    );
+    assert!(answers.next().is_none());
+}
+
+#[test]
+fn exception_from_native() {
+    let program = Lumber::builder()
+        .bind("fail_loudly/0", |_| {
+            Box::new(std::iter::once(Err(Value::from("loud"))))
+        })
+        .build_from_str(
+            "
+            :- use(@core).
+            :- nat(fail_loudly/0).
+            :- pub(quiet/1).
+
+            quiet(E) :- catch(fail_loudly, E, true).
+            ",
+        )
+        .unwrap();
+    let question = Question::try_from("quiet(E)").unwrap();
+    let mut answers = program.ask(&question);
+    let mut answer = answers.next().unwrap();
+    assert_eq!(answer.remove("E"), Some(Value::from("loud")));
+    assert!(answers.next().is_none());
+}
+
+#[test]
+fn exception_uncaught_explained_and_traced() {
+    let program = Lumber::from_source(
+        "
+        :- use(@core).
+        :- pub(items/1).
+
+        items(1).
+        items(2) :- throw(stop).
+        ",
+    )
+    .unwrap();
+    let question = Question::try_from("items(X)").unwrap();
+
+    let mut answers = program.try_ask_explained(&question);
+    assert!(answers.next().unwrap().is_ok());
+    let error = match answers.next() {
+        Some(Err(error)) => error,
+        _ => panic!("expected an exception"),
+    };
+    assert_eq!(error.kind(), ErrorKind::Runtime);
+    assert_eq!(error.exception(), Some(&Value::atom("stop")));
+    assert!(answers.next().is_none());
+
+    let profiler = Profiler::new();
+    let mut answers = program.try_ask_traced(&question, profiler);
+    assert!(answers.next().unwrap().is_ok());
+    let error = match answers.next() {
+        Some(Err(error)) => error,
+        _ => panic!("expected an exception"),
+    };
+    assert_eq!(error.exception(), Some(&Value::atom("stop")));
+    assert!(answers.next().is_none());
+}
@@ -0,0 +1,119 @@
+use super::*;
+
+#[test]
+fn explanation_tree() {
+    let program = Lumber::builder()
+        .build_from_str(
+            "
+:- use(@core).
+:- pub(access/2).
+
+role(alice, admin).
+role(bob, viewer).
+
+grants(admin, write).
+grants(admin, read).
+grants(viewer, read).
+
+access(User, Permission) :- role(User, Role), grants(Role, Permission).
+",
+        )
+        .unwrap();
+
+    let question = Question::try_from("access(bob, P)").unwrap();
+    let mut answers = program.ask_explained(&question);
+    let (mut answer, explanation) = answers.next().unwrap();
+    assert_eq!(answer.remove("P").unwrap(), Value::atom("read"));
+    assert!(answers.next().is_none());
+
+    assert_eq!(explanation.derivations().len(), 1);
+    let access = &explanation.derivations()[0];
+    assert_eq!(access.handle(), "access/2");
+    assert_eq!(
+        (access.clause(), access.line(), access.column()),
+        (0, 12, 1)
+    );
+    assert_eq!(
+        access.arguments(),
+        &[Some(Value::atom("bob")), Some(Value::atom("read"))],
+    );
+
+    let children = access.children();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].handle(), "role/2");
+    assert_eq!((children[0].clause(), children[0].line()), (1, 6));
+    assert_eq!(children[1].handle(), "grants/2");
+    assert_eq!((children[1].clause(), children[1].line()), (2, 10));
+    assert_eq!(
+        children[1].arguments(),
+        &[Some(Value::atom("viewer")), Some(Value::atom("read"))],
+    );
+    assert!(children[1].children().is_empty());
+
+    assert_eq!(
+        explanation.to_string(),
+        "access(bob, read) by clause 0 in ~ at 12:1
+  role(bob, viewer) by clause 1 in ~ at 6:1
+  grants(viewer, read) by clause 2 in ~ at 10:1
+",
+    );
+}
+
+#[test]
+fn explanation_separate_answers() {
+    let program = Lumber::builder()
+        .build_from_str(
+            "
+:- use(@core).
+:- pub(small/1).
+
+small(A) :- between(1, 2, A).
+small(A) :- zero(A).
+
+zero(0).
+",
+        )
+        .unwrap();
+
+    let question = Question::try_from("small(A)").unwrap();
+    let explanations: Vec<_> = program
+        .ask_explained(&question)
+        .map(|(_, explanation)| explanation.to_string())
+        .collect();
+    assert_eq!(
+        explanations,
+        vec![
+            "small(1) by clause 0 in ~ at 5:1\n",
+            "small(2) by clause 0 in ~ at 5:1\n",
+            "small(0) by clause 1 in ~ at 6:1\n  zero(0) by clause 0 in ~ at 8:1\n",
+        ],
+    );
+}
+
+#[test]
+fn explanation_across_modules() {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    let program = Lumber::builder()
+        .build(
+            here.join("explanation_modules"),
+            "
+:- mod(pets).
+:- use(pets(pet/1)).
+:- pub(owned/1).
+
+owned(A) :- pet(A).
+",
+        )
+        .unwrap();
+
+    let question = Question::try_from("owned(A)").unwrap();
+    let (_, explanation) = program.ask_explained(&question).next().unwrap();
+    let owned = &explanation.derivations()[0];
+    let pet = &owned.children()[0];
+    assert_eq!((owned.module(), owned.line()), ("~", 6));
+    assert_eq!((pet.module(), pet.line()), ("pets", 6));
+    assert_eq!(
+        explanation.to_string(),
+        "owned(cat) by clause 0 in ~ at 6:1\n  pets::pet(cat) by clause 0 in pets at 6:1\n",
+    );
+}
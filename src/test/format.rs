@@ -0,0 +1,148 @@
+use super::*;
+
+fn formats(src: &str, expected: &str) {
+    let formatted = format(src).unwrap();
+    assert_eq!(formatted, expected);
+    assert_eq!(format(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn format_spacing() {
+    formats(
+        "foo( A,B ):-bar( [A,..B] ),A=:=1+ -B .\nbaz({a:1,..R},{ 1,2 },{:},x[1]):-R=:=[X:qux(X)].\n",
+        "\
+foo(A, B) :-
+    bar([A, ..B]),
+    A =:= 1 + -B.
+baz({ a: 1, ..R }, { 1, 2 }, {:}, x [1]) :- R =:= [X : qux(X)].
+",
+    );
+}
+
+#[test]
+fn format_rule_layout() {
+    formats(
+        "a(X) :- b(X).\nc(X) ::- d(X) -> e(X), f(X).\n",
+        "\
+a(X) :- b(X).
+c(X) ::-
+    d(X) -> e(X),
+    f(X).
+",
+    );
+}
+
+#[test]
+fn format_disjunction_layout() {
+    formats(
+        "a(X) :- b(X), c(X) ->> d(X); e(X).\nf(X) :- g(X), (h(X); i(X)), j(X).\n",
+        "\
+a(X) :-
+    b(X),
+    c(X)
+->> d(X)
+;   e(X).
+f(X) :-
+    g(X),
+    (   h(X)
+    ;   i(X)
+    ),
+    j(X).
+",
+    );
+}
+
+#[test]
+fn format_long_rule() {
+    let src = format!("a :- {}(1).\n", "b".repeat(100));
+    formats(&src, &format!("a :-\n    {}(1).\n", "b".repeat(100)));
+}
+
+#[test]
+fn format_directive_order() {
+    formats(
+        ":- pub(a/0).\n:- use(@core).\n:- mod(c).\n:- mod(b).\n\n:- nat(d/0).\n:- pub(d/0).\n:- allow(unused_import).\na.\n",
+        "\
+:- mod(b).
+:- mod(c).
+:- use(@core).
+:- pub(a/0).
+
+:- allow(unused_import).
+:- nat(d/0).
+:- pub(d/0).
+a.
+",
+    );
+}
+
+#[test]
+fn format_tests_not_reordered() {
+    formats(
+        ":- pub(a/0).\n:- test(a).\n:- mod(b).\na.\n",
+        ":- pub(a/0).\n:- test(a).\n:- mod(b).\na.\n",
+    );
+}
+
+#[test]
+fn format_named_test() {
+    formats(
+        ":- test( \"is a\" ,a ).\na.\n",
+        ":- test(\"is a\", a).\na.\n",
+    );
+}
+
+#[test]
+fn format_comments() {
+    formats(
+        "// about a\n:- pub(a/1). // exported\n\n\n/* block */\n\na(X) :-\n  // first\n  b(X), // trailing\n  c(X).\n// end\n",
+        "\
+// about a
+:- pub(a/1). // exported
+
+/* block */
+
+a(X) :-
+    // first
+    b(X), // trailing
+    c(X).
+// end
+",
+    );
+}
+
+#[test]
+fn format_comments_within_statement() {
+    formats(
+        "a(X, /* inner */ Y) :- b(X, Y).\n",
+        "/* inner */\na(X, Y) :- b(X, Y).\n",
+    );
+}
+
+#[test]
+fn format_comments_move_with_directives() {
+    formats(
+        ":- pub(a/0).\n// the b module\n:- mod(b).\na.\n",
+        "// the b module\n:- mod(b).\n:- pub(a/0).\na.\n",
+    );
+}
+
+#[test]
+fn format_atom_quoting() {
+    formats(
+        "a('b', 'C', 'd e', #'f'g'#, 'h_1').\n",
+        "a(b, 'C', 'd e', #'f'g'#, h_1).\n",
+    );
+}
+
+#[test]
+fn format_syntax_error() {
+    let error = format("a(.\n").unwrap_err();
+    assert_eq!(error.diagnostics()[0].code(), "syntax_error");
+}
+
+#[test]
+fn format_core_is_formatted() {
+    let core = include_str!("../core/core.lumber");
+    assert_eq!(format(core).unwrap(), core);
+}
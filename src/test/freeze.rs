@@ -0,0 +1,75 @@
+use super::*;
+
+test! {
+    freeze_delays_goal => "
+    :- use(@core).
+    :- pub(small/1).
+    :- pub(frozen/1).
+    :- pub(waits/2).
+
+    small(X) :- freeze(X, [lt, X, 3]), between(1, 5, X).
+    frozen(X) :- freeze(X, false).
+    waits(X, Y) :- freeze(X, [equal, Y, done]), equal(X, go).
+    "
+    ?- "small(X)"
+        X = Value::from(1);
+        X = Value::from(2);
+    ?- "frozen(X)"
+        ;
+    ?- "waits(X, Y)"
+        X = Value::atom("go"), Y = Value::atom("done");
+}
+
+test! {
+    freeze_in_module => "
+    :- use(@core).
+    :- mod(inner).
+    :- use(inner(check/1)).
+    :- pub(check/1).
+    "
+    ?- "check(1)";
+    ?- "check(2)"
+}
+
+fn double(
+    values: Vec<Option<Value>>,
+) -> Box<dyn Iterator<Item = std::result::Result<Vec<Option<Value>>, Value>>> {
+    match &values[..] {
+        [Some(Value::Integer(input)), None] => Box::new(std::iter::once(Ok(vec![
+            values[0].clone(),
+            Some(Value::Integer(input.clone() * 2)),
+        ]))),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+#[test]
+fn freeze_native_suspends() {
+    let program = Lumber::builder()
+        .bind("double/2", double)
+        .modes("double/2", &["+-"])
+        .suspend("double/2")
+        .build_from_str(
+            "
+            :- use(@core).
+            :- nat(double/2).
+            :- pub(later/2).
+            :- pub(never/2).
+
+            later(A, B) :- double(A, B), equal(A, 3).
+            never(A, B) :- double(A, B).
+            ",
+        )
+        .unwrap();
+    let question = Question::try_from("later(A, B)").unwrap();
+    let mut answers = program.ask(&question);
+    let mut answer = answers.next().unwrap();
+    assert_eq!(answer.remove("B"), Some(Value::from(6)));
+    assert!(answers.next().is_none());
+
+    let question = Question::try_from("never(A, B)").unwrap();
+    let mut answers = program.ask(&question);
+    let mut answer = answers.next().unwrap();
+    assert_eq!(answer.remove("B"), None);
+    assert!(answers.next().is_none());
+}
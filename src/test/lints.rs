@@ -0,0 +1,157 @@
+use super::*;
+
+fn warnings(src: &str) -> Vec<(String, usize)> {
+    Lumber::from_source(src)
+        .unwrap()
+        .warnings()
+        .iter()
+        .map(|warning| {
+            assert_eq!(warning.severity(), Severity::Warning);
+            (
+                warning.code().to_owned(),
+                warning.span().map_or(0, |span| span.start_line()),
+            )
+        })
+        .collect()
+}
+
+fn codes(src: &str) -> Vec<String> {
+    warnings(src).into_iter().map(|(code, _)| code).collect()
+}
+
+#[test]
+fn lint_clean() {
+    assert!(codes(
+        "
+        :- use(@core).
+        :- pub(count/2).
+        count([], 0).
+        count([_, ..T], N) :- count(T, M), N =:= M + 1.
+        "
+    )
+    .is_empty());
+}
+
+#[test]
+fn lint_unused_predicate() {
+    assert_eq!(
+        warnings(
+            "
+            :- pub(used/1).
+            used(X) :- helper(X).
+            helper(a).
+            unused(X) :- unused(X).
+            "
+        ),
+        vec![("unused_predicate".to_owned(), 5)],
+    );
+}
+
+#[test]
+fn lint_used_by_test() {
+    assert!(codes(":- test(helper(a)).\nhelper(a).").is_empty());
+}
+
+#[test]
+fn lint_unused_import() {
+    assert_eq!(
+        codes(
+            "
+            :- use(@core(add/3, sub/3)).
+            :- pub(inc/2).
+            inc(A, B) :- add(A, 1, B).
+            "
+        ),
+        vec!["unused_import"],
+    );
+}
+
+#[test]
+fn lint_unused_glob() {
+    assert_eq!(
+        warnings(":- use(@core).\n:- pub(test/1).\ntest(a)."),
+        vec![("unused_glob".to_owned(), 1)],
+    );
+    assert!(codes(":- use(@core).\n:- pub(test/1).\ntest(A) :- A =:= 1 + 1.").is_empty());
+}
+
+#[test]
+fn lint_unextended_predicate() {
+    assert_eq!(
+        codes(":- inc(test/1).\n:- mut(state/1).\n"),
+        vec!["unextended_predicate", "unextended_predicate"],
+    );
+}
+
+#[test]
+fn lint_unreachable_clause() {
+    assert_eq!(
+        warnings(
+            "
+            :- use(@core).
+            :- pub(test/2).
+            test(a, _) ::- true.
+            test(a, 2).
+            test(b, 3).
+            test(A, [A, .._]) ::- true.
+            test(c, [c, d]).
+            test(c, [d, c]).
+            "
+        ),
+        vec![
+            ("unreachable_clause".to_owned(), 5),
+            ("unreachable_clause".to_owned(), 8),
+        ],
+    );
+}
+
+#[test]
+fn lint_unused_operator() {
+    assert_eq!(
+        codes(
+            "
+            :- use(@core).
+            :- op(<+>, add/3, left, 3).
+            :- op(<->, sub/3, left, 3).
+            :- pub(test/1).
+            test(A) :- A =:= 1 <+> 2.
+            "
+        ),
+        vec!["unused_operator"],
+    );
+}
+
+#[test]
+fn lint_discontiguous_clauses() {
+    assert_eq!(
+        warnings(
+            "
+            :- pub(a/1).
+            :- pub(b/1).
+            a(1).
+            b(1).
+            a(2).
+            "
+        ),
+        vec![("discontiguous_clauses".to_owned(), 6)],
+    );
+}
+
+#[test]
+fn lint_allow() {
+    assert!(codes(
+        "
+        :- allow(unused_predicate).
+        :- allow(unused_glob).
+        :- use(@core).
+        unused(a).
+        "
+    )
+    .is_empty());
+}
+
+#[test]
+fn lint_allow_unknown() {
+    let error = Lumber::from_source(":- allow(unused).").unwrap_err();
+    assert_eq!(error.diagnostics()[0].code(), "unknown_lint");
+}
@@ -47,13 +47,31 @@ macro_rules! test {
 
 mod accessibility;
 mod aggregation;
+mod analysis;
 mod bindings;
+mod clpfd;
 mod conjunction;
+mod diagnosis;
+mod diagnostic;
+mod dif;
 mod disjunction;
+mod documentation;
+mod enumeration;
+mod exceptions;
+mod explanation;
+mod format;
+mod freeze;
 mod imports;
+mod lints;
+mod modes;
 mod once;
 mod operators;
 mod procession;
+mod profile;
 mod records;
 mod relations;
+mod strategy;
+mod strict;
 mod tests;
+mod tracer;
+mod types;
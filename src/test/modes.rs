@@ -0,0 +1,98 @@
+use super::*;
+
+fn instantiation_error(handle: &str, positions: Vec<usize>) -> Value {
+    let contents = list![handle, Value::from(positions)];
+    Value::from(Struct::new("instantiation_error", Some(contents)))
+}
+
+fn first_exception(program: &Lumber, question: &str) -> Option<Value> {
+    let question = Question::try_from(question).unwrap();
+    let exception = program
+        .try_ask(&question)
+        .find_map(|answer| answer.err())?
+        .exception()
+        .cloned();
+    exception
+}
+
+const SOURCE: &str = "
+:- use(@core).
+:- pub(sum/3).
+:- pub(double/2).
+:- pub(safe_sum/3).
+
+sum(A, B, C) :- add(A, B, C).
+double(A, B) :- B =:= A * 2.
+safe_sum(A, B, C) :- catch([add, A, B, C], instantiation_error [_, _], [equal, C, unknown]).
+";
+
+#[test]
+fn modes_underbound_native() {
+    let program = Lumber::builder()
+        .check_modes(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    assert_eq!(
+        first_exception(&program, "sum(A, B, 3)"),
+        Some(instantiation_error("@core::add/3", vec![1])),
+    );
+    assert_eq!(
+        first_exception(&program, "sum(A, B, C)"),
+        Some(instantiation_error("@core::add/3", vec![1, 2])),
+    );
+    assert_eq!(
+        first_exception(&program, "double(A, B)"),
+        Some(instantiation_error("@core::mul/3", vec![1])),
+    );
+}
+
+#[test]
+fn modes_satisfied() {
+    let program = Lumber::builder()
+        .check_modes(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    let question = Question::try_from("sum(A, 2, 3)").unwrap();
+    let mut answers = program.try_ask(&question);
+    let mut answer = answers.next().unwrap().ok().unwrap();
+    assert_eq!(answer.remove("A"), Some(Value::from(1)));
+    assert!(answers.next().is_none());
+}
+
+#[test]
+fn modes_caught() {
+    let program = Lumber::builder()
+        .check_modes(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    let question = Question::try_from("safe_sum(1, _, C)").unwrap();
+    let mut answer = program.ask(&question).next().unwrap();
+    assert_eq!(answer.remove("C"), Some(Value::atom("unknown")));
+}
+
+#[test]
+fn modes_not_checked() {
+    let program = Lumber::builder().build_from_str(SOURCE).unwrap();
+    assert_eq!(first_exception(&program, "sum(A, B, 3)"), None);
+    let question = Question::try_from("sum(A, B, 3)").unwrap();
+    assert!(program.ask(&question).next().is_none());
+}
+
+#[test]
+fn modes_user_native() {
+    let program = Lumber::builder()
+        .check_modes(true)
+        .bind("length/2", |_| Box::new(std::iter::empty()))
+        .modes("length/2", &["+-"])
+        .build_from_str(
+            "
+            :- nat(length/2).
+            :- pub(length/2).
+            ",
+        )
+        .unwrap();
+    assert_eq!(
+        first_exception(&program, "length(_, 3)"),
+        Some(instantiation_error("length/2", vec![1])),
+    );
+}
@@ -0,0 +1,96 @@
+use super::*;
+
+#[test]
+fn profile_counts() {
+    let program = Lumber::from_source(
+        "
+:- pub(grandparent/2).
+
+parent(alice, bob).
+parent(bob, carol).
+parent(bob, dave).
+
+grandparent(A, C) :- parent(A, B), parent(B, C).
+",
+    )
+    .unwrap();
+
+    let question = Question::try_from("grandparent(alice, C)").unwrap();
+    let (answers, profiler) = program.ask_profiled(&question);
+    assert_eq!(answers.count(), 2);
+    let profile = profiler.profile();
+
+    let grandparent = profile.entry("grandparent/2").unwrap();
+    assert_eq!(
+        (
+            grandparent.calls(),
+            grandparent.redos(),
+            grandparent.fails()
+        ),
+        (1, 2, 1)
+    );
+    let parent = profile.entry("parent/2").unwrap();
+    assert_eq!((parent.calls(), parent.redos(), parent.fails()), (2, 3, 2));
+    assert_eq!(parent.inferences(), 5);
+    assert_eq!(profile.inferences(), 8);
+    assert!(grandparent.total_time() >= parent.total_time());
+    assert_eq!(profile.entries()[0].handle(), "grandparent/2");
+}
+
+#[test]
+fn profile_collapsed() {
+    let program = Lumber::from_source(
+        "
+:- use(@core).
+:- pub(count/2).
+
+count(0, []).
+count(N, [_, ..T]) :- count(M, T), N =:= M + 1.
+",
+    )
+    .unwrap();
+
+    let question = Question::try_from("count(N, [a, b])").unwrap();
+    let (answers, profiler) = program.ask_profiled(&question);
+    answers.for_each(drop);
+    let profile = profiler.profile();
+    let stacks: Vec<_> = profile
+        .collapsed()
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().0.to_owned())
+        .collect();
+    assert_eq!(
+        stacks,
+        vec![
+            "count/2",
+            "count/2;@core::add/3",
+            "count/2;count/2",
+            "count/2;count/2;@core::add/3",
+            "count/2;count/2;count/2",
+        ],
+    );
+    // Time spent in recursive calls is not counted twice.
+    let count = profile.entry("count/2").unwrap();
+    assert_eq!(count.calls(), 3);
+    assert!(count.self_time() <= count.total_time());
+}
+
+#[test]
+fn profile_infinite() {
+    let program = Lumber::from_source(
+        "
+:- use(@core).
+:- pub(even/1).
+
+even(N) :- nat(M), N =:= M * 2.
+",
+    )
+    .unwrap();
+
+    let question = Question::try_from("even(N)").unwrap();
+    let (answers, profiler) = program.ask_profiled(&question);
+    assert_eq!(answers.take(3).count(), 3);
+    let profile = profiler.profile();
+    assert_eq!(profile.entry("even/1").unwrap().calls(), 1);
+    assert_eq!(profile.entry("@core::mul/3").unwrap().calls(), 3);
+}
@@ -49,3 +49,19 @@ test! {
     "
     ?- "recordTest(test { a: 1, b: 2, c: 3 }, 4, test { a: 4, b: 2, c: 3 })";
 }
+
+test! {
+    record_runtime_keys => "
+    :- use(@core::record).
+    :- pub(field/3).
+
+    field(R, K, V) :- get(R, K, V).
+    "
+    ?- "field({ a: 1, b: 2 }, b, V)"
+        V = Value::from(2);
+    ?- "field({ a: 1, b: 2 }, K, V)"
+        K = Value::atom("a"), V = Value::from(1);
+        K = Value::atom("b"), V = Value::from(2);
+    ?- "@core::record::put({ a: 1 }, b, 2, R)"
+        R = record! { "a" => 1, "b" => 2 };
+}
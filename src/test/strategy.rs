@@ -0,0 +1,153 @@
+use super::*;
+
+fn program() -> Lumber<'static> {
+    Lumber::builder()
+        .build_from_str(
+            "
+            :- use(@core).
+            :- pub(loop/1).
+            :- pub(nat/1).
+            :- pub(either/1).
+            :- pub(pair/2).
+            :- pub(shallow/1).
+            :- pub(guarded/1).
+            :- pub(closest/1).
+            :- pub(gathered/1).
+            :- pub(kind/2).
+
+            loop(A) :- loop(A).
+            loop(found).
+
+            nat(z).
+            nat(s(N)) :- nat(N).
+
+            either(A) :- nat(A); equal(A, done).
+
+            pair(A, B) :- nat(A), nat(B).
+
+            shallow(a).
+            shallow(b) :- shallow(a).
+
+            far :- nat(s(s(s(s(z))))).
+            guarded(A) :- far ->> equal(A, near); equal(A, none).
+
+            path(deep) :- far.
+            path(shallow).
+            closest(A) :- path(A) -> true.
+
+            letter(a).
+            letter(b).
+            digit(1).
+            digit(2).
+            gathered(A) :- A =:= [X : letter(X); digit(X)].
+
+            kind(z, A) ::- equal(A, zero).
+            kind(_, other).
+            ",
+        )
+        .unwrap()
+}
+
+fn answers(program: &Lumber, question: &str, strategy: Strategy, count: usize) -> Vec<Value> {
+    let question = Question::try_from(question).unwrap();
+    program
+        .ask_with(&question, strategy)
+        .take(count)
+        .map(|mut answer| answer.remove("A").unwrap())
+        .collect()
+}
+
+fn nat(n: usize) -> Value {
+    (0..n).fold(Value::atom("z"), |value, _| {
+        Value::from(Struct::new("s", Some(value)))
+    })
+}
+
+#[test]
+fn strategy_iterative_deepening() {
+    let program = program();
+    // Each way of reaching the fact is a separate answer, but none are deeper than the bound.
+    assert_eq!(
+        answers(&program, "loop(A)", Strategy::IterativeDeepening(3), 10),
+        vec![Value::atom("found"); 3],
+    );
+    assert_eq!(
+        answers(&program, "nat(A)", Strategy::IterativeDeepening(3), 10),
+        vec![nat(0), nat(1), nat(2)],
+    );
+}
+
+#[test]
+fn strategy_iterative_deepening_unbounded() {
+    let program = program();
+    let unbounded = Strategy::IterativeDeepening(usize::MAX);
+    assert_eq!(
+        answers(&program, "loop(A)", unbounded, 1),
+        vec![Value::atom("found")],
+    );
+    assert_eq!(
+        answers(&program, "nat(A)", unbounded, 3),
+        vec![nat(0), nat(1), nat(2)],
+    );
+    // The search ends once there are no deeper answers.
+    assert_eq!(
+        answers(&program, "shallow(A)", unbounded, 3),
+        vec![Value::atom("a"), Value::atom("b")],
+    );
+}
+
+#[test]
+fn strategy_interleaving() {
+    let program = program();
+    assert!(
+        answers(&program, "either(A)", Strategy::Interleaving, 3).contains(&Value::atom("done"))
+    );
+
+    let question = Question::try_from("pair(A, B)").unwrap();
+    let found = program
+        .ask_with(&question, Strategy::Interleaving)
+        .take(10)
+        .any(|answer| answer.get("A") != Some(&nat(0)));
+    assert!(found, "all answers had the first value of A");
+}
+
+#[test]
+fn strategy_depth_first() {
+    let program = program();
+    assert_eq!(
+        answers(&program, "either(A)", Strategy::DepthFirst, 3),
+        vec![nat(0), nat(1), nat(2)],
+    );
+}
+
+#[test]
+fn strategy_committed_choices_are_depth_first() {
+    let program = program();
+    for strategy in [
+        Strategy::IterativeDeepening(3),
+        Strategy::IterativeDeepening(usize::MAX),
+        Strategy::Interleaving,
+    ] {
+        assert_eq!(
+            answers(&program, "guarded(A)", strategy, 3),
+            vec![Value::atom("near")],
+        );
+        assert_eq!(
+            answers(&program, "closest(A)", strategy, 3),
+            vec![Value::atom("deep")],
+        );
+        assert_eq!(
+            answers(&program, "gathered(A)", strategy, 3),
+            vec![list![
+                Value::atom("a"),
+                Value::atom("b"),
+                Value::from(1),
+                Value::from(2)
+            ]],
+        );
+        assert_eq!(
+            answers(&program, "kind(z, A)", strategy, 3),
+            vec![Value::atom("zero")],
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+fn existence_error(handle: &str, suggestions: Vec<&str>) -> Value {
+    let suggestions = Value::from(suggestions);
+    let contents = list![Value::atom("procedure"), handle, suggestions];
+    Value::from(Struct::new("existence_error", Some(contents)))
+}
+
+fn first_exception(program: &Lumber, question: &str) -> Option<Value> {
+    let question = Question::try_from(question).unwrap();
+    let exception = program
+        .try_ask(&question)
+        .find_map(|answer| answer.err())?
+        .exception()
+        .cloned();
+    exception
+}
+
+const SOURCE: &str = "
+:- use(@core).
+:- pub(greeting/1).
+:- pub(greetings/1).
+:- pub(call_unknown/0).
+
+greeting(hello).
+greeting(goodbye).
+greetings(X) :- greeting(X).
+secret(42).
+call_unknown :- call(greetng, _).
+";
+
+#[test]
+fn strict_question_typo() {
+    let program = Lumber::builder()
+        .strict(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    assert_eq!(
+        first_exception(&program, "greetng(X)"),
+        Some(existence_error(
+            "greetng/1",
+            vec!["greeting/1", "greetings/1"]
+        )),
+    );
+}
+
+#[test]
+fn strict_question_private() {
+    let program = Lumber::builder()
+        .strict(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    assert_eq!(
+        first_exception(&program, "secret(X)"),
+        Some(existence_error("secret/1", vec![])),
+    );
+}
+
+#[test]
+fn strict_question_checked_before_running() {
+    let program = Lumber::builder()
+        .strict(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    let question = Question::try_from("greeting(X), greetng(X)").unwrap();
+    let mut answers = program.try_ask(&question);
+    assert!(matches!(answers.next(), Some(Err(..))));
+    assert!(answers.next().is_none());
+}
+
+#[test]
+fn strict_runtime_goal() {
+    let program = Lumber::builder()
+        .strict(true)
+        .build_from_str(SOURCE)
+        .unwrap();
+    assert_eq!(
+        first_exception(&program, "call_unknown"),
+        Some(existence_error(
+            "greetng/1",
+            vec!["greeting/1", "greetings/1"]
+        )),
+    );
+}
+
+#[test]
+fn not_strict_fails_quietly() {
+    let program = Lumber::builder().build_from_str(SOURCE).unwrap();
+    assert_eq!(first_exception(&program, "greetng(X)"), None);
+    assert_eq!(first_exception(&program, "call_unknown"), None);
+    let question = Question::try_from("greetng(X)").unwrap();
+    assert!(program.ask(&question).next().is_none());
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::time::Duration;
 
 test! {
     lumber_tests => r#"
@@ -41,3 +42,133 @@ fn lumber_tests_compile_without_test() {
         :- test(hello(a, c)).
     "#).is_ok());
 }
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_named() {
+    let program = Lumber::from_source(r#"
+        hello(a, b).
+        :- test("says hello", hello(a, b)).
+        :- test(hello( a,
+            c )).
+    "#).unwrap();
+    let report = program.tests().run();
+    assert!(!report.is_success());
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 1);
+    let names: Vec<_> = report.results().iter().map(TestResult::name).collect();
+    assert_eq!(names, vec!["says hello", "hello( a, c )"]);
+    assert_eq!(report.results()[0].module(), "~");
+    assert_eq!(report.results()[0].line(), 3);
+    assert!(matches!(report.results()[1].outcome(), TestOutcome::Failed(..)));
+}
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_failure_message() {
+    match Lumber::builder().test(true).build_from_str(r#"
+        hello(a, b).
+        :- test("says goodbye", hello(a, c)).
+    "#) {
+        Ok(..) => panic!("Expected tests to fail"),
+        Err(error) => assert!(error.to_string().contains("says goodbye")),
+    }
+}
+
+#[test]
+fn lumber_tests_filter() {
+    let here = PathBuf::from(file!()).parent().unwrap().to_owned();
+    let program = Lumber::builder()
+        .build(
+            here.join("tests_modules"),
+            r#"
+:- mod(animals).
+quiet.
+:- test("nothing meows", quiet).
+"#,
+        )
+        .unwrap();
+    let report = program.tests().filter("meow").run();
+    assert_eq!(report.results().len(), 3);
+    assert_eq!(report.filtered_out(), 0);
+    let report = program.tests().module("animals").run();
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 1);
+    assert_eq!(report.filtered_out(), 1);
+    assert_eq!(report.results()[0].module(), "animals");
+    let report = program.tests().module("animals").filter("cats").run();
+    assert!(report.is_success());
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.filtered_out(), 2);
+    let report = program.tests().module("anim").run();
+    assert_eq!(report.results().len(), 0);
+}
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_timeout() {
+    let program = Lumber::from_source(r#"
+        digit(0). digit(1). digit(2). digit(3). digit(4).
+        digit(5). digit(6). digit(7). digit(8). digit(9).
+        done.
+        :- test("runs forever", digit(A), digit(B), digit(C), digit(D), digit(E), digit(F), digit(G), digit(H), digit(ten)).
+        :- test("finishes", done).
+        :- test("gives up", digit(A), digit(B), digit(C), digit(D), digit(E), digit(F), digit(G), digit(H), digit(ten) ->> digit(ten); done).
+    "#).unwrap();
+    let report = program.tests().timeout(Duration::from_millis(50)).run();
+    assert!(matches!(report.results()[0].outcome(), TestOutcome::TimedOut));
+    assert!(report.results()[1].is_passed());
+    // Abandoned branches fail, but that must not be mistaken for the test passing.
+    assert!(matches!(report.results()[2].outcome(), TestOutcome::TimedOut));
+}
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_exception() {
+    let program = Lumber::from_source(r#"
+        :- use(@core(throw/1)).
+        :- test("throws", throw(oops)).
+    "#).unwrap();
+    let report = program.tests().run();
+    match report.results()[0].outcome() {
+        TestOutcome::Exception(value) => assert_eq!(value.to_string(), "oops"),
+        outcome => panic!("Expected an exception, but the test turned out {:?}", outcome),
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_tap() {
+    let program = Lumber::from_source(r#"
+        hello(a, b).
+        :- test("says # hello", hello(a, b)).
+        :- test("says goodbye", hello(a, c)).
+    "#).unwrap();
+    let tap = program.tests().run().tap();
+    let lines: Vec<_> = tap.lines().collect();
+    assert_eq!(lines[0], "TAP version 13");
+    assert_eq!(lines[1], "1..2");
+    assert_eq!(lines[2], r"ok 1 - ~: says \# hello");
+    assert!(lines.contains(&"not ok 2 - ~: says goodbye"));
+    assert!(lines.contains(&"    No answer was found."));
+}
+
+#[test]
+#[rustfmt::skip]
+fn lumber_tests_junit() {
+    let program = Lumber::from_source(r#"
+        :- use(@core(throw/1)).
+        hello(a, b).
+        :- test("says <hello>", hello(a, b)).
+        :- test("says goodbye", hello(a, c)).
+        :- test("throws", throw(oops)).
+    "#).unwrap();
+    let junit = program.tests().run().junit();
+    assert!(junit.starts_with("<?xml"));
+    assert!(junit.contains(r#"<testsuites name="lumber" tests="3" failures="1" errors="1""#));
+    assert!(junit.contains(r#"<testsuite name="~" tests="3" failures="1" errors="1""#));
+    assert!(junit.contains(r#"<testcase name="says &lt;hello&gt;" classname="~" line="4""#));
+    assert!(junit.contains(r#"<failure message="No answer was found.">"#));
+    assert!(junit.contains(r#"<error message="Uncaught exception: oops">"#));
+    assert!(junit.trim_end().ends_with("</testsuites>"));
+}
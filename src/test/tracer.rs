@@ -0,0 +1,147 @@
+use super::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const SRC: &str = "
+:- use(@core).
+:- pub(access/2).
+:- pub(count/1).
+:- pub(evens/1).
+
+role(bob, viewer).
+role(bob, editor).
+
+grants(editor, write).
+
+access(User, Permission) :- role(User, Role), grants(Role, Permission).
+
+count(N) :- add(1, 2, N).
+
+evens(N) :- nat(M), N =:= M * 2.
+";
+
+#[derive(Default)]
+struct Events(RefCell<Vec<Event>>);
+
+impl Tracer for Events {
+    fn trace(&self, event: &Event) {
+        self.0.borrow_mut().push(event.clone());
+    }
+}
+
+fn trace(program: &Lumber, question: &str) -> String {
+    let question = Question::try_from(question).unwrap();
+    let tracer = Rc::new(TextTracer::new(vec![]));
+    program.ask_traced(&question, tracer.clone()).for_each(drop);
+    let tracer = Rc::try_unwrap(tracer).ok().unwrap();
+    String::from_utf8(tracer.into_inner()).unwrap()
+}
+
+#[test]
+fn tracer_text() {
+    let program = Lumber::from_source(SRC).unwrap();
+    assert_eq!(
+        trace(&program, "access(bob, P)"),
+        "call access(bob, _)
+  call role(bob, _)
+  exit role(bob, viewer) by clause 0
+  call grants(viewer, _)
+  fail grants(viewer, _)
+  redo role(bob, _) by clause 0
+  exit role(bob, editor) by clause 1
+  call grants(editor, _)
+  exit grants(editor, write) by clause 0
+exit access(bob, write) by clause 0
+redo access(bob, _) by clause 0
+  redo grants(editor, _) by clause 0
+  fail grants(editor, _)
+  redo role(bob, _) by clause 1
+  fail role(bob, _)
+fail access(bob, _)
+",
+    );
+}
+
+#[test]
+fn tracer_events() {
+    let program = Lumber::from_source(SRC).unwrap();
+    let question = Question::try_from("count(N)").unwrap();
+    let events = Rc::new(Events::default());
+    let mut answers = program.ask_traced(&question, events.clone());
+    assert_eq!(answers.next().unwrap().get("N"), Some(&Value::integer(3)));
+
+    let events = events.0.borrow();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].port(), Port::Call);
+    assert_eq!(events[0].handle(), "count/1");
+    assert_eq!(events[0].depth(), 1);
+    assert_eq!(events[1].port(), Port::Call);
+    assert_eq!(events[1].handle(), "@core::add/3");
+    assert_eq!(events[1].depth(), 2);
+    assert_eq!(events[2].port(), Port::Exit);
+    assert_eq!(events[2].clause(), None);
+    assert_eq!(
+        events[2].arguments(),
+        &[
+            Some(Value::integer(1)),
+            Some(Value::integer(2)),
+            Some(Value::integer(3)),
+        ],
+    );
+    assert_eq!(events[3].port(), Port::Exit);
+    assert_eq!(events[3].clause(), Some(0));
+    assert_eq!(events[3].arguments(), &[Some(Value::integer(3))]);
+    assert_eq!(events[0].bindings(), &[("N".to_owned(), None)]);
+    assert_eq!(
+        events[3].bindings(),
+        &[("N".to_owned(), Some(Value::integer(3)))]
+    );
+}
+
+/// Abandons the search at the first call to multiplication.
+#[derive(Default)]
+struct Abandon(RefCell<usize>);
+
+impl Tracer for Abandon {
+    fn trace(&self, event: &Event) {
+        if event.port() == Port::Call && event.handle() == "@core::mul/3" {
+            *self.0.borrow_mut() += 1;
+            event.abandon();
+        }
+    }
+}
+
+#[test]
+fn tracer_abandons_search() {
+    let program = Lumber::from_source(SRC).unwrap();
+    let question = Question::try_from("evens(N)").unwrap();
+    let tracer = Rc::new(Abandon::default());
+    assert_eq!(program.ask_traced(&question, tracer.clone()).count(), 0);
+    assert_eq!(*tracer.0.borrow(), 1);
+}
+
+#[test]
+fn tracer_from_builder() {
+    let events = Rc::new(Events::default());
+    let program = Lumber::builder()
+        .tracer(events.clone())
+        .build_from_str(SRC)
+        .unwrap();
+    let question = Question::try_from("access(alice, P)").unwrap();
+    assert!(program.ask(&question).next().is_none());
+    let ports: Vec<_> = events
+        .0
+        .borrow()
+        .iter()
+        .map(|event| (event.port(), event.depth()))
+        .collect();
+    assert_eq!(
+        ports,
+        vec![
+            (Port::Call, 1),
+            (Port::Call, 2),
+            (Port::Fail, 2),
+            (Port::Fail, 1),
+        ],
+    );
+}
@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn type_test_opaque() {
+    let program = Lumber::builder()
+        .bind("handle/1", |_| {
+            Box::new(std::iter::once(Ok(vec![Some(Value::any(42u8))])))
+        })
+        .build_from_str(
+            "
+            :- use(@core).
+            :- nat(handle/1).
+            :- pub(opaque_handle/0).
+            :- pub(handle_is/1).
+
+            opaque_handle :- handle(H), opaque(H).
+            handle_is(T) :- handle(H), integer(H), equal(T, integer).
+            handle_is(T) :- handle(H), atom(H), equal(T, atom).
+            ",
+        )
+        .unwrap();
+    let question = Question::try_from("opaque_handle").unwrap();
+    assert_eq!(program.ask(&question).count(), 1);
+    let question = Question::try_from("handle_is(T)").unwrap();
+    assert_eq!(program.ask(&question).count(), 0);
+}